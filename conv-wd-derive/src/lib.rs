@@ -0,0 +1,163 @@
+//! Derive macro for `conv_wd::DirLayout`.
+//! See the documentation of the trait in `conv-wd` for the supported attributes.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Ident, LitStr, parse_macro_input};
+
+/// Derives `conv_wd::DirLayout` for a struct with named fields.
+/// Every field needs a `#[layout(...)]` attribute with its format
+/// (`json`, `toml`, `text`, `bytes`, or `dir`) and optionally its file name
+/// (`file = "..."`).
+#[proc_macro_derive(DirLayout, attributes(layout))]
+pub fn derive_dir_layout(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Format of a field in the directory layout.
+enum Format {
+    Json,
+    Toml,
+    Text,
+    Bytes,
+    Dir,
+}
+
+/// A field of the struct and where it is stored in the directory.
+struct LayoutField {
+    ident: Ident,
+    format: Format,
+    file: String,
+}
+
+/// Generates the `DirLayout` implementation for the given struct.
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "DirLayout can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "DirLayout can only be derived for structs with named fields",
+        ));
+    };
+    let fields = fields
+        .named
+        .iter()
+        .map(parse_field)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let saves = fields.iter().map(|field| {
+        let LayoutField { ident, format, file } = field;
+        match format {
+            Format::Json => quote! { directory.write_json(#file, &self.#ident); },
+            Format::Toml => quote! { directory.write_toml(#file, &self.#ident); },
+            Format::Text => quote! { directory.write_string(#file, self.#ident.clone()); },
+            Format::Bytes => quote! { directory.write_bytes(#file, &self.#ident); },
+            Format::Dir => quote! {
+                ::conv_wd::DirLayout::save_to(&self.#ident, &::conv_wd::layout_subdir(directory, #file));
+            },
+        }
+    });
+    let loads = fields.iter().map(|field| {
+        let LayoutField {
+            ident,
+            format,
+            file,
+        } = field;
+        let value = match format {
+            Format::Json => quote! { directory.read_json(#file) },
+            Format::Toml => quote! { directory.read_toml(#file) },
+            Format::Text => quote! { directory.read_string(#file) },
+            Format::Bytes => quote! { directory.read_bytes(#file) },
+            Format::Dir => quote! {
+                ::conv_wd::DirLayout::load_from(&::conv_wd::layout_subdir(directory, #file))
+            },
+        };
+        quote! { #ident: #value, }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::conv_wd::DirLayout for #name #type_generics #where_clause {
+            fn save_to(&self, directory: &::conv_wd::Directory) {
+                #(#saves)*
+            }
+
+            fn load_from(directory: &::conv_wd::Directory) -> Self {
+                Self {
+                    #(#loads)*
+                }
+            }
+        }
+    })
+}
+
+/// Parses the `#[layout(...)]` attribute of a field.
+fn parse_field(field: &syn::Field) -> syn::Result<LayoutField> {
+    let ident = field.ident.clone().expect("named fields have identifiers");
+    let mut format = None;
+    let mut file = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("layout"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let parsed = if meta.path.is_ident("json") {
+                Format::Json
+            } else if meta.path.is_ident("toml") {
+                Format::Toml
+            } else if meta.path.is_ident("text") {
+                Format::Text
+            } else if meta.path.is_ident("bytes") {
+                Format::Bytes
+            } else if meta.path.is_ident("dir") {
+                Format::Dir
+            } else if meta.path.is_ident("file") {
+                let value: LitStr = meta.value()?.parse()?;
+                if value.value().contains(['/', '\\']) {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "file names must not contain path separators, use a nested layout instead",
+                    ));
+                }
+                file = Some(value.value());
+                return Ok(());
+            } else {
+                return Err(meta.error("unsupported layout attribute"));
+            };
+            if format.replace(parsed).is_some() {
+                return Err(meta.error("a field can only have one format"));
+            }
+            Ok(())
+        })?;
+    }
+    let Some(format) = format else {
+        return Err(syn::Error::new_spanned(
+            field,
+            "missing format, e.g. #[layout(json)]",
+        ));
+    };
+    let file = file.unwrap_or_else(|| {
+        let name = ident.to_string();
+        match format {
+            Format::Text => format!("{name}.txt"),
+            Format::Bytes => format!("{name}.bin"),
+            Format::Json | Format::Toml | Format::Dir => name,
+        }
+    });
+    Ok(LayoutField {
+        ident,
+        format,
+        file,
+    })
+}
@@ -0,0 +1,71 @@
+use std::sync::OnceLock;
+
+use crate::RetryPolicy;
+
+/// Process-wide defaults for new `Directory` instances, e.g. set once in a test harness
+/// instead of on every constructor call. The defaults apply to all constructors and can
+/// still be changed per instance with the chained modifiers.
+/// `Config::default()` matches the behavior without a configuration.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    /// Keep directories when they are dropped, as with `Directory::keep`.
+    pub keep: bool,
+    /// Keep directories that are dropped while panicking, as with `Directory::keep_on_panic`.
+    pub keep_on_panic: bool,
+    /// Add a `.gitignore` file to new directories, as with `Directory::with_gitignore`.
+    pub gitignore: bool,
+    /// Permission bits of new directories on Unix, e.g. `0o700`
+    /// (ignored on other platforms). Without a mode, the umask applies.
+    pub mode: Option<u32>,
+    /// Retry policy for removals, as with `Directory::with_remove_retry`.
+    pub remove_retry: RetryPolicy,
+    /// Maximum size of a single written file, as with `Directory::with_max_file_size`.
+    pub max_file_size: Option<u64>,
+    /// Maximum depth of recursive operations, as with `Directory::with_max_depth`.
+    pub max_depth: Option<usize>,
+}
+
+/// The process-wide configuration.
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Returns the process-wide configuration.
+/// If none has been set with `set_config`, the default configuration is used from then on.
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+/// Sets the process-wide configuration.
+/// The configuration can only be set once, before any directory is created
+/// (or `config` is called), so all directories of a process share the same defaults.
+/// Returns the given configuration as error if the configuration is already in use.
+///
+/// # Arguments
+/// * `config` - The configuration to use for all new directories.
+///
+/// # Example
+/// ```rust
+/// use conv_wd::{Config, Directory};
+///
+/// // E.g. in a test harness, before the first directory is created:
+/// conv_wd::set_config(Config {
+///     keep_on_panic: true,
+///     ..Config::default()
+/// })
+/// .unwrap();
+///
+/// assert!(conv_wd::config().keep_on_panic);
+/// ```
+pub fn set_config(config: Config) -> std::result::Result<(), Config> {
+    CONFIG.set(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config() {
+        assert_eq!(super::config(), &Config::default());
+        assert_eq!(set_config(Config::default()), Err(Config::default()));
+    }
+}
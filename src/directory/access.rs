@@ -13,6 +13,75 @@ impl Directory {
     pub fn path_buf(&self) -> PathBuf {
         self.path.clone()
     }
+
+    /// Consumes self and returns the path of the directory as a `PathBuf`.
+    /// Ownership of the directory is relinquished, i.e. it is made persistent
+    /// and is not removed from the file system.
+    pub fn into_path_buf(self) -> PathBuf {
+        self.keep().path_buf()
+    }
+
+    /// Returns the path of the directory relative to the current working directory
+    /// for short log lines, e.g. `target/out/run_0003` instead of a long absolute path.
+    /// Returns `.` if the directory is the current working directory, and the full path
+    /// if it is not below the current working directory or that cannot be determined.
+    pub fn display_relative(&self) -> String {
+        match std::env::current_dir() {
+            Ok(cwd) => self.display_relative_to(cwd),
+            Err(_) => self.path.display().to_string(),
+        }
+    }
+
+    /// Returns the path of the directory relative to the given base for short log lines,
+    /// like `display_relative`. Symlinks in both paths are resolved if needed,
+    /// so e.g. a relative path of the directory is also matched.
+    ///
+    /// # Arguments
+    /// * `base` - The path to which the displayed path is relative.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let base = Directory::cargo_target_subdir("display_relative_to_example");
+    /// let run = Directory::create(base.path().join("out/run_0003"));
+    ///
+    /// let expected = std::path::Path::new("out/run_0003").display().to_string();
+    /// assert_eq!(run.display_relative_to(base.path()), expected);
+    /// ```
+    pub fn display_relative_to<P: AsRef<Path>>(&self, base: P) -> String {
+        let base = base.as_ref();
+        let relative = self
+            .path
+            .strip_prefix(base)
+            .ok()
+            .map(Path::to_path_buf)
+            .or_else(|| {
+                let path = self.path.canonicalize().ok()?;
+                let base = base.canonicalize().ok()?;
+                path.strip_prefix(base).ok().map(Path::to_path_buf)
+            });
+        match relative {
+            Some(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+            Some(relative) => relative.display().to_string(),
+            None => self.path.display().to_string(),
+        }
+    }
+}
+
+impl AsRef<Path> for Directory {
+    /// Returns the path of the directory, so a `Directory` can be passed
+    /// wherever a path is expected.
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl From<Directory> for PathBuf {
+    /// Converts a `Directory` into its path (see `Directory::into_path_buf`).
+    fn from(directory: Directory) -> Self {
+        directory.into_path_buf()
+    }
 }
 
 #[cfg(test)]
@@ -40,4 +109,68 @@ mod tests {
 
         assert_eq!(directory.path_buf(), dir_path);
     }
+
+    #[test]
+    fn into_path_buf() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+
+        let path_buf = Directory::create(&dir_path).into_path_buf();
+
+        assert_eq!(path_buf, dir_path);
+        assert!(dir_path.exists());
+        assert!(dir_path.is_dir());
+    }
+
+    #[test]
+    fn display_relative() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+
+        let directory = Directory::create(&dir_path);
+
+        // The temporary directory is not below the working directory of the tests.
+        assert_eq!(directory.display_relative(), dir_path.display().to_string());
+    }
+
+    #[test]
+    fn display_relative_to() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("out/run_0003"));
+        let other = tempdir().unwrap();
+
+        assert_eq!(
+            directory.display_relative_to(temp_dir.path()),
+            Path::new("out/run_0003").display().to_string()
+        );
+        assert_eq!(
+            directory.display_relative_to(temp_dir.path().join("out/run_0003")),
+            "."
+        );
+        assert_eq!(
+            directory.display_relative_to(other.path()),
+            directory.path().display().to_string()
+        );
+    }
+
+    #[test]
+    fn as_ref_path() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+
+        let directory = Directory::create(&dir_path);
+
+        assert!(std::fs::metadata(&directory).unwrap().is_dir());
+    }
+
+    #[test]
+    fn from_directory_for_path_buf() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+
+        let path_buf = PathBuf::from(Directory::create(&dir_path));
+
+        assert_eq!(path_buf, dir_path);
+        assert!(dir_path.exists());
+    }
 }
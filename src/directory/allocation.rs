@@ -0,0 +1,92 @@
+use super::*;
+
+use fs4::FileExt;
+use std::path::Path;
+
+use crate::util::assert_relative_path;
+
+/// Methods for quickly setting up large files, e.g. for benchmarks.
+impl Directory {
+    /// Creates a sparse file of the given length at the given path within the directory.
+    /// The file reads as zeros, but on file systems with sparse file support
+    /// (most Unix file systems), no disk space is allocated for it.
+    /// An existing file is truncated first.
+    /// Panics if the path is absolute or if the file cannot be created.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    /// * `len` - The length of the file in bytes.
+    pub fn create_sparse<P: AsRef<Path>>(&self, relative_path: P, len: u64) {
+        assert_relative_path(relative_path.as_ref());
+        let file_path = self.path.join(relative_path.as_ref());
+        std::fs::File::create(&file_path)
+            .and_then(|file| file.set_len(len))
+            .unwrap_or_else(|e| {
+                panic!(
+                    "Failed to create sparse file at {}: {e}",
+                    file_path.display()
+                )
+            });
+    }
+
+    /// Preallocates disk space for the file at the given path within the directory,
+    /// creating the file if it does not exist. Existing content is preserved.
+    /// Uses `fallocate` (Linux), `F_PREALLOCATE` (macOS), or `SetFileInformationByHandle`
+    /// (Windows), so no zeros need to be written. Subsequent writes within the
+    /// preallocated length cannot fail because of a lack of disk space.
+    /// Panics if the path is absolute or if the space cannot be allocated.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    /// * `len` - The number of bytes to allocate.
+    pub fn preallocate<P: AsRef<Path>>(&self, relative_path: P, len: u64) {
+        assert_relative_path(relative_path.as_ref());
+        let file_path = self.path.join(relative_path.as_ref());
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&file_path)
+            .and_then(|file| file.allocate(len))
+            .unwrap_or_else(|e| {
+                panic!("Failed to preallocate file at {}: {e}", file_path.display())
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    const LEN: u64 = 16 * 1024 * 1024;
+
+    #[test]
+    fn create_sparse() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("sparse.bin", "existing content");
+
+        directory.create_sparse("sparse.bin", LEN);
+
+        let file_path = directory.path().join("sparse.bin");
+        assert_eq!(std::fs::metadata(&file_path).unwrap().len(), LEN);
+        let content = std::fs::read(&file_path).unwrap();
+        assert!(content.iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn preallocate() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("preallocated.bin", "existing content");
+
+        directory.preallocate("preallocated.bin", LEN);
+
+        let file_path = directory.path().join("preallocated.bin");
+        assert_eq!(std::fs::metadata(&file_path).unwrap().len(), LEN);
+        let content = std::fs::read(&file_path).unwrap();
+        assert!(content.starts_with(b"existing content"));
+    }
+}
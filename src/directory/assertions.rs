@@ -0,0 +1,408 @@
+use super::*;
+
+use std::path::Path;
+
+/// Environment variable that, if set to `1`, `true`, or `yes`,
+/// causes golden files to be rewritten instead of compared.
+const UPDATE_GOLDEN_ENV_VAR: &str = "UPDATE_GOLDEN";
+
+/// Assertion helpers for tests.
+/// On failure, these methods panic with a message that includes a listing
+/// of the directory tree, so the actual state can be inspected in the test output.
+impl Directory {
+    /// Asserts that a file exists at the given path within the directory.
+    #[track_caller]
+    pub fn assert_file_exists<P: AsRef<Path>>(&self, relative_path: P) {
+        let file_path = self.path.join(relative_path.as_ref());
+        if !file_path.is_file() {
+            self.fail(format_args!("Expected a file at {}", file_path.display()));
+        }
+    }
+
+    /// Asserts that a directory exists at the given path within the directory.
+    #[track_caller]
+    pub fn assert_dir_exists<P: AsRef<Path>>(&self, relative_path: P) {
+        let dir_path = self.path.join(relative_path.as_ref());
+        if !dir_path.is_dir() {
+            self.fail(format_args!(
+                "Expected a directory at {}",
+                dir_path.display()
+            ));
+        }
+    }
+
+    /// Asserts that the file at the given path within the directory has exactly the expected content.
+    #[track_caller]
+    pub fn assert_file_eq<P: AsRef<Path>, C: AsRef<[u8]>>(&self, relative_path: P, expected: C) {
+        self.assert_file_exists(relative_path.as_ref());
+        let file_path = self.path.join(relative_path.as_ref());
+        let actual = self.read_for_assertion(&file_path);
+        self.assert_content_eq(&file_path, &actual, expected.as_ref());
+    }
+
+    /// Asserts that the file at the given path within the directory contains the given text.
+    #[track_caller]
+    pub fn assert_file_contains<P: AsRef<Path>>(&self, relative_path: P, needle: &str) {
+        self.assert_file_exists(relative_path.as_ref());
+        let file_path = self.path.join(relative_path.as_ref());
+        let actual = self.read_for_assertion(&file_path);
+        let actual = String::from_utf8_lossy(&actual);
+        if !actual.contains(needle) {
+            self.fail(format_args!(
+                "Expected {} to contain {needle:?}\n  actual: {actual:?}",
+                file_path.display()
+            ));
+        }
+    }
+
+    /// Asserts that the file at the given path within the directory matches the golden file
+    /// at `golden_path` (snapshot testing).
+    /// If the `UPDATE_GOLDEN` environment variable is set to `1`, `true`, or `yes`,
+    /// the golden file is (re)written with the actual content instead of being compared.
+    /// Panics if the contents differ or the golden file does not exist
+    /// (unless in update mode), or if the golden file cannot be written.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the generated file within the directory.
+    /// * `golden_path` - The path of the golden file with the expected content.
+    #[track_caller]
+    pub fn assert_matches_golden<P: AsRef<Path>, G: AsRef<Path>>(
+        &self,
+        relative_path: P,
+        golden_path: G,
+    ) {
+        let update =
+            std::env::var(UPDATE_GOLDEN_ENV_VAR).is_ok_and(|value| policy::is_truthy(&value));
+        self.check_golden(relative_path.as_ref(), golden_path.as_ref(), update);
+    }
+
+    /// Asserts that the tree of the directory equals the tree at `expected`, e.g. an expected
+    /// output fixture. Entries are compared by existence, type, and content like in `diff_dir`.
+    /// On failure, the message lists the unexpected (`+`), missing (`-`), and changed (`~`)
+    /// entries, followed by a line diff of each changed text file.
+    ///
+    /// # Arguments
+    /// * `expected` - The root of the expected tree, e.g. another `Directory`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let expected = Directory::cargo_target_subdir("assert_dir_eq_example/expected");
+    /// expected.write_string("output.txt", "done");
+    /// let actual = Directory::cargo_target_subdir("assert_dir_eq_example/actual");
+    /// actual.write_string("output.txt", "done");
+    ///
+    /// actual.assert_dir_eq(&expected);
+    /// ```
+    #[track_caller]
+    pub fn assert_dir_eq<P: AsRef<Path>>(&self, expected: P) {
+        self.check_dir_eq(expected.as_ref(), false);
+    }
+
+    /// Asserts that the tree of the directory equals the tree at `expected` like
+    /// `assert_dir_eq`, and that the entries have the same permissions (the mode bits
+    /// on Unix, and the read-only attribute on other platforms). Symlinks are compared
+    /// by their targets only.
+    ///
+    /// # Arguments
+    /// * `expected` - The root of the expected tree, e.g. another `Directory`.
+    #[track_caller]
+    pub fn assert_dir_eq_with_permissions<P: AsRef<Path>>(&self, expected: P) {
+        self.check_dir_eq(expected.as_ref(), true);
+    }
+
+    /// Compares the tree of the directory against the tree at `expected`,
+    /// optionally including permissions, and fails with a report of all mismatches.
+    #[track_caller]
+    fn check_dir_eq(&self, expected: &Path, permissions: bool) {
+        let mut diff = self.diff_dir(expected);
+        let mut details = String::new();
+        for changed in &diff.changed {
+            let (path, expected_path) = (self.path.join(changed), expected.join(changed));
+            if path.is_file() && expected_path.is_file() {
+                let file_diff = self.diff_file(changed, &expected_path);
+                details.push_str(&format!("\n{}:\n{file_diff}", changed.display()));
+            }
+        }
+        if permissions {
+            let entries = self.walk_tree(&self.path).unwrap_or_else(|e| {
+                panic!(
+                    "Failed to read directory tree at {}: {e}",
+                    self.path.display()
+                )
+            });
+            for entry in entries {
+                let (Ok(metadata), Ok(expected_metadata)) = (
+                    std::fs::symlink_metadata(self.path.join(&entry)),
+                    std::fs::symlink_metadata(expected.join(&entry)),
+                ) else {
+                    continue;
+                };
+                let (actual, expected) = (
+                    permissions_repr(&metadata),
+                    permissions_repr(&expected_metadata),
+                );
+                if metadata.is_symlink() || actual == expected {
+                    continue;
+                }
+                details.push_str(&format!(
+                    "\n{}: expected permissions {expected}, actual {actual}\n",
+                    entry.display()
+                ));
+                if !diff.changed.contains(&entry) {
+                    diff.changed.push(entry);
+                }
+            }
+            diff.changed.sort();
+        }
+        if !diff.is_equal() {
+            self.fail(format_args!(
+                "Directory {} differs from {}\n{diff}{details}",
+                self.path.display(),
+                expected.display()
+            ));
+        }
+    }
+
+    /// Compares the file at `relative_path` against the golden file,
+    /// or rewrites the golden file if `update` is set.
+    #[track_caller]
+    fn check_golden(&self, relative_path: &Path, golden_path: &Path, update: bool) {
+        self.assert_file_exists(relative_path);
+        let file_path = self.path.join(relative_path);
+        let actual = self.read_for_assertion(&file_path);
+
+        if update {
+            if let Some(parent) = golden_path.parent() {
+                std::fs::create_dir_all(parent).unwrap_or_else(|e| {
+                    panic!("Failed to create directory at {}: {e}", parent.display())
+                });
+            }
+            std::fs::write(golden_path, &actual).unwrap_or_else(|e| {
+                panic!(
+                    "Failed to write golden file at {}: {e}",
+                    golden_path.display()
+                )
+            });
+            return;
+        }
+
+        if !golden_path.is_file() {
+            self.fail(format_args!(
+                "Golden file {} does not exist (set {UPDATE_GOLDEN_ENV_VAR}=1 to create it)",
+                golden_path.display()
+            ));
+        }
+        let expected = self.read_for_assertion(golden_path);
+        self.assert_content_eq(&file_path, &actual, &expected);
+    }
+
+    /// Panics with a readable message if `actual` differs from `expected`.
+    #[track_caller]
+    fn assert_content_eq(&self, file_path: &Path, actual: &[u8], expected: &[u8]) {
+        if actual != expected {
+            match (std::str::from_utf8(actual), std::str::from_utf8(expected)) {
+                (Ok(actual), Ok(expected)) => self.fail(format_args!(
+                    "Unexpected content in {}\n  expected: {expected:?}\n    actual: {actual:?}",
+                    file_path.display()
+                )),
+                _ => self.fail(format_args!(
+                    "Unexpected binary content in {} (expected {} bytes, got {} bytes)",
+                    file_path.display(),
+                    expected.len(),
+                    actual.len()
+                )),
+            }
+        }
+    }
+
+    /// Reads the content of a file for an assertion.
+    /// Panics with the tree listing if the file cannot be read.
+    #[track_caller]
+    fn read_for_assertion(&self, file_path: &Path) -> Vec<u8> {
+        std::fs::read(file_path).unwrap_or_else(|e| {
+            self.fail(format_args!(
+                "Failed to read file at {}: {e}",
+                file_path.display()
+            ))
+        })
+    }
+
+    /// Panics with the given message followed by a listing of the directory tree.
+    #[track_caller]
+    fn fail(&self, message: std::fmt::Arguments) -> ! {
+        panic!("{message}\nDirectory tree:\n{}", self.tree_listing());
+    }
+}
+
+/// Returns the permissions of an entry for failure messages.
+#[cfg(unix)]
+fn permissions_repr(metadata: &std::fs::Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    format!("{:o}", metadata.permissions().mode() & 0o7777)
+}
+
+/// Returns the permissions of an entry for failure messages.
+#[cfg(not(unix))]
+fn permissions_repr(metadata: &std::fs::Metadata) -> String {
+    match metadata.permissions().readonly() {
+        true => "read-only".to_string(),
+        false => "writable".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    fn setup() -> (tempfile::TempDir, Directory) {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("file.txt", "Hello, world!");
+        std::fs::create_dir(directory.path().join("subdir")).unwrap();
+        (temp_dir, directory)
+    }
+
+    #[test]
+    fn assert_file_exists() {
+        let (_temp_dir, directory) = setup();
+
+        directory.assert_file_exists("file.txt");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected a file at")]
+    fn assert_file_exists_fails_for_directory() {
+        let (_temp_dir, directory) = setup();
+
+        directory.assert_file_exists("subdir");
+    }
+
+    #[test]
+    fn assert_dir_exists() {
+        let (_temp_dir, directory) = setup();
+
+        directory.assert_dir_exists("subdir");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected a directory at")]
+    fn assert_dir_exists_fails_for_missing_directory() {
+        let (_temp_dir, directory) = setup();
+
+        directory.assert_dir_exists("missing");
+    }
+
+    #[test]
+    fn assert_file_eq() {
+        let (_temp_dir, directory) = setup();
+
+        directory.assert_file_eq("file.txt", "Hello, world!");
+    }
+
+    #[test]
+    #[should_panic(expected = "Unexpected content")]
+    fn assert_file_eq_fails_for_different_content() {
+        let (_temp_dir, directory) = setup();
+
+        directory.assert_file_eq("file.txt", "Goodbye, world!");
+    }
+
+    #[test]
+    fn assert_file_contains() {
+        let (_temp_dir, directory) = setup();
+
+        directory.assert_file_contains("file.txt", "world");
+    }
+
+    #[test]
+    #[should_panic(expected = "file.txt\n")]
+    fn assert_file_contains_fails_with_tree_listing() {
+        let (_temp_dir, directory) = setup();
+
+        directory.assert_file_contains("file.txt", "moon");
+    }
+
+    #[test]
+    fn assert_matches_golden() {
+        let (temp_dir, directory) = setup();
+        let golden_path = temp_dir.path().join("golden/file.txt");
+        std::fs::create_dir_all(golden_path.parent().unwrap()).unwrap();
+        std::fs::write(&golden_path, "Hello, world!").unwrap();
+
+        directory.assert_matches_golden("file.txt", &golden_path);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not exist")]
+    fn assert_matches_golden_fails_for_missing_golden_file() {
+        let (temp_dir, directory) = setup();
+
+        directory.check_golden(
+            Path::new("file.txt"),
+            &temp_dir.path().join("golden/file.txt"),
+            false,
+        );
+    }
+
+    #[test]
+    fn update_golden() {
+        let (temp_dir, directory) = setup();
+        let golden_path = temp_dir.path().join("golden/file.txt");
+
+        directory.check_golden(Path::new("file.txt"), &golden_path, true);
+
+        assert_eq!(
+            std::fs::read_to_string(&golden_path).unwrap(),
+            "Hello, world!"
+        );
+        directory.check_golden(Path::new("file.txt"), &golden_path, false);
+    }
+
+    #[test]
+    fn assert_dir_eq() {
+        let (temp_dir, directory) = setup();
+        let expected = temp_dir.path().join("expected");
+        directory.sync_to(&expected, SyncPolicy::default());
+
+        directory.assert_dir_eq(&expected);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "+ extra.txt\n- missing.txt\n~ file.txt\n\nfile.txt:\n-    1: Hello, moon!\n+    1: Hello, world!"
+    )]
+    fn assert_dir_eq_fails_with_report() {
+        let (temp_dir, directory) = setup();
+        let expected = Directory::create(temp_dir.path().join("expected"));
+        std::fs::create_dir(expected.path().join("subdir")).unwrap();
+        expected.write_string("file.txt", "Hello, moon!");
+        expected.write_string("missing.txt", "");
+        directory.write_string("extra.txt", "");
+
+        directory.assert_dir_eq(&expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[should_panic(expected = "file.txt: expected permissions 755, actual 644")]
+    fn assert_dir_eq_with_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (temp_dir, directory) = setup();
+        let expected = temp_dir.path().join("expected");
+        directory.sync_to(&expected, SyncPolicy::default());
+        directory.assert_dir_eq_with_permissions(&expected);
+        let set_mode = |path: PathBuf, mode| {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap()
+        };
+        set_mode(directory.path().join("file.txt"), 0o644);
+        set_mode(expected.join("file.txt"), 0o755);
+
+        directory.assert_dir_eq_with_permissions(&expected);
+    }
+}
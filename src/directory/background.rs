@@ -0,0 +1,123 @@
+use super::*;
+
+use crate::{Error, Result};
+
+/// Methods for removing directories without blocking an async executor.
+///
+/// Removing a large tree in `Drop` blocks the calling thread, which stalls all tasks
+/// scheduled on the same worker of an async runtime. These methods move the removal
+/// to the blocking thread pool of the tokio runtime instead.
+impl Directory {
+    /// Removes the directory from the file system on the blocking thread pool of the
+    /// current tokio runtime, regardless of the drop policy.
+    /// Failed attempts are retried according to the directory's retry policy.
+    /// Panics if not called from within a tokio runtime.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let dir = Directory::cargo_target_subdir("remove_async_example");
+    /// let path = dir.path_buf();
+    /// dir.remove_async().await.unwrap();
+    /// assert!(!path.exists());
+    /// # });
+    /// ```
+    pub async fn remove_async(mut self) -> Result<()> {
+        #[cfg(feature = "ctrlc")]
+        crate::signal::unregister(&self.path);
+        // Dropping the instance closes any open handle without removing the directory.
+        self.keep_on_drop = true;
+        let path = self.path.clone();
+        let policy = self.remove_retry;
+        drop(self);
+
+        let task_path = path.clone();
+        match tokio::task::spawn_blocking(move || util::remove_dir_with_retry(&task_path, &policy))
+            .await
+        {
+            Ok(result) => result.map_err(|e| Error::io(path, e)),
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(Error::io(path, std::io::Error::other(e))),
+        }
+    }
+
+    /// Creates a new Directory instance from self that, when dropped as a temporary
+    /// directory from within a tokio runtime, is removed on the runtime's blocking
+    /// thread pool instead of blocking the dropping thread.
+    /// Outside of a runtime, the directory is removed synchronously as usual.
+    ///
+    /// The removal completes asynchronously, so the directory may still exist for
+    /// a short time after the drop, and removal errors are printed to stderr
+    /// instead of causing a panic. Use `remove_async` to wait for the removal.
+    pub fn with_background_drop(mut self) -> Self {
+        self.background_drop = true;
+        self
+    }
+
+    /// Hands the removal of the directory off to the blocking thread pool of the
+    /// current tokio runtime if background drop is enabled.
+    /// Returns whether the removal was handed off.
+    pub(super) fn remove_in_background(&self) -> bool {
+        if !self.background_drop {
+            return false;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return false;
+        };
+        let path = self.path.clone();
+        let policy = self.remove_retry;
+        runtime.spawn_blocking(move || {
+            if let Err(e) = util::remove_dir_with_retry(&path, &policy) {
+                eprintln!("Failed to remove directory at {}: {e}", path.display());
+            }
+        });
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn remove_async() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+        let directory = Directory::create(&dir_path).keep();
+        directory.write_string("file.txt", "content");
+
+        directory.remove_async().await.unwrap();
+
+        assert!(!dir_path.exists());
+    }
+
+    #[tokio::test]
+    async fn with_background_drop() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+
+        drop(Directory::create(&dir_path).with_background_drop());
+
+        for _ in 0..100 {
+            if !dir_path.exists() {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("Directory at {} was not removed", dir_path.display());
+    }
+
+    #[test]
+    fn with_background_drop_outside_runtime() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+
+        drop(Directory::create(&dir_path).with_background_drop());
+
+        assert!(!dir_path.exists());
+    }
+}
@@ -0,0 +1,66 @@
+use super::*;
+
+/// Byte order mark of UTF-8.
+pub(super) const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Methods for handling byte order marks (BOMs) in text files. Some consumers
+/// (e.g. Excel or older MSVC tools) require a BOM to detect the encoding of a file,
+/// while most others choke on it. BOMs are never written by default, but always
+/// stripped by `read_string` (and `read_string_with_encoding` with the `encoding` feature).
+impl Directory {
+    /// Creates a new Directory instance from self that writes a byte order mark at the
+    /// start of text files written with `write_string`, `write_string_new`, `write_json`,
+    /// and `write_toml` (and `write_string_with_encoding` for UTF-8 and UTF-16 with the
+    /// `encoding` feature). Files that the directory maintains itself, such as the marker
+    /// and `.gitignore`, are always written without a byte order mark.
+    pub fn with_bom(mut self) -> Self {
+        self.bom = true;
+        self
+    }
+
+    /// Returns the bytes of the given text as written to a UTF-8 text file,
+    /// i.e. with the directory's line endings and byte order mark applied, if any.
+    pub(super) fn text_bytes(&self, content: String) -> Vec<u8> {
+        self.with_bom_prefix(UTF8_BOM, self.apply_newline(content).into_bytes())
+    }
+
+    /// Returns the given bytes prefixed with the given byte order mark
+    /// if the directory writes byte order marks.
+    pub(super) fn with_bom_prefix(&self, bom: &[u8], bytes: Vec<u8>) -> Vec<u8> {
+        if self.bom {
+            [bom, &bytes].concat()
+        } else {
+            bytes
+        }
+    }
+}
+
+/// Returns the given bytes without the given leading byte order mark, if present.
+pub(super) fn strip_bom<'a>(bytes: &'a [u8], bom: &[u8]) -> &'a [u8] {
+    bytes.strip_prefix(bom).unwrap_or(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn with_bom() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_bom();
+
+        directory.write_string("file.txt", "text");
+
+        let content = std::fs::read(directory.path().join("file.txt")).unwrap();
+        assert_eq!(content, b"\xEF\xBB\xBFtext");
+        assert_eq!(directory.read_string("file.txt"), "text");
+    }
+
+    #[test]
+    fn strip_bom() {
+        assert_eq!(super::strip_bom(b"\xEF\xBB\xBFtext", UTF8_BOM), b"text");
+        assert_eq!(super::strip_bom(b"text", UTF8_BOM), b"text");
+    }
+}
@@ -0,0 +1,514 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path};
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::{Error, Result};
+
+/// Magic bytes at the start of a bundle file, including the format version.
+const BUNDLE_MAGIC: &[u8; 8] = b"CONVWDB1";
+
+/// Manifest of a bundle, stored as JSON after the magic bytes and its length.
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    entries: Vec<ManifestEntry>,
+}
+
+/// An entry of a bundle. Paths use `/` as separator on all platforms.
+/// The permission bits and modification times (in nanoseconds since the Unix epoch)
+/// are only recorded with `Directory::preserve_metadata`.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum ManifestEntry {
+    Dir {
+        path: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mtime: Option<u64>,
+    },
+    File {
+        path: String,
+        len: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mtime: Option<u64>,
+    },
+    Symlink {
+        path: String,
+        target: String,
+    },
+}
+
+/// Methods for exporting and importing whole directories as single-file bundles,
+/// e.g. for shipping a working directory between machines without archive tooling.
+///
+/// A bundle starts with the magic bytes `CONVWDB1`, followed by the length of a JSON
+/// manifest as little-endian `u64`, the manifest itself, and the contents of all files
+/// in manifest order. The manifest lists all directories, files (with their lengths),
+/// and symlinks (with their targets, which are stored verbatim). With `preserve_metadata`,
+/// it also records the permissions and modification times of directories and files.
+/// Unpacking rejects symlinks whose targets are absolute or lead out of the directory,
+/// and entries at or below symlinks, so a bundle can't write outside of its directory.
+impl Directory {
+    /// Writes the tree of the directory to a bundle file at `bundle_path`.
+    /// With `with_nfc_names`, entry names are stored in Unicode NFC.
+    /// Returns an error if the tree cannot be read, if a path is not valid UTF-8,
+    /// or if the bundle cannot be written.
+    ///
+    /// # Arguments
+    /// * `bundle_path` - The path of the bundle file to write (outside of the directory).
+    pub fn bundle_to<P: AsRef<Path>>(&self, bundle_path: P) -> Result<()> {
+        let bundle_path = bundle_path.as_ref();
+        let entries = self.walk_tree(&self.path)?;
+        let mut manifest = Manifest {
+            entries: Vec::with_capacity(entries.len()),
+        };
+        for entry in &entries {
+            let entry_path = self.path.join(entry);
+            let metadata =
+                std::fs::symlink_metadata(&entry_path).map_err(|e| Error::io(&entry_path, e))?;
+            let path = to_bundle_path(&self.normalize_name(entry), &entry_path)?;
+            let (mode, mtime) = if self.preserve_metadata {
+                (Some(mode_of(&metadata)), mtime_of(&metadata))
+            } else {
+                (None, None)
+            };
+            manifest.entries.push(if metadata.is_dir() {
+                ManifestEntry::Dir { path, mode, mtime }
+            } else if metadata.is_symlink() {
+                let target =
+                    std::fs::read_link(&entry_path).map_err(|e| Error::io(&entry_path, e))?;
+                let target = target
+                    .to_str()
+                    .ok_or_else(|| Error::io(&entry_path, invalid_data("path is not valid UTF-8")))?
+                    .to_string();
+                ManifestEntry::Symlink { path, target }
+            } else {
+                ManifestEntry::File {
+                    path,
+                    len: metadata.len(),
+                    mode,
+                    mtime,
+                }
+            });
+        }
+        let manifest_json = serde_json::to_vec(&manifest).map_err(|e| Error::Serialization {
+            format: "JSON",
+            path: bundle_path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+
+        let io_error = |e| Error::io(bundle_path, e);
+        let mut writer = BufWriter::new(File::create(bundle_path).map_err(io_error)?);
+        writer.write_all(BUNDLE_MAGIC).map_err(io_error)?;
+        writer
+            .write_all(&(manifest_json.len() as u64).to_le_bytes())
+            .map_err(io_error)?;
+        writer.write_all(&manifest_json).map_err(io_error)?;
+        self.progress_start(entries.len(), || {
+            format!(
+                "Bundling {} to {}",
+                self.path.display(),
+                bundle_path.display()
+            )
+        });
+        for (entry, manifest_entry) in entries.iter().zip(&manifest.entries) {
+            if let ManifestEntry::File { len, .. } = manifest_entry {
+                let entry_path = self.path.join(entry);
+                let file = File::open(&entry_path).map_err(|e| Error::io(&entry_path, e))?;
+                let copied = std::io::copy(&mut file.take(*len), &mut writer).map_err(io_error)?;
+                if copied != *len {
+                    return Err(Error::io(
+                        entry_path,
+                        invalid_data("file was truncated while bundling"),
+                    ));
+                }
+            }
+            self.progress_inc();
+        }
+        self.progress_finish();
+        writer
+            .into_inner()
+            .map_err(|e| io_error(e.into_error()))?
+            .sync_all()
+            .map_err(io_error)
+    }
+
+    /// Creates a Directory at `target` (see `create`) and unpacks the bundle file at
+    /// `bundle_path` (see `bundle_to`) into it. The new directory is temporary.
+    /// Returns an error if the bundle cannot be read or is invalid, e.g. because it
+    /// contains paths or symlinks that would escape the directory, or if an entry
+    /// cannot be written.
+    /// Panics if the directory cannot be created.
+    ///
+    /// # Arguments
+    /// * `bundle_path` - The path of the bundle file to read.
+    /// * `target` - The path of the directory to unpack the bundle into.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let dir = Directory::cargo_target_subdir("from_bundle_example/source");
+    /// dir.write_string("input.txt", "Hello, bundle!");
+    /// let bundle_path = dir.path().with_extension("bundle");
+    /// dir.bundle_to(&bundle_path).unwrap();
+    ///
+    /// let copy = Directory::from_bundle(&bundle_path, dir.path().with_file_name("copy")).unwrap();
+    /// assert!(copy.diff_dir(&dir).is_equal());
+    /// # std::fs::remove_file(bundle_path).unwrap();
+    /// ```
+    pub fn from_bundle<P: AsRef<Path>, Q: AsRef<Path>>(bundle_path: P, target: Q) -> Result<Self> {
+        let bundle_path = bundle_path.as_ref();
+        let io_error = |e| Error::io(bundle_path, e);
+        let mut reader = BufReader::new(File::open(bundle_path).map_err(io_error)?);
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic).map_err(io_error)?;
+        if &magic != BUNDLE_MAGIC {
+            return Err(io_error(invalid_data("not a conv-wd bundle")));
+        }
+        let mut manifest_len = [0; 8];
+        reader.read_exact(&mut manifest_len).map_err(io_error)?;
+        let mut manifest_json = Vec::new();
+        (&mut reader)
+            .take(u64::from_le_bytes(manifest_len))
+            .read_to_end(&mut manifest_json)
+            .map_err(io_error)?;
+        let manifest: Manifest = serde_json::from_slice(&manifest_json)
+            .map_err(|e| io_error(invalid_data(&format!("invalid manifest: {e}"))))?;
+
+        let directory = Self::create(target);
+        let mut dirs = Vec::new();
+        for manifest_entry in &manifest.entries {
+            match manifest_entry {
+                ManifestEntry::Dir { path, mode, mtime } => {
+                    let entry_path = directory.bundle_entry_path(path, bundle_path)?;
+                    std::fs::create_dir_all(&entry_path).map_err(|e| Error::io(&entry_path, e))?;
+                    dirs.push((entry_path, *mode, *mtime));
+                }
+                ManifestEntry::File {
+                    path,
+                    len,
+                    mode,
+                    mtime,
+                } => {
+                    let entry_path = directory.bundle_entry_path(path, bundle_path)?;
+                    let mut file =
+                        File::create(&entry_path).map_err(|e| Error::io(&entry_path, e))?;
+                    let copied = std::io::copy(&mut (&mut reader).take(*len), &mut file)
+                        .map_err(|e| Error::io(&entry_path, e))?;
+                    if copied != *len {
+                        return Err(io_error(invalid_data("bundle is truncated")));
+                    }
+                    drop(file);
+                    apply_metadata(&entry_path, *mode, *mtime)
+                        .map_err(|e| Error::io(&entry_path, e))?;
+                }
+                ManifestEntry::Symlink { path, target } => {
+                    let entry_path = directory.bundle_entry_path(path, bundle_path)?;
+                    check_symlink_target(path, target, bundle_path)?;
+                    // Used on Windows to decide between a file and a directory symlink.
+                    let resolved_target =
+                        entry_path.parent().unwrap_or(&directory.path).join(target);
+                    sync::create_symlink(Path::new(target), &resolved_target, &entry_path)
+                        .map_err(|e| Error::io(&entry_path, e))?;
+                }
+            }
+        }
+        // Directories are updated after their contents, deepest first.
+        for (entry_path, mode, mtime) in dirs.into_iter().rev() {
+            apply_metadata(&entry_path, mode, mtime).map_err(|e| Error::io(&entry_path, e))?;
+        }
+        Ok(directory)
+    }
+
+    /// Returns the path of a bundle entry within the directory.
+    /// Returns an error if the path is absolute or would escape the directory, also
+    /// through a symlink at or above the entry, or `Error::DepthExceeded` if it is nested
+    /// deeper than the maximum depth, if any.
+    fn bundle_entry_path(&self, path: &str, bundle_path: &Path) -> Result<PathBuf> {
+        let relative_path = Path::new(path);
+        if !relative_path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(Error::io(
+                bundle_path,
+                invalid_data(&format!("invalid entry path {path:?}")),
+            ));
+        }
+        if let Some(max_depth) = self.max_depth
+            && relative_path.components().count() > max_depth
+        {
+            return Err(Error::DepthExceeded {
+                path: self.path.join(relative_path),
+                max_depth,
+            });
+        }
+        let mut entry_path = self.path.clone();
+        for component in relative_path.components() {
+            entry_path.push(component);
+            match std::fs::symlink_metadata(&entry_path) {
+                Ok(metadata) if metadata.is_symlink() => {
+                    return Err(Error::io(
+                        bundle_path,
+                        invalid_data(&format!("entry path {path:?} is or lies below a symlink")),
+                    ));
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        Ok(self.path.join(relative_path))
+    }
+}
+
+/// Checks that the target of the symlink entry at `path` stays within the directory,
+/// i.e. that it is relative and doesn't go up beyond the root with `..`.
+fn check_symlink_target(path: &str, target: &str, bundle_path: &Path) -> Result<()> {
+    let invalid_target = || {
+        Error::io(
+            bundle_path,
+            invalid_data(&format!("invalid target {target:?} of symlink {path:?}")),
+        )
+    };
+    let mut depth = Path::new(path).components().count().saturating_sub(1);
+    for component in Path::new(target).components() {
+        depth = match component {
+            Component::Normal(_) => depth + 1,
+            Component::CurDir => depth,
+            Component::ParentDir => depth.checked_sub(1).ok_or_else(invalid_target)?,
+            Component::RootDir | Component::Prefix(_) => return Err(invalid_target()),
+        };
+    }
+    Ok(())
+}
+
+/// Returns the permission bits of an entry for the manifest. Outside of Unix,
+/// only the read-only attribute is recorded, as `0o444` or `0o666`.
+fn mode_of(metadata: &std::fs::Metadata) -> u32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        metadata.permissions().mode() & 0o7777
+    }
+    #[cfg(not(unix))]
+    if metadata.permissions().readonly() {
+        0o444
+    } else {
+        0o666
+    }
+}
+
+/// Returns the modification time of an entry for the manifest,
+/// unless it is before the Unix epoch or not available.
+fn mtime_of(metadata: &std::fs::Metadata) -> Option<u64> {
+    let since_epoch = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    u64::try_from(since_epoch.as_nanos()).ok()
+}
+
+/// Applies the recorded permissions and modification time, if any, to an unpacked entry.
+fn apply_metadata(path: &Path, mode: Option<u32>, mtime: Option<u64>) -> std::io::Result<()> {
+    if let Some(mtime) = mtime {
+        util::set_modified(path, UNIX_EPOCH + Duration::from_nanos(mtime))?;
+    }
+    if let Some(mode) = mode {
+        #[cfg(unix)]
+        let permissions = {
+            use std::os::unix::fs::PermissionsExt;
+
+            std::fs::Permissions::from_mode(mode)
+        };
+        #[cfg(not(unix))]
+        let permissions = {
+            let mut permissions = std::fs::metadata(path)?.permissions();
+            permissions.set_readonly(mode & 0o222 == 0);
+            permissions
+        };
+        std::fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
+}
+
+/// Converts a relative path to its representation in a bundle.
+/// Returns an error if the path is not valid UTF-8.
+fn to_bundle_path(path: &Path, entry_path: &Path) -> Result<String> {
+    let components = path
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| Error::io(entry_path, invalid_data("path is not valid UTF-8")))?;
+    Ok(components.join("/"))
+}
+
+/// Creates an `InvalidData` I/O error with the given message.
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn bundle_to() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("source"));
+        std::fs::create_dir_all(directory.path().join("subdir/empty")).unwrap();
+        directory.write_string("subdir/file.txt", "Hello, bundle!");
+        directory.write_bytes("binary.bin", [0u8, 1, 2, 255]);
+        let bundle_path = temp_dir.path().join("source.bundle");
+
+        directory.bundle_to(&bundle_path).unwrap();
+
+        let content = std::fs::read(&bundle_path).unwrap();
+        assert!(content.starts_with(BUNDLE_MAGIC));
+        assert!(content.ends_with(b"\0\x01\x02\xffHello, bundle!"));
+    }
+
+    #[test]
+    fn from_bundle() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("source"));
+        std::fs::create_dir_all(directory.path().join("subdir/empty")).unwrap();
+        directory.write_string("subdir/file.txt", "Hello, bundle!");
+        directory.write_bytes("binary.bin", [0u8, 1, 2, 255]);
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("subdir/file.txt", directory.path().join("link")).unwrap();
+        let bundle_path = temp_dir.path().join("source.bundle");
+        directory.bundle_to(&bundle_path).unwrap();
+
+        let copy = Directory::from_bundle(&bundle_path, temp_dir.path().join("copy")).unwrap();
+
+        assert!(copy.diff_dir(&directory).is_equal());
+    }
+
+    #[test]
+    fn from_bundle_rejects_invalid_file() {
+        let temp_dir = tempdir().unwrap();
+        let bundle_path = temp_dir.path().join("invalid.bundle");
+        std::fs::write(&bundle_path, "not a bundle").unwrap();
+
+        let result = Directory::from_bundle(&bundle_path, temp_dir.path().join("copy"));
+
+        assert!(matches!(result, Err(Error::Io { .. })));
+    }
+
+    /// Writes a bundle with the given manifest and file contents.
+    fn write_bundle(bundle_path: &Path, manifest: &str, contents: &[u8]) {
+        let mut content = BUNDLE_MAGIC.to_vec();
+        content.extend_from_slice(&(manifest.len() as u64).to_le_bytes());
+        content.extend_from_slice(manifest.as_bytes());
+        content.extend_from_slice(contents);
+        std::fs::write(bundle_path, content).unwrap();
+    }
+
+    #[test]
+    fn from_bundle_rejects_escaping_paths() {
+        let temp_dir = tempdir().unwrap();
+        let bundle_path = temp_dir.path().join("escaping.bundle");
+        let manifest = r#"{"entries":[{"kind":"file","path":"../escaped.txt","len":0}]}"#;
+        write_bundle(&bundle_path, manifest, b"");
+
+        let result = Directory::from_bundle(&bundle_path, temp_dir.path().join("copy"));
+
+        assert!(matches!(result, Err(Error::Io { .. })));
+        assert!(!temp_dir.path().join("escaped.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn from_bundle_rejects_escaping_symlinks() {
+        let temp_dir = tempdir().unwrap();
+        let outside = temp_dir.path().join("outside");
+        std::fs::create_dir(&outside).unwrap();
+        let bundle_path = temp_dir.path().join("escaping.bundle");
+        let manifest = format!(
+            r#"{{"entries":[{{"kind":"symlink","path":"link","target":{:?}}},{{"kind":"file","path":"link/pwned.txt","len":5}}]}}"#,
+            outside.to_str().unwrap()
+        );
+        write_bundle(&bundle_path, &manifest, b"pwned");
+
+        let result = Directory::from_bundle(&bundle_path, temp_dir.path().join("copy"));
+
+        assert!(matches!(result, Err(Error::Io { .. })));
+        assert!(!outside.join("pwned.txt").exists());
+
+        let manifest = r#"{"entries":[{"kind":"dir","path":"subdir"},{"kind":"symlink","path":"subdir/link","target":"../../outside"}]}"#;
+        write_bundle(&bundle_path, manifest, b"");
+
+        let result = Directory::from_bundle(&bundle_path, temp_dir.path().join("copy2"));
+
+        assert!(matches!(result, Err(Error::Io { .. })));
+        assert!(!temp_dir.path().join("copy2/subdir/link").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn from_bundle_rejects_entries_below_symlinks() {
+        let temp_dir = tempdir().unwrap();
+        let bundle_path = temp_dir.path().join("symlinked.bundle");
+        let manifest = r#"{"entries":[{"kind":"dir","path":"subdir"},{"kind":"symlink","path":"link","target":"subdir"},{"kind":"file","path":"link/file.txt","len":0}]}"#;
+        write_bundle(&bundle_path, manifest, b"");
+
+        let result = Directory::from_bundle(&bundle_path, temp_dir.path().join("copy"));
+
+        assert!(matches!(result, Err(Error::Io { .. })));
+        assert!(!temp_dir.path().join("copy/subdir/file.txt").exists());
+    }
+
+    #[test]
+    fn bundle_max_depth() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("source")).with_max_depth(1);
+        std::fs::create_dir(directory.path().join("subdir")).unwrap();
+        directory.write_string("subdir/file.txt", "");
+        let bundle_path = temp_dir.path().join("source.bundle");
+
+        let result = directory.bundle_to(&bundle_path);
+        let entry_path = directory.bundle_entry_path("subdir/file.txt", &bundle_path);
+
+        assert!(matches!(
+            result,
+            Err(Error::DepthExceeded { max_depth: 1, .. })
+        ));
+        assert!(matches!(
+            entry_path,
+            Err(Error::DepthExceeded { max_depth: 1, .. })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn from_bundle_preserve_metadata() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("source")).preserve_metadata();
+        std::fs::create_dir(directory.path().join("bin")).unwrap();
+        directory.write_string("bin/run.sh", "#!/bin/sh\n");
+        let script = directory.path().join("bin/run.sh");
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let mtime = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        util::set_modified(&script, mtime).unwrap();
+        util::set_modified(&directory.path().join("bin"), mtime).unwrap();
+        let bundle_path = temp_dir.path().join("source.bundle");
+        directory.bundle_to(&bundle_path).unwrap();
+
+        let copy = Directory::from_bundle(&bundle_path, temp_dir.path().join("copy")).unwrap();
+
+        let metadata = std::fs::metadata(copy.path().join("bin/run.sh")).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o7777, 0o755);
+        assert_eq!(metadata.modified().unwrap(), mtime);
+        let dir_metadata = std::fs::metadata(copy.path().join("bin")).unwrap();
+        assert_eq!(dir_metadata.modified().unwrap(), mtime);
+    }
+}
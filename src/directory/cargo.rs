@@ -8,6 +8,8 @@ impl Directory {
     /// Creates a new `Directory` instance representing a
     /// subdirectory of the cargo manifest directory.
     /// The directory is created if it does not exist.
+    /// If the `CONV_WD_ROOT` environment variable is set, the subdirectory is created
+    /// below that root instead (this applies to all `cargo_*` constructors).
     /// Panics if the `CARGO_MANIFEST_DIR` environment variable is not set,
     /// the subdirectory path is an absolute path, invalid,
     /// or if the directory cannot be created.
@@ -28,10 +30,13 @@ impl Directory {
     /// );
     /// ```
     pub fn cargo_manifest_subdir<P: AsRef<Path>>(subdir: P) -> Self {
-        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
-            .expect("CARGO_MANIFEST_DIR environment variable is not set");
+        let manifest_dir = root::base_or_root(|| {
+            std::env::var("CARGO_MANIFEST_DIR")
+                .expect("CARGO_MANIFEST_DIR environment variable is not set")
+                .into()
+        });
         assert_relative_path(subdir.as_ref());
-        let path = std::path::Path::new(&manifest_dir).join(subdir.as_ref());
+        let path = manifest_dir.join(subdir.as_ref());
         Directory::create(path)
     }
 
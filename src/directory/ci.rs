@@ -0,0 +1,162 @@
+use super::*;
+
+use crate::util::assert_relative_path;
+use std::ffi::OsString;
+use std::path::Path;
+
+/// Environment variables that point to the checkout of the project on CI,
+/// in order of precedence (GitHub Actions, GitLab CI).
+const WORKSPACE_VARS: [&str; 2] = ["GITHUB_WORKSPACE", "CI_PROJECT_DIR"];
+
+/// Environment variables that point to a scratch directory for temporary files,
+/// in order of precedence (GitHub Actions runners, generic Unix).
+const TEMP_VARS: [&str; 2] = ["RUNNER_TEMP", "TMPDIR"];
+
+/// Constructors that place directories relative to a base resolved from the environment,
+/// so the same code places working directories sensibly both locally and on CI.
+/// Like the `cargo_*` constructors, they are redirected by the `CONV_WD_ROOT`
+/// environment variable if it is set.
+impl Directory {
+    /// Creates a new `Directory` instance representing a subdirectory of the workspace,
+    /// i.e. the checkout of the project: `GITHUB_WORKSPACE` or `CI_PROJECT_DIR` on CI,
+    /// and `CARGO_MANIFEST_DIR` or the current directory locally.
+    /// The directory is created if it does not exist.
+    /// Panics if the subdirectory path is absolute, if the current directory is needed
+    /// but cannot be determined, or if the directory cannot be created.
+    ///
+    /// # Arguments
+    /// * `subdir` - The subdirectory path relative to the workspace.
+    pub fn ci_workspace_subdir<P: AsRef<Path>>(subdir: P) -> Self {
+        assert_relative_path(subdir.as_ref());
+        let base = root::base_or_root(|| workspace_base(std::env::var_os));
+        Directory::create(base.join(subdir.as_ref()))
+    }
+
+    /// Creates a new `Directory` instance representing a subdirectory of the scratch
+    /// directory for temporary files: `RUNNER_TEMP` on CI, which is cleaned between jobs,
+    /// then `TMPDIR`, and the system's temporary directory otherwise.
+    /// The directory is created if it does not exist.
+    /// Panics if the subdirectory path is absolute or if the directory cannot be created.
+    ///
+    /// # Arguments
+    /// * `subdir` - The subdirectory path relative to the scratch directory.
+    pub fn ci_temp_subdir<P: AsRef<Path>>(subdir: P) -> Self {
+        assert_relative_path(subdir.as_ref());
+        let base = root::base_or_root(|| temp_base(std::env::var_os));
+        Directory::create(base.join(subdir.as_ref()))
+    }
+}
+
+/// Returns the base of `ci_workspace_subdir` for the environment variables as looked up
+/// by `lookup`: the first of `WORKSPACE_VARS` that is set, then `CARGO_MANIFEST_DIR`,
+/// and the current directory otherwise.
+/// Panics if the current directory is needed but cannot be determined.
+fn workspace_base(lookup: impl Fn(&'static str) -> Option<OsString>) -> PathBuf {
+    resolve_base(&WORKSPACE_VARS, &lookup)
+        .or_else(|| resolve_base(&["CARGO_MANIFEST_DIR"], &lookup))
+        .unwrap_or_else(|| {
+            std::env::current_dir()
+                .unwrap_or_else(|e| panic!("Failed to determine the current directory: {e}"))
+        })
+}
+
+/// Returns the base of `ci_temp_subdir` for the environment variables as looked up
+/// by `lookup`: the first of `TEMP_VARS` that is set, and the system's temporary
+/// directory otherwise.
+fn temp_base(lookup: impl Fn(&'static str) -> Option<OsString>) -> PathBuf {
+    resolve_base(&TEMP_VARS, lookup).unwrap_or_else(std::env::temp_dir)
+}
+
+/// Returns the value of the first of the given environment variables that is set
+/// and not empty, as looked up by `lookup`.
+fn resolve_base(
+    vars: &[&'static str],
+    lookup: impl Fn(&'static str) -> Option<OsString>,
+) -> Option<PathBuf> {
+    vars.iter()
+        .filter_map(|var| lookup(var))
+        .find(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_base() {
+        let lookup = |var: &str| match var {
+            "GITHUB_WORKSPACE" => Some(OsString::new()),
+            "CI_PROJECT_DIR" => Some(OsString::from("/builds/project")),
+            _ => None,
+        };
+
+        assert_eq!(
+            super::resolve_base(&WORKSPACE_VARS, lookup),
+            Some(PathBuf::from("/builds/project"))
+        );
+        assert_eq!(super::resolve_base(&TEMP_VARS, lookup), None);
+    }
+
+    /// Returns a lookup of the given environment variables.
+    fn vars(
+        vars: &'static [(&'static str, &'static str)],
+    ) -> impl Fn(&'static str) -> Option<OsString> {
+        move |var| {
+            vars.iter()
+                .find(|(name, _)| *name == var)
+                .map(|(_, value)| OsString::from(value))
+        }
+    }
+
+    #[test]
+    fn workspace_base() {
+        assert_eq!(
+            super::workspace_base(vars(&[
+                ("GITHUB_WORKSPACE", "/github/workspace"),
+                ("CI_PROJECT_DIR", "/builds/project"),
+                ("CARGO_MANIFEST_DIR", "/manifest"),
+            ])),
+            PathBuf::from("/github/workspace")
+        );
+        assert_eq!(
+            super::workspace_base(vars(&[
+                ("CI_PROJECT_DIR", "/builds/project"),
+                ("CARGO_MANIFEST_DIR", "/manifest"),
+            ])),
+            PathBuf::from("/builds/project")
+        );
+        assert_eq!(
+            super::workspace_base(vars(&[("CARGO_MANIFEST_DIR", "/manifest")])),
+            PathBuf::from("/manifest")
+        );
+        assert_eq!(
+            super::workspace_base(vars(&[])),
+            std::env::current_dir().unwrap()
+        );
+    }
+
+    #[test]
+    fn temp_base() {
+        assert_eq!(
+            super::temp_base(vars(&[
+                ("RUNNER_TEMP", "/runner/temp"),
+                ("TMPDIR", "/tmpdir")
+            ])),
+            PathBuf::from("/runner/temp")
+        );
+        assert_eq!(
+            super::temp_base(vars(&[("RUNNER_TEMP", ""), ("TMPDIR", "/tmpdir")])),
+            PathBuf::from("/tmpdir")
+        );
+        assert_eq!(super::temp_base(vars(&[])), std::env::temp_dir());
+    }
+
+    #[test]
+    fn ci_temp_subdir() {
+        let directory = Directory::ci_temp_subdir("conv_wd_ci_temp_subdir_test");
+
+        assert!(directory.path().ends_with("conv_wd_ci_temp_subdir_test"));
+        assert!(directory.path().is_dir());
+    }
+}
@@ -0,0 +1,278 @@
+use super::*;
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::process::ProcessId;
+use crate::{Error, Result};
+
+/// Name of the file that records the process owning a claimed directory.
+const CLAIM_FILE_NAME: &str = ".conv-wd.claim";
+
+/// Counter for the names of the private claim files, so concurrent claims by several
+/// threads of the same process don't collide.
+static NEXT_TEMP_FILE: AtomicU64 = AtomicU64::new(0);
+
+/// Methods for claiming ownership of shared directories between processes.
+impl Directory {
+    /// Claims ownership of the directory for the current process.
+    /// A claim file recording the PID and start time of the process is written into the
+    /// directory and removed again when the returned guard is dropped.
+    /// Fails with `Error::Claimed` if another live process holds the claim, and also if
+    /// the current process already holds it, so there is only ever one guard per claim.
+    /// Claims of processes that are no longer running (e.g. crashed CI jobs)
+    /// are reclaimed automatically. The start time guards against PID reuse
+    /// on platforms where it is available (Linux and Windows).
+    ///
+    /// Claims only coordinate processes on the same host,
+    /// since process liveness cannot be checked across hosts.
+    pub fn claim(&self) -> Result<ClaimGuard> {
+        let claim_path = self.path.join(CLAIM_FILE_NAME);
+        let owner = ProcessId::current();
+        // Write the claim to a private file first and link it into place, so other
+        // processes never observe a partially written claim file.
+        let temp_path = self.path.join(temp_file_name(&owner, "tmp"));
+        std::fs::write(&temp_path, format_owner(&owner)).map_err(|e| Error::io(&temp_path, e))?;
+        let result = link_claim(&temp_path, &claim_path, &owner);
+        let _ = std::fs::remove_file(&temp_path);
+        result?;
+        Ok(ClaimGuard {
+            path: claim_path,
+            owner,
+        })
+    }
+}
+
+/// Links the prepared claim file into place, taking over stale claims.
+fn link_claim(temp_path: &Path, claim_path: &Path, owner: &ProcessId) -> Result<()> {
+    const MAX_ATTEMPTS: usize = 10;
+
+    for _ in 0..MAX_ATTEMPTS {
+        match std::fs::hard_link(temp_path, claim_path) {
+            Ok(()) => return Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(Error::io(claim_path, e)),
+        }
+        match read_owner(claim_path) {
+            Some(current) if current.is_alive() => {
+                return Err(Error::Claimed {
+                    path: claim_path.to_path_buf(),
+                    pid: current.pid,
+                });
+            }
+            stale => {
+                take_over_stale_claim(claim_path, stale, owner)
+                    .map_err(|e| Error::io(claim_path, e))?;
+            }
+        }
+    }
+    Err(Error::io(
+        claim_path,
+        std::io::Error::other("claim is contended by too many processes"),
+    ))
+}
+
+/// Removes a stale claim file.
+/// The file is first moved to a private name, so a fresh claim that another process
+/// created in the meantime can be detected and put back instead of being removed.
+fn take_over_stale_claim(
+    claim_path: &Path,
+    stale: Option<ProcessId>,
+    owner: &ProcessId,
+) -> std::io::Result<()> {
+    let stale_path = claim_path.with_file_name(temp_file_name(owner, "stale"));
+    match std::fs::rename(claim_path, &stale_path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        result => result?,
+    }
+    if read_owner(&stale_path) != stale {
+        // Another process reclaimed the directory in the meantime.
+        let _ = std::fs::hard_link(&stale_path, claim_path);
+    }
+    std::fs::remove_file(&stale_path)
+}
+
+/// Returns a name for a private claim file of the given owner that is unique
+/// within the process.
+fn temp_file_name(owner: &ProcessId, kind: &str) -> String {
+    format!(
+        "{CLAIM_FILE_NAME}.{kind}-{}-{}",
+        owner.pid,
+        NEXT_TEMP_FILE.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Formats the claim file content for the given owner.
+fn format_owner(owner: &ProcessId) -> String {
+    let mut content = format!("pid = {}\n", owner.pid);
+    if let Some(start_time) = owner.start_time {
+        content.push_str(&format!("start_time = {start_time}\n"));
+    }
+    content
+}
+
+/// Reads the owner from the claim file at the given path.
+/// Returns `None` if the file cannot be read or parsed.
+fn read_owner(claim_path: &Path) -> Option<ProcessId> {
+    let content = std::fs::read_to_string(claim_path).ok()?;
+    let mut pid = None;
+    let mut start_time = None;
+    for line in content.lines() {
+        let (key, value) = line.split_once('=')?;
+        let value = value.trim();
+        match key.trim() {
+            "pid" => pid = Some(value.parse().ok()?),
+            "start_time" => start_time = Some(value.parse().ok()?),
+            _ => {}
+        }
+    }
+    Some(ProcessId {
+        pid: pid?,
+        start_time,
+    })
+}
+
+/// Guard returned by [`Directory::claim`].
+/// Releases the claim by removing the claim file when dropped.
+#[must_use = "the claim is released when the guard is dropped"]
+pub struct ClaimGuard {
+    path: PathBuf,
+    owner: ProcessId,
+}
+
+impl Drop for ClaimGuard {
+    /// Removes the claim file if it still records the current process.
+    fn drop(&mut self) {
+        if read_owner(&self.path) == Some(self.owner) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn claim() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("shared_dir"));
+        let claim_path = directory.path().join(CLAIM_FILE_NAME);
+
+        {
+            let _guard = directory.claim().unwrap();
+            assert_eq!(read_owner(&claim_path), Some(ProcessId::current()));
+        }
+        assert!(!claim_path.exists());
+        assert!(
+            std::fs::read_dir(directory.path())
+                .unwrap()
+                .next()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn claim_held_by_current_process() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("shared_dir"));
+        let claim_path = directory.path().join(CLAIM_FILE_NAME);
+        let guard = directory.claim().unwrap();
+
+        let result = directory.claim();
+
+        assert!(
+            matches!(result, Err(Error::Claimed { pid, .. }) if pid == ProcessId::current().pid)
+        );
+        assert_eq!(read_owner(&claim_path), Some(ProcessId::current()));
+        drop(guard);
+        assert!(!claim_path.exists());
+    }
+
+    #[test]
+    fn claim_from_threads() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("shared_dir"));
+
+        let results = std::thread::scope(|scope| {
+            let handles = (0..8)
+                .map(|_| scope.spawn(|| directory.claim()))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+        assert!(
+            results
+                .iter()
+                .all(|result| matches!(result, Ok(_) | Err(Error::Claimed { .. })))
+        );
+        drop(results);
+        assert!(
+            std::fs::read_dir(directory.path())
+                .unwrap()
+                .next()
+                .is_none()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn claim_held_by_live_process() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("shared_dir"));
+        let mut child = std::process::Command::new("sleep")
+            .arg("10")
+            .spawn()
+            .unwrap();
+        let other = ProcessId {
+            pid: child.id(),
+            start_time: None,
+        };
+        directory.write_string(CLAIM_FILE_NAME, format_owner(&other));
+
+        let result = directory.claim();
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(matches!(result, Err(Error::Claimed { pid, .. }) if pid == other.pid));
+    }
+
+    #[test]
+    fn reclaim_from_dead_process() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("shared_dir"));
+        let current = ProcessId::current();
+        let dead = ProcessId {
+            pid: current.pid,
+            start_time: Some(current.start_time.unwrap_or(0) + 1),
+        };
+        directory.write_string(CLAIM_FILE_NAME, format_owner(&dead));
+
+        let _guard = directory.claim().unwrap();
+
+        assert_eq!(
+            read_owner(&directory.path().join(CLAIM_FILE_NAME)),
+            Some(current)
+        );
+    }
+
+    #[test]
+    fn reclaim_unreadable_claim() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("shared_dir"));
+        directory.write_string(CLAIM_FILE_NAME, "garbage");
+
+        let _guard = directory.claim().unwrap();
+
+        assert_eq!(
+            read_owner(&directory.path().join(CLAIM_FILE_NAME)),
+            Some(ProcessId::current())
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use super::*;
+
+use serde::de::DeserializeOwned;
+use toml::{Table, Value};
+
+/// Methods for loading the configuration of a tool whose working directory holds
+/// its configuration files.
+impl Directory {
+    /// Loads the configuration `name` by merging, in increasing order of precedence:
+    /// 1. the file `{name}.toml` in the directory,
+    /// 2. the optional local override file `{name}.local.toml` in the directory,
+    /// 3. environment variables prefixed with the upper-case name and an underscore,
+    ///    where double underscores separate nested keys
+    ///    (e.g. `APP_SERVER__PORT=8080` sets `server.port` for the name `app`).
+    ///    Values are parsed as TOML values (e.g. numbers or booleans) where possible,
+    ///    and taken as strings otherwise.
+    ///
+    /// Tables are merged recursively, all other values are replaced.
+    /// Returns the merged configuration deserialized into `T`.
+    /// Panics if `{name}.toml` cannot be read, if a file is not valid TOML,
+    /// or if the merged configuration cannot be deserialized.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the configuration, e.g. `app` for `app.toml`.
+    pub fn load_config<T: DeserializeOwned>(&self, name: &str) -> T {
+        let mut config = self.read_config_file(&format!("{name}.toml"));
+        let local_file = format!("{name}.local.toml");
+        if self.path.join(&local_file).exists() {
+            merge(&mut config, self.read_config_file(&local_file));
+        }
+        let prefix = format!("{}_", name.to_uppercase().replace('-', "_"));
+        merge(&mut config, env_table(&prefix, std::env::vars()));
+
+        config.try_into().unwrap_or_else(|e| {
+            panic!(
+                "Failed to deserialize configuration {name} in {}: {e}",
+                self.path.display()
+            )
+        })
+    }
+
+    /// Reads and parses the TOML file at the given path within the directory.
+    /// Panics if the file cannot be read or is not valid TOML.
+    fn read_config_file(&self, relative_path: &str) -> Table {
+        self.read_string(relative_path).parse().unwrap_or_else(|e| {
+            panic!(
+                "Failed to parse TOML file at {}: {e}",
+                self.path.join(relative_path).display()
+            )
+        })
+    }
+}
+
+/// Merges `overrides` into `base`: tables are merged recursively,
+/// all other values are replaced.
+fn merge(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(table)) => merge(base_table, table),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Returns the table of the environment variables with the given prefix.
+/// Keys are lower-cased, and double underscores separate nested keys.
+fn env_table(prefix: &str, vars: impl Iterator<Item = (String, String)>) -> Table {
+    let mut table = Table::new();
+    for (name, raw_value) in vars {
+        let Some(key) = name.strip_prefix(prefix) else {
+            continue;
+        };
+        let key = key.to_lowercase();
+        let mut keys = key.split("__").collect::<Vec<_>>();
+        let last = keys.pop().unwrap_or_default();
+        let mut nested = Table::new();
+        nested.insert(last.to_string(), parse_env_value(&raw_value));
+        for key in keys.into_iter().rev() {
+            let mut parent = Table::new();
+            parent.insert(key.to_string(), Value::Table(nested));
+            nested = parent;
+        }
+        merge(&mut table, nested);
+    }
+    table
+}
+
+/// Parses the value of an environment variable as a TOML value,
+/// falling back to a string.
+fn parse_env_value(raw_value: &str) -> Value {
+    format!("value = {raw_value}")
+        .parse::<Table>()
+        .ok()
+        .filter(|table| table.len() == 1)
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(raw_value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::Deserialize;
+    use tempfile::tempdir;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        name: String,
+        server: Server,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    #[test]
+    fn load_config() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string(
+            "conv_wd_load_config_test.toml",
+            "name = \"base\"\n[server]\nhost = \"localhost\"\nport = 80\n",
+        );
+        directory.write_string(
+            "conv_wd_load_config_test.local.toml",
+            "[server]\nport = 8080\n",
+        );
+
+        let config: Config = directory.load_config("conv_wd_load_config_test");
+
+        assert_eq!(
+            config,
+            Config {
+                name: "base".to_string(),
+                server: Server {
+                    host: "localhost".to_string(),
+                    port: 8080,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn env_table() {
+        let vars = [
+            ("APP_NAME", "from env"),
+            ("APP_SERVER__PORT", "9090"),
+            ("APP_SERVER__TLS", "true"),
+            ("OTHER_NAME", "ignored"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+
+        let table = super::env_table("APP_", vars.into_iter());
+
+        assert_eq!(
+            table.to_string(),
+            "name = \"from env\"\n\n[server]\nport = 9090\ntls = true\n"
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let mut base = "a = 1\n[t]\nb = 2\nc = 3\n".parse::<Table>().unwrap();
+
+        super::merge(&mut base, "a = \"x\"\n[t]\nc = 4\n".parse().unwrap());
+
+        assert_eq!(base.to_string(), "a = \"x\"\n\n[t]\nb = 2\nc = 4\n");
+    }
+}
@@ -1,37 +1,319 @@
 use super::*;
 
+use crate::{Config, Error, Result};
+use std::ffi::{OsStr, OsString};
 use std::path::Path;
 
 /// Constructors and factory methods.
 impl Directory {
     /// Creates a new Directory instance with the given path.
     /// The directory is also created on the file system if it does not exist.
+    /// The process-wide defaults (see `conv_wd::config`) are applied.
     /// Panics if the directory cannot be created.
     ///
     /// # Arguments
     /// * `path` - The path where the directory should be created.
     pub fn create<P: AsRef<Path>>(path: P) -> Self {
-        let dir = Self {
+        Self::new_unchecked(path).apply_config(crate::config())
+    }
+
+    /// Takes ownership of an existing directory at the given path.
+    /// Like any temporary directory, it is removed from the file system
+    /// (including all of its contents) when the instance is dropped,
+    /// which is useful for cleaning up leftover state from previous runs.
+    /// Unlike `create`, this never creates the directory, and the directory is removed
+    /// on drop even if the process-wide configuration keeps new directories.
+    /// Panics if the path does not point to an existing directory.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the existing directory.
+    pub fn adopt<P: AsRef<Path>>(path: P) -> Self {
+        Self::adopt_with_config(path.as_ref(), crate::config())
+    }
+
+    /// Takes ownership of the existing directory at the given path like `adopt`,
+    /// applying the given configuration except for `Config::keep`.
+    /// Panics if the path does not point to an existing directory.
+    fn adopt_with_config(path: &Path, config: &Config) -> Self {
+        if !path.is_dir() {
+            panic!(
+                "Cannot adopt {}: the path is not an existing directory",
+                path.display()
+            );
+        }
+        Self::new_unchecked(path).apply_config(&Config {
+            keep: false,
+            ..*config
+        })
+    }
+
+    /// Creates a new Directory instance with the given path like `create`,
+    /// but fails with `Error::DirectoryExists` if the directory already exists
+    /// instead of adopting it. This guarantees that the caller is the sole creator
+    /// of the directory, since checking and creating is a single atomic operation.
+    /// Missing parent directories are created.
+    ///
+    /// # Arguments
+    /// * `path` - The path where the directory should be created.
+    pub fn create_new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let created_base = util::first_missing_ancestor(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| Error::io(parent, e))?;
+        }
+        std::fs::create_dir(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => Error::DirectoryExists(path.to_path_buf()),
+            _ => Error::io(path, e),
+        })?;
+        let mut directory = Self::create(path);
+        directory.created_base = created_base;
+        Ok(directory)
+    }
+
+    /// Creates a new Directory instance at the given path, or at the first free sibling
+    /// path `<name>-1`, `<name>-2`, ... if the directory already exists.
+    /// The chosen path is available via `path()`.
+    /// Each candidate is created with `create_new`, so parallel jobs using the same
+    /// base path get distinct directories without further coordination.
+    /// Panics if the path has no file name or if a directory cannot be created.
+    ///
+    /// # Arguments
+    /// * `path` - The preferred path of the directory.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let base = Directory::cargo_target_subdir("create_unique_example");
+    /// let first = Directory::create_unique(base.path().join("run"));
+    /// let second = Directory::create_unique(base.path().join("run"));
+    ///
+    /// assert_eq!(first.path(), base.path().join("run"));
+    /// assert_eq!(second.path(), base.path().join("run-1"));
+    /// ```
+    pub fn create_unique<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        let name = path.file_name().unwrap_or_else(|| {
+            panic!(
+                "Cannot create a unique directory for {}: the path has no file name",
+                path.display()
+            )
+        });
+        let mut candidate = path.to_path_buf();
+        for suffix in 1.. {
+            match Self::create_new(&candidate) {
+                Ok(dir) => return dir,
+                Err(Error::DirectoryExists(_)) => {
+                    let mut unique_name = name.to_os_string();
+                    unique_name.push(format!("-{suffix}"));
+                    candidate.set_file_name(unique_name);
+                }
+                Err(e) => panic!("Failed to create directory at {}: {e}", candidate.display()),
+            }
+        }
+        unreachable!("Ran out of unique names for {}", path.display())
+    }
+
+    /// Creates a new Directory instance with the given path like `create`,
+    /// but first removes a file that exists at the path.
+    /// This is intended for regeneration workflows where a stale file is expected
+    /// in place of the directory. Existing directories are adopted as with `create`.
+    /// Panics if the file cannot be removed or the directory cannot be created.
+    ///
+    /// # Arguments
+    /// * `path` - The path where the directory should be created.
+    pub fn create_replacing<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        if path
+            .symlink_metadata()
+            .is_ok_and(|metadata| !metadata.is_dir())
+        {
+            std::fs::remove_file(path)
+                .unwrap_or_else(|e| panic!("Failed to remove file at {}: {e}", path.display()));
+        }
+        Self::create(path)
+    }
+
+    /// Creates a new Directory instance like `create` at the path given by
+    /// the environment variable `name`, e.g. an output directory configured by CI.
+    /// Fails with `Error::EnvVarNotSet` if the variable is not set or empty,
+    /// and with `Error::Io` if the directory cannot be created.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the environment variable that contains the path.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::{Directory, Error};
+    ///
+    /// assert!(matches!(
+    ///     Directory::from_env("FROM_ENV_EXAMPLE_MISSING"),
+    ///     Err(Error::EnvVarNotSet(name)) if name == "FROM_ENV_EXAMPLE_MISSING"
+    /// ));
+    /// ```
+    pub fn from_env<K: AsRef<OsStr>>(name: K) -> Result<Self> {
+        let name = name.as_ref();
+        Self::from_env_value(name, std::env::var_os(name))
+    }
+
+    /// Creates a new Directory instance like `from_env`, but at the given fallback path
+    /// if the environment variable `name` is not set or empty.
+    /// Panics if the directory cannot be created.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the environment variable that contains the path.
+    /// * `fallback` - The path of the directory if the variable is not set or empty.
+    pub fn from_env_or<K: AsRef<OsStr>, P: AsRef<Path>>(name: K, fallback: P) -> Self {
+        match Self::from_env(name) {
+            Ok(directory) => directory,
+            Err(Error::EnvVarNotSet(_)) => Self::create(fallback),
+            Err(e) => panic!("Failed to create directory: {e}"),
+        }
+    }
+
+    /// Creates a new Directory instance for the given value of the environment
+    /// variable `name`, see `from_env`.
+    fn from_env_value(name: &OsStr, value: Option<OsString>) -> Result<Self> {
+        let path = value
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+            .ok_or_else(|| Error::EnvVarNotSet(name.to_string_lossy().into_owned()))?;
+        let created_base = util::first_missing_ancestor(&path);
+        std::fs::create_dir_all(&path).map_err(|e| Error::io(&path, e))?;
+        let mut directory = Self::create(&path);
+        directory.created_base = created_base;
+        Ok(directory)
+    }
+
+    /// Creates a new temporary Directory instance with default settings
+    /// without touching the file system.
+    pub(super) fn new_unchecked<P: AsRef<Path>>(path: P) -> Self {
+        Self {
             path: path.as_ref().to_path_buf(),
             keep_on_drop: false,
-        };
+            keep_on_panic: false,
+            remove_retry: RetryPolicy::default(),
+            durable: false,
+            marker: false,
+            nfc_names: false,
+            portable_names: false,
+            max_file_size: None,
+            max_depth: None,
+            #[cfg(feature = "glob")]
+            filter: None,
+            preserve_metadata: false,
+            newline: None,
+            bom: false,
+            stats: None,
+            created_base: None,
+            remove_base_if_created: false,
+            #[cfg(feature = "ignore")]
+            respect_ignore_files: false,
+            #[cfg(feature = "cap-std")]
+            handle: None,
+            #[cfg(feature = "indicatif")]
+            progress: None,
+            #[cfg(feature = "tokio")]
+            background_drop: false,
+        }
+    }
+
+    /// Applies the given configuration to a new instance and creates the directory.
+    /// Panics if the directory cannot be created or configured.
+    fn apply_config(mut self, config: &Config) -> Self {
+        self.keep_on_drop = config.keep;
+        self.keep_on_panic = config.keep_on_panic;
+        self.remove_retry = config.remove_retry;
+        self.max_file_size = config.max_file_size;
+        self.max_depth = config.max_depth;
+        self.created_base = util::first_missing_ancestor(&self.path);
+        self.ensure_exists();
+        #[cfg(unix)]
+        if let Some(mode) = config.mode {
+            use std::os::unix::fs::PermissionsExt;
 
-        dir.ensure_exists();
-        dir
+            std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(mode))
+                .unwrap_or_else(|e| {
+                    panic!(
+                        "Failed to set permissions of directory at {}: {e}",
+                        self.path.display()
+                    )
+                });
+        }
+        #[cfg(feature = "ctrlc")]
+        if !self.is_kept() {
+            crate::signal::register(&self.path);
+        }
+        if config.gitignore {
+            self.write_gitignore();
+        }
+        self
     }
 
     /// Creates a new persistent Directory instance from self.
     /// The directory will not be removed when the instance is dropped.
     pub fn keep(mut self) -> Self {
+        #[cfg(feature = "ctrlc")]
+        if !self.keep_on_drop {
+            crate::signal::unregister(&self.path);
+        }
         self.keep_on_drop = true;
+        self.update_marker();
+        self
+    }
+
+    /// Creates a new Directory instance from self that is kept if it is dropped
+    /// while the current thread is panicking, e.g. because a test assertion failed.
+    /// The path of the retained directory is printed to stderr, so the working
+    /// directory of a failed test can be inspected.
+    pub fn keep_on_panic(mut self) -> Self {
+        self.keep_on_panic = true;
+        self.update_marker();
         self
     }
 
     /// Creates a new Directory instance from self.
     /// Removes all content on creation.
-    pub fn clean(self) -> Self {
-        self.remove();
-        self.ensure_exists();
+    /// With the `indicatif` feature, removal drives the progress bar set via `with_progress`.
+    /// With the `glob` feature and `with_filter`, only the selected entries are removed,
+    /// and directories only if they become empty.
+    /// Panics if the tree is nested deeper than the maximum depth (see `with_max_depth`),
+    /// in which case nothing is removed.
+    pub fn clean(#[cfg_attr(not(feature = "cap-std"), allow(unused_mut))] mut self) -> Self {
+        #[cfg(feature = "glob")]
+        let filtered = self.filter.is_some();
+        #[cfg(not(feature = "glob"))]
+        let filtered = false;
+        let entries = (self.max_depth.is_some() || filtered).then(|| {
+            self.walk_tree(&self.path).unwrap_or_else(|e| {
+                panic!("Failed to clean directory at {}: {e}", self.path.display())
+            })
+        });
+        match entries {
+            #[cfg(feature = "glob")]
+            Some(entries) if filtered => self.remove_entries(&entries),
+            _ => {
+                #[cfg(feature = "indicatif")]
+                self.remove_files_with_progress();
+                self.remove();
+                self.ensure_exists();
+            }
+        }
+        #[cfg(feature = "cap-std")]
+        if self.handle.is_some() {
+            self.handle = Some(self.open_handle());
+        }
+        self.update_marker();
+        self
+    }
+
+    /// Creates a new Directory instance from self that uses the given policy
+    /// to retry failed removals (on drop and in `clean`).
+    ///
+    /// # Arguments
+    /// * `policy` - The retry policy to use for remove operations.
+    pub fn with_remove_retry(mut self, policy: RetryPolicy) -> Self {
+        self.remove_retry = policy;
         self
     }
 
@@ -47,6 +329,7 @@ impl Directory {
 mod tests {
     use super::*;
 
+    use std::time::Duration;
     use tempfile::tempdir;
 
     #[test]
@@ -64,6 +347,212 @@ mod tests {
         assert!(!dir_path.exists());
     }
 
+    #[test]
+    fn apply_config() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+        let config = Config {
+            keep: true,
+            gitignore: true,
+            mode: Some(0o700),
+            remove_retry: RetryPolicy::none(),
+            ..Config::default()
+        };
+
+        let directory = Directory::new_unchecked(&dir_path).apply_config(&config);
+
+        assert!(directory.keep_on_drop);
+        assert_eq!(directory.remove_retry, RetryPolicy::none());
+        assert!(dir_path.join(".gitignore").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&dir_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+        drop(directory);
+        assert!(dir_path.exists());
+    }
+
+    #[test]
+    fn adopt() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("leftover_dir");
+        std::fs::create_dir_all(dir_path.join("subdir")).unwrap();
+        std::fs::write(dir_path.join("subdir/file.txt"), b"Leftover content").unwrap();
+
+        {
+            let directory = Directory::adopt(&dir_path);
+            assert_eq!(directory.path, dir_path);
+            assert!(dir_path.join("subdir/file.txt").exists());
+        }
+        assert!(!dir_path.exists());
+    }
+
+    #[test]
+    fn adopt_ignores_keep_config() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("leftover_dir");
+        std::fs::create_dir(&dir_path).unwrap();
+        let config = Config {
+            keep: true,
+            max_file_size: Some(10),
+            ..Config::default()
+        };
+
+        {
+            let directory = Directory::adopt_with_config(&dir_path, &config);
+            assert!(!directory.keep_on_drop);
+            assert_eq!(directory.max_file_size, Some(10));
+        }
+        assert!(!dir_path.exists());
+    }
+
+    #[test]
+    #[should_panic(expected = "not an existing directory")]
+    fn adopt_missing_directory() {
+        let temp_dir = tempdir().unwrap();
+
+        Directory::adopt(temp_dir.path().join("missing_dir"));
+    }
+
+    #[test]
+    fn create_new() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("parent/test_dir");
+
+        {
+            let directory = Directory::create_new(&dir_path).unwrap();
+
+            assert!(directory.path.is_dir());
+            assert!(matches!(
+                Directory::create_new(&dir_path),
+                Err(Error::DirectoryExists(path)) if path == dir_path
+            ));
+            assert!(dir_path.exists());
+        }
+        assert!(!dir_path.exists());
+    }
+
+    #[test]
+    fn create_unique() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("run");
+
+        let first = Directory::create_unique(&dir_path);
+        let second = Directory::create_unique(&dir_path);
+        let third = Directory::create_unique(&dir_path);
+
+        assert_eq!(first.path, dir_path);
+        assert_eq!(second.path, temp_dir.path().join("run-1"));
+        assert_eq!(third.path, temp_dir.path().join("run-2"));
+        assert!(third.path.is_dir());
+    }
+
+    #[test]
+    fn create_unique_in_parallel() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("run");
+
+        let directories: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| Directory::create_unique(&dir_path)))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        let mut paths: Vec<_> = directories.iter().map(|d| d.path_buf()).collect();
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), 8);
+    }
+
+    #[test]
+    fn create_replacing() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+        std::fs::write(&dir_path, b"Stale file").unwrap();
+
+        let directory = Directory::create_replacing(&dir_path);
+
+        assert!(directory.path.is_dir());
+        assert_eq!(directory.path, dir_path);
+    }
+
+    #[test]
+    fn create_replacing_existing_directory() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+        std::fs::create_dir_all(&dir_path).unwrap();
+        std::fs::write(dir_path.join("file.txt"), b"Existing content").unwrap();
+
+        let directory = Directory::create_replacing(&dir_path);
+
+        assert!(directory.path.is_dir());
+        assert!(dir_path.join("file.txt").exists());
+    }
+
+    #[test]
+    fn from_env() {
+        assert!(matches!(
+            Directory::from_env("CONV_WD_TEST_FROM_ENV_MISSING"),
+            Err(Error::EnvVarNotSet(name)) if name == "CONV_WD_TEST_FROM_ENV_MISSING"
+        ));
+    }
+
+    #[test]
+    fn from_env_value() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("a/test_dir");
+        let name = OsStr::new("OUT_DIR");
+
+        let directory = Directory::from_env_value(name, Some(dir_path.clone().into())).unwrap();
+
+        assert!(directory.path.is_dir());
+        assert_eq!(directory.path, dir_path);
+        assert_eq!(directory.created_base, Some(temp_dir.path().join("a")));
+        assert!(matches!(
+            Directory::from_env_value(name, Some(OsString::new())),
+            Err(Error::EnvVarNotSet(_))
+        ));
+        assert!(matches!(
+            Directory::from_env_value(name, None),
+            Err(Error::EnvVarNotSet(_))
+        ));
+    }
+
+    #[test]
+    fn from_env_value_fails_for_existing_file() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("file.txt");
+        std::fs::write(&file_path, b"content").unwrap();
+
+        let result = Directory::from_env_value(OsStr::new("OUT_DIR"), Some(file_path.into()));
+
+        assert!(matches!(result, Err(Error::Io { .. })));
+    }
+
+    #[test]
+    fn from_env_or() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("fallback_dir");
+
+        let directory = Directory::from_env_or("CONV_WD_TEST_FROM_ENV_OR_MISSING", &dir_path);
+
+        assert!(directory.path.is_dir());
+        assert_eq!(directory.path, dir_path);
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to create directory")]
+    fn create_fails_for_existing_file() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+        std::fs::write(&dir_path, b"Stale file").unwrap();
+
+        Directory::create(&dir_path);
+    }
+
     #[test]
     fn keep() {
         let temp_dir = tempdir().unwrap();
@@ -79,6 +568,34 @@ mod tests {
         assert!(dir_path.is_dir());
     }
 
+    #[test]
+    fn keep_on_panic() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("panic_dir");
+
+        let thread_dir_path = dir_path.clone();
+        let result = std::thread::spawn(move || {
+            let _directory = Directory::create(&thread_dir_path).keep_on_panic();
+            panic!("Simulated test failure");
+        })
+        .join();
+
+        assert!(result.is_err());
+        assert!(dir_path.exists());
+        assert!(dir_path.is_dir());
+    }
+
+    #[test]
+    fn keep_on_panic_without_panic() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("panic_dir");
+
+        {
+            let _directory = Directory::create(&dir_path).keep_on_panic();
+        }
+        assert!(!dir_path.exists());
+    }
+
     #[test]
     fn clean() {
         let temp_dir = tempdir().unwrap();
@@ -96,6 +613,19 @@ mod tests {
         assert!(std::fs::read_dir(&dir_path).unwrap().next().is_none());
     }
 
+    #[test]
+    fn with_remove_retry() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("temp_dir");
+        let policy = RetryPolicy::new(3, Duration::from_millis(5), Duration::from_secs(1));
+
+        {
+            let directory = Directory::create(&dir_path).with_remove_retry(policy);
+            assert_eq!(directory.remove_retry, policy);
+        }
+        assert!(!dir_path.exists());
+    }
+
     #[test]
     fn with_gitignore() {
         let temp_dir = tempdir().unwrap();
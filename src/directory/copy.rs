@@ -0,0 +1,211 @@
+//! Kernel-accelerated file copies.
+//!
+//! Large artifacts are copied without a round trip of their data through user space
+//! where the platform supports it, and in chunks, so progress can be reported while
+//! a single large file is copied.
+
+#[cfg(any(target_os = "linux", test))]
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// Size of the chunks in which files are copied on Linux.
+#[cfg(any(target_os = "linux", test))]
+const COPY_CHUNK_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Copies the content and permissions of a file like `std::fs::copy`, calling `on_chunk`
+/// with the number of bytes copied after each chunk.
+///
+/// On Linux, the data is copied with `copy_file_range`, which stays in the kernel (and
+/// on the server for NFS and SMB), with a fallback to `read`/`write` where it is not
+/// supported, e.g. across file systems on older kernels.
+/// On Windows, the file is copied with `CopyFileExW`, which reports progress through
+/// a callback. On other platforms, `std::fs::copy` is used, which uses the native copy
+/// functions of the platform but reports progress only once at the end.
+pub(super) fn copy_file(
+    source: &Path,
+    target: &Path,
+    on_chunk: &mut dyn FnMut(u64),
+) -> io::Result<()> {
+    #[cfg(target_os = "linux")]
+    return copy_file_range(source, target, on_chunk);
+    #[cfg(windows)]
+    return copy_file_ex(source, target, on_chunk);
+    #[cfg(not(any(target_os = "linux", windows)))]
+    {
+        on_chunk(std::fs::copy(source, target)?);
+        Ok(())
+    }
+}
+
+/// Copies a file with `copy_file_range`, falling back to copying in user space.
+#[cfg(target_os = "linux")]
+fn copy_file_range(source: &Path, target: &Path, on_chunk: &mut dyn FnMut(u64)) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let mut reader = File::open(source)?;
+    let permissions = reader.metadata()?.permissions();
+    let mut writer = File::create(target)?;
+    writer.set_permissions(permissions)?;
+    loop {
+        // SAFETY: Both file descriptors are open, and null offsets use the file positions.
+        let copied = unsafe {
+            libc::copy_file_range(
+                reader.as_raw_fd(),
+                std::ptr::null_mut(),
+                writer.as_raw_fd(),
+                std::ptr::null_mut(),
+                COPY_CHUNK_SIZE as usize,
+                0,
+            )
+        };
+        match copied {
+            // Some file systems report the end of the file for content they cannot copy in
+            // the kernel, so the rest is copied in user space, which returns at the real end.
+            0 => break,
+            copied if copied > 0 => on_chunk(copied as u64),
+            _ => {
+                let e = io::Error::last_os_error();
+                match e.raw_os_error() {
+                    Some(
+                        libc::EXDEV | libc::ENOSYS | libc::EINVAL | libc::EOPNOTSUPP | libc::EPERM,
+                    ) => break,
+                    _ => return Err(e),
+                }
+            }
+        }
+    }
+    copy_chunks(&mut reader, &mut writer, on_chunk)
+}
+
+/// Copies a file with `CopyFileExW`, reporting its progress.
+#[cfg(windows)]
+fn copy_file_ex(source: &Path, target: &Path, on_chunk: &mut dyn FnMut(u64)) -> io::Result<()> {
+    use std::ffi::c_void;
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::HANDLE;
+    use windows_sys::Win32::Storage::FileSystem::{
+        COPYPROGRESSROUTINE_PROGRESS, CopyFileExW, PROGRESS_CONTINUE,
+    };
+
+    /// State shared with the progress callback.
+    struct Progress<'a> {
+        on_chunk: &'a mut dyn FnMut(u64),
+        reported: u64,
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    unsafe extern "system" fn progress_routine(
+        _total_size: i64,
+        transferred: i64,
+        _stream_size: i64,
+        _stream_transferred: i64,
+        _stream: u32,
+        _reason: u32,
+        _source: HANDLE,
+        _target: HANDLE,
+        data: *const c_void,
+    ) -> COPYPROGRESSROUTINE_PROGRESS {
+        // SAFETY: `data` points to the `Progress` passed to `CopyFileExW` below,
+        // which outlives the copy and is not accessed elsewhere in the meantime.
+        let progress = unsafe { &mut *(data as *mut Progress) };
+        let transferred = transferred as u64;
+        if transferred > progress.reported {
+            (progress.on_chunk)(transferred - progress.reported);
+            progress.reported = transferred;
+        }
+        PROGRESS_CONTINUE
+    }
+
+    let wide = |path: &Path| {
+        path.as_os_str()
+            .encode_wide()
+            .chain(Some(0))
+            .collect::<Vec<u16>>()
+    };
+    let (source, target) = (wide(source), wide(target));
+    let mut progress = Progress {
+        on_chunk,
+        reported: 0,
+    };
+    // SAFETY: The paths are null-terminated, and `progress` outlives the call.
+    let copied = unsafe {
+        CopyFileExW(
+            source.as_ptr(),
+            target.as_ptr(),
+            Some(progress_routine),
+            &mut progress as *mut Progress as *const c_void,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if copied == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Copies the rest of `reader` to `writer` through user space in chunks.
+#[cfg(any(target_os = "linux", test))]
+fn copy_chunks(
+    reader: &mut File,
+    writer: &mut File,
+    on_chunk: &mut dyn FnMut(u64),
+) -> io::Result<()> {
+    use std::io::Read;
+
+    loop {
+        let copied = io::copy(&mut reader.by_ref().take(COPY_CHUNK_SIZE), writer)?;
+        if copied == 0 {
+            return Ok(());
+        }
+        on_chunk(copied);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn copy_file() {
+        let temp_dir = tempdir().unwrap();
+        let source = temp_dir.path().join("source.bin");
+        let target = temp_dir.path().join("target.bin");
+        let content = (0..3 * 1024 * 1024)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<_>>();
+        std::fs::write(&source, &content).unwrap();
+        std::fs::write(&target, "old content that is replaced").unwrap();
+
+        let mut reported = 0;
+        super::copy_file(&source, &target, &mut |bytes| reported += bytes).unwrap();
+
+        assert_eq!(std::fs::read(&target).unwrap(), content);
+        assert_eq!(reported, content.len() as u64);
+    }
+
+    #[test]
+    fn copy_chunks() {
+        let temp_dir = tempdir().unwrap();
+        let source = temp_dir.path().join("source.bin");
+        let target = temp_dir.path().join("target.bin");
+        std::fs::write(&source, vec![1; COPY_CHUNK_SIZE as usize + 10]).unwrap();
+
+        let mut chunks = Vec::new();
+        super::copy_chunks(
+            &mut File::open(&source).unwrap(),
+            &mut File::create(&target).unwrap(),
+            &mut |bytes| chunks.push(bytes),
+        )
+        .unwrap();
+
+        assert_eq!(chunks, [COPY_CHUNK_SIZE, 10]);
+        assert_eq!(
+            std::fs::read(&source).unwrap(),
+            std::fs::read(&target).unwrap()
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use super::*;
+
+use ::csv::Terminator;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+use crate::util::assert_relative_path;
+use crate::{Error, Result};
+
+/// Methods for writing and reading tabular data as CSV files with a header row,
+/// e.g. for round-trip tests of tabular artifacts.
+impl Directory {
+    /// Writes serde-serializable records as CSV to a file at the given path within the
+    /// directory. The header row is derived from the field names of the records.
+    /// Rows end with the line endings set by `with_newline`, if any, while line breaks
+    /// within quoted fields are written as they are. The byte order mark set by `with_bom`
+    /// is applied.
+    /// Adds the `.csv` extension to the file name if not already present (replaces existing extension).
+    /// Panics if the path is absolute or if the serialization or write operation fails.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    /// * `records` - The records to write, one per row.
+    pub fn write_csv<P: AsRef<Path>, T: Serialize>(&self, relative_path: P, records: &[T]) {
+        let content = to_csv(relative_path.as_ref(), records, self.csv_terminator())
+            .unwrap_or_else(|e| panic!("{e}"));
+        self.write_bytes(
+            relative_path.as_ref().with_extension("csv"),
+            self.with_bom_prefix(bom::UTF8_BOM, content.into_bytes()),
+        );
+    }
+
+    /// Appends a serde-serializable record as a CSV row to a file at the given path within
+    /// the directory, e.g. to accumulate result rows of a long-running process.
+    /// If the file does not exist or is empty, it is created with a header row first.
+    /// The file is locked while the row is appended (see `lock_file`), so concurrent
+    /// writers neither interleave rows nor write the header twice.
+    /// Like in `write_csv`, rows end with the line endings set by `with_newline`, if any,
+    /// and a new file starts with the byte order mark set by `with_bom`.
+    /// Unlike `write_csv`, the path is used as is.
+    /// Panics if the path is absolute, if the file would exceed the maximum file size
+    /// (see `with_max_file_size`), or if the serialization or write operation fails.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    /// * `record` - The record to append.
+    pub fn append_csv_record<P: AsRef<Path>, T: Serialize>(&self, relative_path: P, record: &T) {
+        self.append_bytes(relative_path.as_ref(), |is_empty| {
+            let row = to_csv_row(
+                relative_path.as_ref(),
+                record,
+                is_empty,
+                self.csv_terminator(),
+            )
+            .unwrap_or_else(|e| panic!("{e}"))
+            .into_bytes();
+            if is_empty {
+                self.with_bom_prefix(bom::UTF8_BOM, row)
+            } else {
+                row
+            }
+        });
+    }
+
+    /// Reads the records of a CSV file with a header row from the given path within the
+    /// directory. Columns are matched to fields by the names in the header row.
+    /// Panics if the path is absolute, if the file cannot be read,
+    /// or if a record cannot be deserialized.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    /// struct Row {
+    ///     name: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// let dir = Directory::cargo_target_subdir("read_csv_example");
+    /// let rows = vec![Row { name: "a".into(), value: 1 }];
+    /// dir.write_csv("rows.csv", &rows);
+    /// assert_eq!(dir.read_csv::<_, Row>("rows.csv"), rows);
+    /// ```
+    pub fn read_csv<P: AsRef<Path>, T: DeserializeOwned>(&self, relative_path: P) -> Vec<T> {
+        assert_relative_path(relative_path.as_ref());
+        let file_path = self.path.join(relative_path.as_ref());
+        let content = self.read_string(relative_path.as_ref());
+        ::csv::Reader::from_reader(content.as_bytes())
+            .deserialize()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap_or_else(|e| {
+                panic!(
+                    "Failed to deserialize CSV records from file at {}: {e}",
+                    file_path.display()
+                )
+            })
+    }
+
+    /// Returns the row terminator for the line endings set by `with_newline`, if any.
+    /// Line breaks within quoted fields are part of the data and are never converted.
+    fn csv_terminator(&self) -> Terminator {
+        match self.newline.map(Newline::as_str) {
+            Some("\r\n") => Terminator::CRLF,
+            _ => Terminator::Any(b'\n'),
+        }
+    }
+}
+
+/// Serializes records as CSV with a header row for the file at the given path.
+fn to_csv<T: Serialize>(
+    relative_path: &Path,
+    records: &[T],
+    terminator: Terminator,
+) -> Result<String> {
+    serialize_csv(relative_path, records, true, terminator)
+}
+
+/// Serializes a single record as a CSV row for the file at the given path,
+/// preceded by a header row if requested.
+fn to_csv_row<T: Serialize>(
+    relative_path: &Path,
+    record: &T,
+    has_headers: bool,
+    terminator: Terminator,
+) -> Result<String> {
+    serialize_csv(
+        relative_path,
+        std::slice::from_ref(record),
+        has_headers,
+        terminator,
+    )
+}
+
+/// Serializes records as CSV for the file at the given path, with rows ending in the
+/// given terminator and a header row derived from the first record if requested.
+fn serialize_csv<T: Serialize>(
+    relative_path: &Path,
+    records: &[T],
+    has_headers: bool,
+    terminator: Terminator,
+) -> Result<String> {
+    let mut writer = ::csv::WriterBuilder::new()
+        .has_headers(has_headers)
+        .terminator(terminator)
+        .from_writer(Vec::new());
+    let error = |message: String| Error::Serialization {
+        format: "CSV",
+        path: relative_path.to_path_buf(),
+        message,
+    };
+    for record in records {
+        writer.serialize(record).map_err(|e| error(e.to_string()))?;
+    }
+    let bytes = writer.into_inner().map_err(|e| error(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| error(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::Deserialize;
+    use tempfile::tempdir;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Row {
+        name: String,
+        value: u32,
+    }
+
+    fn rows() -> Vec<Row> {
+        vec![
+            Row {
+                name: "first".to_string(),
+                value: 1,
+            },
+            Row {
+                name: "second, with comma".to_string(),
+                value: 2,
+            },
+        ]
+    }
+
+    #[test]
+    fn write_csv() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_csv("rows", &rows());
+
+        let content = std::fs::read_to_string(directory.path().join("rows.csv")).unwrap();
+        assert_eq!(content, "name,value\nfirst,1\n\"second, with comma\",2\n");
+    }
+
+    #[test]
+    fn write_csv_with_newline() {
+        let temp_dir = tempdir().unwrap();
+        let directory =
+            Directory::create(temp_dir.path().join("test_dir")).with_newline(Newline::CrLf);
+        let rows = vec![Row {
+            name: "first line\nsecond line".to_string(),
+            value: 1,
+        }];
+
+        directory.write_csv("rows", &rows);
+
+        let content = std::fs::read_to_string(directory.path().join("rows.csv")).unwrap();
+        assert_eq!(content, "name,value\r\n\"first line\nsecond line\",1\r\n");
+        assert_eq!(directory.read_csv::<_, Row>("rows.csv"), rows);
+    }
+
+    #[test]
+    fn append_csv_record() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        for row in rows() {
+            directory.append_csv_record("rows.csv", &row);
+        }
+
+        let content = std::fs::read_to_string(directory.path().join("rows.csv")).unwrap();
+        assert_eq!(content, "name,value\nfirst,1\n\"second, with comma\",2\n");
+    }
+
+    #[test]
+    fn append_csv_record_with_bom_and_stats() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"))
+            .with_bom()
+            .with_stats();
+
+        for row in rows() {
+            directory.append_csv_record("rows.csv", &row);
+        }
+
+        let content = std::fs::read(directory.path().join("rows.csv")).unwrap();
+        assert!(content.starts_with(bom::UTF8_BOM));
+        assert_eq!(directory.read_csv::<_, Row>("rows.csv"), rows());
+        let stats = directory.stats();
+        assert_eq!(stats.files_written, 2);
+        assert_eq!(stats.bytes_written, content.len() as u64);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the maximum file size")]
+    fn append_csv_record_max_file_size() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_max_file_size(32);
+
+        for row in rows() {
+            directory.append_csv_record("rows.csv", &row);
+        }
+    }
+
+    #[test]
+    fn read_csv() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string(
+            "rows.csv",
+            "value,name\r\n1,first\r\n2,\"second, with comma\"\r\n",
+        );
+
+        assert_eq!(directory.read_csv::<_, Row>("rows.csv"), rows());
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to deserialize CSV records")]
+    fn read_csv_invalid_record() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("rows.csv", "name,value\nfirst,not a number\n");
+
+        directory.read_csv::<_, Row>("rows.csv");
+    }
+}
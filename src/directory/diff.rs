@@ -0,0 +1,381 @@
+use super::*;
+
+use similar::{ChangeTag, TextDiff};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::util::assert_relative_path;
+
+/// Structured difference between two files, as returned by `Directory::diff_file`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FileDiff {
+    /// Both files have the same content.
+    Equal,
+    /// Both files are valid UTF-8 text and differ in the given lines.
+    Text(Vec<LineChange>),
+    /// At least one file is binary, and the files differ in the given byte ranges.
+    /// Bytes beyond the end of the shorter file count as different.
+    Binary {
+        /// Ranges of byte offsets at which the files differ.
+        ranges: Vec<Range<usize>>,
+        /// Length of the file in the directory.
+        len: usize,
+        /// Length of the other file.
+        other_len: usize,
+    },
+}
+
+/// Differences between two directory trees, as returned by `Directory::diff_dir`.
+/// All paths are relative to the roots of the trees and sorted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DirDiff {
+    /// Entries that only exist in the directory.
+    pub added: Vec<PathBuf>,
+    /// Entries that only exist in the other tree.
+    pub removed: Vec<PathBuf>,
+    /// Files that exist in both trees but differ in type, size, or content.
+    pub changed: Vec<PathBuf>,
+}
+
+impl DirDiff {
+    /// Returns whether the trees are equal.
+    pub fn is_equal(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for DirDiff {
+    /// Formats the diff for failure messages: one line per entry, prefixed with
+    /// `+` (added), `-` (removed), or `~` (changed).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (prefix, paths) in [
+            ("+", &self.added),
+            ("-", &self.removed),
+            ("~", &self.changed),
+        ] {
+            for path in paths {
+                writeln!(f, "{prefix} {}", path.display())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A changed line in a [`FileDiff::Text`], with its 1-based line number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LineChange {
+    /// The line only exists in the other file, at the given line.
+    Removed { line: usize, text: String },
+    /// The line only exists in the file in the directory, at the given line.
+    Added { line: usize, text: String },
+}
+
+impl FileDiff {
+    /// Returns whether the files have the same content.
+    pub fn is_equal(&self) -> bool {
+        matches!(self, Self::Equal)
+    }
+}
+
+impl fmt::Display for FileDiff {
+    /// Formats the diff for failure messages: `-` for removed and `+` for added lines,
+    /// or the differing byte ranges for binary files.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Equal => writeln!(f, "files are equal"),
+            Self::Text(changes) => changes.iter().try_for_each(|change| match change {
+                LineChange::Removed { line, text } => writeln!(f, "-{line:>5}: {text}"),
+                LineChange::Added { line, text } => writeln!(f, "+{line:>5}: {text}"),
+            }),
+            Self::Binary {
+                ranges,
+                len,
+                other_len,
+            } => {
+                writeln!(f, "binary files differ ({len} vs. {other_len} bytes)")?;
+                ranges
+                    .iter()
+                    .try_for_each(|range| writeln!(f, "  bytes {}..{}", range.start, range.end))
+            }
+        }
+    }
+}
+
+/// Methods for comparing files and trees.
+impl Directory {
+    /// Compares the file at the given path within the directory with the file at `other_path`.
+    /// Lines (or bytes) that only exist in the other file are reported as removed,
+    /// so `other_path` is typically the expected output and the file in the directory
+    /// the actual output.
+    /// Panics if the path is absolute or if either file cannot be read.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    /// * `other_path` - The path of the file to compare against.
+    pub fn diff_file<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        relative_path: P,
+        other_path: Q,
+    ) -> FileDiff {
+        assert_relative_path(relative_path.as_ref());
+        let content = read_for_diff(&self.path.join(relative_path.as_ref()));
+        let other_content = read_for_diff(other_path.as_ref());
+        diff_contents(&content, &other_content)
+    }
+
+    /// Compares the tree of the directory with the tree at `other`, e.g. an expected
+    /// output fixture. Entries are compared by existence and type, and files of equal
+    /// size additionally by content. Symlinks are compared by their targets.
+    /// With `with_nfc_names`, entry names are compared in Unicode NFC.
+    /// Panics if either tree cannot be read.
+    ///
+    /// # Arguments
+    /// * `other` - The root of the tree to compare against, e.g. another `Directory`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let expected = Directory::cargo_target_subdir("diff_dir_example/expected").clean();
+    /// let actual = Directory::cargo_target_subdir("diff_dir_example/actual").clean();
+    /// expected.write_string("output.txt", "done");
+    /// actual.write_string("output.txt", "failed");
+    /// actual.write_string("extra.log", "");
+    ///
+    /// let diff = actual.diff_dir(&expected);
+    /// assert_eq!(diff.added, ["extra.log"].map(std::path::PathBuf::from));
+    /// assert_eq!(diff.changed, ["output.txt"].map(std::path::PathBuf::from));
+    /// ```
+    pub fn diff_dir<P: AsRef<Path>>(&self, other: P) -> DirDiff {
+        let other = other.as_ref();
+        let entries = self.walk_for_diff(&self.path);
+        let other_entries = self.walk_for_diff(other);
+        let mut diff = DirDiff::default();
+        for (name, entry) in &entries {
+            match other_entries.get(name) {
+                None => diff.added.push(name.clone()),
+                Some(other_entry) => {
+                    if !entries_equal(&self.path.join(entry), &other.join(other_entry)) {
+                        diff.changed.push(name.clone());
+                    }
+                }
+            }
+        }
+        diff.removed = other_entries
+            .into_keys()
+            .filter(|name| !entries.contains_key(name))
+            .collect();
+        diff
+    }
+
+    /// Walks a tree for a diff.
+    /// Returns the entries keyed by their (normalized, see `with_nfc_names`) names.
+    /// Panics if the tree cannot be read.
+    fn walk_for_diff(&self, root: &Path) -> BTreeMap<PathBuf, PathBuf> {
+        self.walk_tree(root)
+            .unwrap_or_else(|e| panic!("Failed to read directory tree at {}: {e}", root.display()))
+            .into_iter()
+            .map(|entry| (self.normalize_name(&entry).into_owned(), entry))
+            .collect()
+    }
+}
+
+/// Returns whether two entries have the same type and, for files, the same content,
+/// or for symlinks, the same target.
+/// Panics if an entry cannot be read.
+fn entries_equal(path: &Path, other: &Path) -> bool {
+    let metadata = symlink_metadata_for_diff(path);
+    let other_metadata = symlink_metadata_for_diff(other);
+    if metadata.file_type() != other_metadata.file_type() {
+        return false;
+    }
+    if metadata.is_dir() {
+        true
+    } else if metadata.is_symlink() {
+        std::fs::read_link(path).ok() == std::fs::read_link(other).ok()
+    } else {
+        metadata.len() == other_metadata.len() && read_for_diff(path) == read_for_diff(other)
+    }
+}
+
+/// Returns the metadata of an entry without following symlinks.
+/// Panics if the metadata cannot be read.
+fn symlink_metadata_for_diff(path: &Path) -> std::fs::Metadata {
+    std::fs::symlink_metadata(path)
+        .unwrap_or_else(|e| panic!("Failed to read metadata of {}: {e}", path.display()))
+}
+
+/// Reads a file for a diff.
+/// Panics if the file cannot be read.
+fn read_for_diff(file_path: &Path) -> Vec<u8> {
+    std::fs::read(file_path)
+        .unwrap_or_else(|e| panic!("Failed to read file at {}: {e}", file_path.display()))
+}
+
+/// Computes the diff from `other` to `content`.
+fn diff_contents(content: &[u8], other: &[u8]) -> FileDiff {
+    if content == other {
+        return FileDiff::Equal;
+    }
+    match (std::str::from_utf8(content), std::str::from_utf8(other)) {
+        (Ok(text), Ok(other_text)) => FileDiff::Text(diff_lines(text, other_text)),
+        _ => FileDiff::Binary {
+            ranges: diff_bytes(content, other),
+            len: content.len(),
+            other_len: other.len(),
+        },
+    }
+}
+
+/// Computes the changed lines from `other` to `text`.
+fn diff_lines(text: &str, other: &str) -> Vec<LineChange> {
+    TextDiff::from_lines(other, text)
+        .iter_all_changes()
+        .filter_map(|change| {
+            let text = change.value().trim_end_matches(['\n', '\r']).to_string();
+            match change.tag() {
+                ChangeTag::Equal => None,
+                ChangeTag::Delete => Some(LineChange::Removed {
+                    line: change.old_index()? + 1,
+                    text,
+                }),
+                ChangeTag::Insert => Some(LineChange::Added {
+                    line: change.new_index()? + 1,
+                    text,
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Computes the ranges of byte offsets at which `content` and `other` differ.
+fn diff_bytes(content: &[u8], other: &[u8]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for offset in 0..content.len().max(other.len()) {
+        if content.get(offset) == other.get(offset) {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(range) if range.end == offset => range.end += 1,
+            _ => ranges.push(offset..offset + 1),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn diff_file_text() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("actual.txt", "first\nchanged\nthird\nfourth\n");
+        let expected = temp_dir.path().join("expected.txt");
+        std::fs::write(&expected, "first\nsecond\nthird\n").unwrap();
+
+        let diff = directory.diff_file("actual.txt", &expected);
+
+        assert_eq!(
+            diff,
+            FileDiff::Text(vec![
+                LineChange::Removed {
+                    line: 2,
+                    text: "second".to_string()
+                },
+                LineChange::Added {
+                    line: 2,
+                    text: "changed".to_string()
+                },
+                LineChange::Added {
+                    line: 4,
+                    text: "fourth".to_string()
+                },
+            ])
+        );
+        assert_eq!(
+            diff.to_string(),
+            "-    2: second\n+    2: changed\n+    4: fourth\n"
+        );
+    }
+
+    #[test]
+    fn diff_file_binary() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_bytes("actual.bin", [0u8, 1, 9, 9, 4, 5, 6]);
+        let expected = temp_dir.path().join("expected.bin");
+        std::fs::write(&expected, [0xffu8, 1, 2, 3, 4]).unwrap();
+
+        let diff = directory.diff_file("actual.bin", &expected);
+
+        assert_eq!(
+            diff,
+            FileDiff::Binary {
+                ranges: vec![0..1, 2..4, 5..7],
+                len: 7,
+                other_len: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn diff_dir() {
+        let temp_dir = tempdir().unwrap();
+        let actual = Directory::create(temp_dir.path().join("actual"));
+        let expected = Directory::create(temp_dir.path().join("expected"));
+        for directory in [&actual, &expected] {
+            std::fs::create_dir(directory.path().join("subdir")).unwrap();
+            directory.write_string("subdir/same.txt", "same");
+            directory.write_string("changed.txt", "before");
+        }
+        actual.write_string("changed.txt", "after!");
+        actual.write_string("added.txt", "");
+        expected.write_string("subdir/removed.txt", "");
+
+        let diff = actual.diff_dir(&expected);
+
+        assert_eq!(
+            diff,
+            DirDiff {
+                added: vec![PathBuf::from("added.txt")],
+                removed: vec![PathBuf::from("subdir/removed.txt")],
+                changed: vec![PathBuf::from("changed.txt")],
+            }
+        );
+        assert_eq!(
+            diff.to_string(),
+            format!(
+                "+ added.txt\n- {}\n~ changed.txt\n",
+                Path::new("subdir").join("removed.txt").display()
+            )
+        );
+    }
+
+    #[test]
+    fn diff_dir_equal() {
+        let temp_dir = tempdir().unwrap();
+        let actual = Directory::create(temp_dir.path().join("actual"));
+        let expected = Directory::create(temp_dir.path().join("expected"));
+        actual.write_string("file.txt", "same");
+        expected.write_string("file.txt", "same");
+
+        assert!(actual.diff_dir(&expected).is_equal());
+    }
+
+    #[test]
+    fn diff_file_equal() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("actual.txt", "same\n");
+        let expected = temp_dir.path().join("expected.txt");
+        std::fs::write(&expected, "same\n").unwrap();
+
+        assert!(directory.diff_file("actual.txt", &expected).is_equal());
+    }
+}
@@ -0,0 +1,114 @@
+use super::*;
+
+use std::collections::BTreeMap;
+
+/// Methods for `.env` files in the directory, e.g. for tools under test
+/// that read their settings from a `.env` file in their working directory.
+impl Directory {
+    /// Writes the given variables to the `.env` file in the directory, one per line.
+    /// Values are double-quoted, with backslashes, quotes, dollar signs, and newlines
+    /// escaped, so they are read back verbatim (without variable substitution)
+    /// by `read_dotenv` and other dotenv implementations.
+    /// Panics if a variable name is not a valid identifier
+    /// (ASCII letters, digits, and underscores, not starting with a digit),
+    /// or if the write operation fails.
+    ///
+    /// # Arguments
+    /// * `vars` - The names and values of the variables, e.g. a map.
+    pub fn write_dotenv<I, K, V>(&self, vars: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut content = String::new();
+        for (name, value) in vars {
+            let name = name.as_ref();
+            if !is_valid_name(name) {
+                panic!("Invalid variable name for .env file: {name:?}");
+            }
+            content.push_str(&format!("{name}=\"{}\"\n", escape(value.as_ref())));
+        }
+        self.write_internal(".env", &content);
+    }
+
+    /// Reads the variables from the `.env` file in the directory
+    /// using [`dotenvy`](https://crates.io/crates/dotenvy).
+    /// The process environment is not modified.
+    /// Panics if the file cannot be read or parsed.
+    pub fn read_dotenv(&self) -> BTreeMap<String, String> {
+        let file_path = self.path.join(".env");
+        dotenvy::from_path_iter(&file_path)
+            .and_then(|vars| vars.collect())
+            .unwrap_or_else(|e| panic!("Failed to read .env file at {}: {e}", file_path.display()))
+    }
+}
+
+/// Returns whether the given name is a valid variable name.
+fn is_valid_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Escapes a value for a double-quoted string in a `.env` file.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | '"' | '$' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn write_dotenv() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_dotenv([("PLAIN", "value"), ("QUOTED", "say \"hi\" $HOME")]);
+
+        assert_eq!(
+            directory.read_string(".env"),
+            "PLAIN=\"value\"\nQUOTED=\"say \\\"hi\\\" \\$HOME\"\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid variable name")]
+    fn write_dotenv_invalid_name() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_dotenv([("1INVALID", "value")]);
+    }
+
+    #[test]
+    fn read_dotenv() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let vars = BTreeMap::from(
+            [
+                ("EMPTY", ""),
+                ("MULTILINE", "first\nsecond"),
+                ("SPECIAL", "a\\b \"c\" $d 'e' # f"),
+            ]
+            .map(|(name, value)| (name.to_string(), value.to_string())),
+        );
+
+        directory.write_dotenv(&vars);
+
+        assert_eq!(directory.read_dotenv(), vars);
+    }
+}
@@ -2,10 +2,37 @@ use super::*;
 
 impl Drop for Directory {
     /// Drops the Directory instance.
-    /// If the directory is marked as temporary, it is removed from the file system.
+    /// If the directory is marked as temporary, it is removed from the file system
+    /// (with `remove_base_if_created`, together with the parents created along with it),
+    /// unless it is marked to be kept on panic and the current thread is panicking,
+    /// or the `CONV_WD_KEEP` environment variable requests to keep all directories.
+    /// A kept directory is recorded as kept in its marker, if it has one.
+    /// Panics if the directory cannot be removed, unless the thread is already panicking.
+    /// With `with_background_drop`, the removal is handed off to the tokio runtime instead.
     fn drop(&mut self) {
-        if !self.keep_on_drop {
+        #[cfg(feature = "ctrlc")]
+        crate::signal::unregister(&self.path);
+        if self.is_kept() {
+            self.record_kept();
+        } else if !std::thread::panicking() {
+            #[cfg(feature = "tokio")]
+            if self.remove_in_background() {
+                return;
+            }
             self.remove();
+            self.remove_created_parents();
+        } else if self.keep_on_panic {
+            self.record_kept();
+            eprintln!(
+                "Keeping directory {} for inspection after panic",
+                self.path.display()
+            );
+        } else {
+            match self.try_remove() {
+                Ok(()) => self.remove_created_parents(),
+                // Panicking again while unwinding would abort the process.
+                Err(e) => eprintln!("Failed to remove directory at {}: {e}", self.path.display()),
+            }
         }
     }
 }
@@ -22,10 +49,7 @@ mod tests {
         let dir_path = temp_dir.path().join("temp_dir");
 
         {
-            let directory = Directory {
-                path: dir_path.clone(),
-                keep_on_drop: false,
-            };
+            let directory = Directory::new_unchecked(&dir_path);
             directory.ensure_exists();
         }
         assert!(!dir_path.exists());
@@ -37,10 +61,8 @@ mod tests {
         let dir_path = temp_dir.path().join("persistent_dir");
 
         {
-            let directory = Directory {
-                path: dir_path.clone(),
-                keep_on_drop: true,
-            };
+            let mut directory = Directory::new_unchecked(&dir_path);
+            directory.keep_on_drop = true;
             directory.ensure_exists();
         }
 
@@ -0,0 +1,88 @@
+use super::*;
+
+use std::path::Path;
+
+/// Methods for durable writes that survive crashes and power loss.
+impl Directory {
+    /// Creates a new Directory instance from self whose writes are durable:
+    /// written files are flushed to disk (`fsync`), and so is the containing directory
+    /// after files or links have been created in it. The directory itself is also
+    /// flushed to its parent. This is slower, but ensures that crash-recovery state
+    /// kept in the directory is actually on disk when a write returns.
+    /// Syncing directories is only supported on Unix and is skipped elsewhere.
+    /// Panics if the directory cannot be synced.
+    pub fn durable(mut self) -> Self {
+        self.durable = true;
+        if let Some(parent) = self.path.parent() {
+            sync_dir(parent).unwrap_or_else(|e| {
+                panic!("Failed to sync directory at {}: {e}", parent.display())
+            });
+        }
+        self
+    }
+
+    /// Returns whether writes within the directory are durable.
+    pub fn is_durable(&self) -> bool {
+        self.durable
+    }
+
+    /// Flushes the file at the given path and its parent directory to disk
+    /// if the directory is durable.
+    pub(super) fn sync_if_durable(&self, file_path: &Path) -> std::io::Result<()> {
+        if !self.durable {
+            return Ok(());
+        }
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(file_path)?
+            .sync_all()?;
+        match file_path.parent() {
+            Some(parent) => sync_dir(parent),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Flushes the entries of the directory at the given path to disk.
+pub(super) fn sync_dir(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    std::fs::File::open(path)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn durable() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).durable();
+
+        directory.write_string("state.txt", "durable state");
+        directory
+            .write_string_new("new_state.txt", "new durable state")
+            .unwrap();
+
+        assert!(directory.is_durable());
+        assert_eq!(
+            std::fs::read_to_string(directory.path().join("state.txt")).unwrap(),
+            "durable state"
+        );
+        assert_eq!(
+            std::fs::read_to_string(directory.path().join("new_state.txt")).unwrap(),
+            "new durable state"
+        );
+    }
+
+    #[test]
+    fn sync_dir() {
+        let temp_dir = tempdir().unwrap();
+
+        super::sync_dir(temp_dir.path()).unwrap();
+    }
+}
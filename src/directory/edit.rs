@@ -0,0 +1,84 @@
+use super::*;
+
+use std::path::Path;
+use toml_edit::DocumentMut;
+
+/// Methods for editing files in place while preserving their formatting,
+/// e.g. human-maintained configuration files in the directory.
+impl Directory {
+    /// Loads the TOML file at the given path within the directory, applies `edit` to it,
+    /// and saves the result. Comments, ordering, and formatting of the parts that are
+    /// not changed are preserved.
+    /// Unlike `write_toml`, the path is used as is.
+    /// Panics if the path is absolute, if the file cannot be read or is not valid TOML,
+    /// or if the write operation fails.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the TOML file within the directory.
+    /// * `edit` - The function that modifies the document.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let dir = Directory::cargo_target_subdir("edit_toml_example");
+    /// dir.write_string("Cargo.toml", "[package]\nname = \"demo\" # keep me\n");
+    /// dir.edit_toml("Cargo.toml", |doc| doc["package"]["version"] = toml_edit::value("0.2.0"));
+    /// assert_eq!(
+    ///     dir.read_string("Cargo.toml"),
+    ///     "[package]\nname = \"demo\" # keep me\nversion = \"0.2.0\"\n"
+    /// );
+    /// ```
+    pub fn edit_toml<P: AsRef<Path>, F: FnOnce(&mut DocumentMut)>(
+        &self,
+        relative_path: P,
+        edit: F,
+    ) {
+        let content = self.read_string(relative_path.as_ref());
+        let mut document = content.parse::<DocumentMut>().unwrap_or_else(|e| {
+            panic!(
+                "Failed to parse TOML file at {}: {e}",
+                self.path.join(relative_path.as_ref()).display()
+            )
+        });
+        edit(&mut document);
+        self.write_string(relative_path, document.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn edit_toml() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string(
+            "config.toml",
+            "# Settings\n[server]\nhost = \"localhost\"   # default\nport = 8080\n",
+        );
+
+        directory.edit_toml("config.toml", |doc| {
+            doc["server"]["port"] = toml_edit::value(9090);
+            doc["server"]["workers"] = toml_edit::value(4);
+        });
+
+        assert_eq!(
+            directory.read_string("config.toml"),
+            "# Settings\n[server]\nhost = \"localhost\"   # default\nport = 9090\nworkers = 4\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to parse TOML file")]
+    fn edit_toml_invalid() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("config.toml", "not toml");
+
+        directory.edit_toml("config.toml", |_| {});
+    }
+}
@@ -0,0 +1,224 @@
+use super::*;
+
+use encoding_rs::{Encoding, UTF_8, UTF_16BE, UTF_16LE};
+use std::path::Path;
+
+use crate::util::assert_relative_path;
+
+/// Methods for reading and writing text in legacy encodings such as UTF-16 or Latin-1
+/// (`encoding_rs::WINDOWS_1252`), for interop with tools that don't accept UTF-8.
+impl Directory {
+    /// Writes a string in the given encoding to a file at the given path within the
+    /// directory, with the line endings set by `with_newline`, if any.
+    /// A byte order mark is written for UTF-8 and UTF-16 if set by `with_bom`.
+    /// Panics if the path is absolute, if the string contains characters that cannot
+    /// be represented in the encoding, or if the write operation fails.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    /// * `content` - The text to write.
+    /// * `encoding` - The encoding of the file, e.g. `encoding_rs::UTF_16LE`.
+    pub fn write_string_with_encoding<P: AsRef<Path>, S: Into<String>>(
+        &self,
+        relative_path: P,
+        content: S,
+        encoding: &'static Encoding,
+    ) {
+        let content = self.apply_newline(content.into());
+        let bytes = encode(&content, encoding).unwrap_or_else(|| {
+            panic!(
+                "Failed to encode content for file at {} as {}: unmappable characters",
+                relative_path.as_ref().display(),
+                encoding.name()
+            )
+        });
+        self.write_bytes(relative_path, self.with_bom_prefix(bom_of(encoding), bytes));
+    }
+
+    /// Reads a file in the given encoding from the given path within the directory.
+    /// A leading byte order mark of the encoding is stripped.
+    /// Panics if the path is absolute, if the file cannot be read,
+    /// or if its content is not valid in the encoding.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    /// * `encoding` - The encoding of the file, e.g. `encoding_rs::UTF_16LE`.
+    pub fn read_string_with_encoding<P: AsRef<Path>>(
+        &self,
+        relative_path: P,
+        encoding: &'static Encoding,
+    ) -> String {
+        assert_relative_path(relative_path.as_ref());
+        let file_path = self.path.join(relative_path.as_ref());
+        let bytes = std::fs::read(&file_path)
+            .unwrap_or_else(|e| panic!("Failed to read file at {}: {e}", file_path.display()));
+        encoding
+            .decode_without_bom_handling_and_without_replacement(bom::strip_bom(
+                &bytes,
+                bom_of(encoding),
+            ))
+            .unwrap_or_else(|| {
+                panic!(
+                    "Failed to decode file at {}: not valid {}",
+                    file_path.display(),
+                    encoding.name()
+                )
+            })
+            .into_owned()
+    }
+
+    /// Reads a text file in an unknown encoding from the given path within the directory,
+    /// e.g. a file dropped into the directory by an external tool.
+    /// The encoding is taken from a leading byte order mark or, if there is none, guessed
+    /// from the content (using [`chardetng`](https://crates.io/crates/chardetng)).
+    /// Malformed sequences are replaced with U+FFFD.
+    /// Returns the decoded text and the detected encoding.
+    /// Panics if the path is absolute or if the file cannot be read.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    pub fn read_string_lossy_detect<P: AsRef<Path>>(
+        &self,
+        relative_path: P,
+    ) -> (String, &'static Encoding) {
+        assert_relative_path(relative_path.as_ref());
+        let file_path = self.path.join(relative_path.as_ref());
+        let bytes = std::fs::read(&file_path)
+            .unwrap_or_else(|e| panic!("Failed to read file at {}: {e}", file_path.display()));
+        let (encoding, bom_len) = Encoding::for_bom(&bytes).unwrap_or_else(|| {
+            let mut detector = chardetng::EncodingDetector::new();
+            detector.feed(&bytes, true);
+            (detector.guess(None, true), 0)
+        });
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        (text.into_owned(), encoding)
+    }
+}
+
+/// Returns the byte order mark of the given encoding,
+/// or an empty slice for encodings without one.
+fn bom_of(encoding: &'static Encoding) -> &'static [u8] {
+    if encoding == UTF_8 {
+        bom::UTF8_BOM
+    } else if encoding == UTF_16LE {
+        b"\xFF\xFE"
+    } else if encoding == UTF_16BE {
+        b"\xFE\xFF"
+    } else {
+        b""
+    }
+}
+
+/// Encodes the text in the given encoding.
+/// Returns `None` if the text contains characters that cannot be represented.
+/// `encoding_rs` only decodes UTF-16, so it is encoded here.
+fn encode(text: &str, encoding: &'static Encoding) -> Option<Vec<u8>> {
+    if encoding == UTF_16LE {
+        Some(text.encode_utf16().flat_map(u16::to_le_bytes).collect())
+    } else if encoding == UTF_16BE {
+        Some(text.encode_utf16().flat_map(u16::to_be_bytes).collect())
+    } else {
+        let (bytes, _, had_errors) = encoding.encode(text);
+        (!had_errors && encoding.output_encoding() == encoding).then(|| bytes.into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use encoding_rs::WINDOWS_1252;
+    use tempfile::tempdir;
+
+    #[test]
+    fn write_string_with_encoding() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_string_with_encoding("utf16le.txt", "h\u{e9}", UTF_16LE);
+        directory.write_string_with_encoding("utf16be.txt", "h\u{e9}", UTF_16BE);
+        directory.write_string_with_encoding("latin1.txt", "h\u{e9}", WINDOWS_1252);
+
+        let read = |name| std::fs::read(directory.path().join(name)).unwrap();
+        assert_eq!(read("utf16le.txt"), [b'h', 0, 0xe9, 0]);
+        assert_eq!(read("utf16be.txt"), [0, b'h', 0, 0xe9]);
+        assert_eq!(read("latin1.txt"), [b'h', 0xe9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "unmappable characters")]
+    fn write_string_with_encoding_unmappable() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_string_with_encoding("latin1.txt", "\u{4e2d}", WINDOWS_1252);
+    }
+
+    #[test]
+    fn read_string_with_encoding() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        for encoding in [UTF_8, UTF_16LE, UTF_16BE, WINDOWS_1252] {
+            directory.write_string_with_encoding("file.txt", "caf\u{e9}\n", encoding);
+
+            assert_eq!(
+                directory.read_string_with_encoding("file.txt", encoding),
+                "caf\u{e9}\n"
+            );
+        }
+    }
+
+    #[test]
+    fn read_string_lossy_detect() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_bytes("utf16le.txt", b"\xFF\xFEh\0\xE9\0");
+        directory.write_string("utf8.txt", "Gr\u{fc}\u{df}e aus K\u{f6}ln");
+        directory.write_string_with_encoding(
+            "latin1.txt",
+            "Gr\u{fc}\u{df}e aus K\u{f6}ln",
+            WINDOWS_1252,
+        );
+
+        assert_eq!(
+            directory.read_string_lossy_detect("utf16le.txt"),
+            ("h\u{e9}".to_string(), UTF_16LE)
+        );
+        assert_eq!(
+            directory.read_string_lossy_detect("utf8.txt"),
+            ("Gr\u{fc}\u{df}e aus K\u{f6}ln".to_string(), UTF_8)
+        );
+        assert_eq!(
+            directory.read_string_lossy_detect("latin1.txt"),
+            ("Gr\u{fc}\u{df}e aus K\u{f6}ln".to_string(), WINDOWS_1252)
+        );
+    }
+
+    #[test]
+    fn with_bom() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_bom();
+
+        directory.write_string_with_encoding("utf16le.txt", "h", UTF_16LE);
+        directory.write_string_with_encoding("latin1.txt", "h", WINDOWS_1252);
+
+        let read = |name| std::fs::read(directory.path().join(name)).unwrap();
+        assert_eq!(read("utf16le.txt"), [0xff, 0xfe, b'h', 0]);
+        assert_eq!(read("latin1.txt"), [b'h']);
+        assert_eq!(
+            directory.read_string_with_encoding("utf16le.txt", UTF_16LE),
+            "h"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "not valid UTF-8")]
+    fn read_string_with_encoding_invalid() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_bytes("file.txt", [0xff]);
+
+        directory.read_string_with_encoding("file.txt", UTF_8);
+    }
+}
@@ -1,65 +1,548 @@
 use super::*;
 
-use serde::Serialize;
+use base64::prelude::{BASE64_STANDARD, Engine as _};
+#[cfg(any(feature = "json", feature = "toml"))]
+use serde::{Serialize, de::DeserializeOwned};
+use std::io::{BufRead, Write};
 use std::path::Path;
 
-use crate::util::assert_relative_path;
+use crate::util::{assert_relative_path, validate_relative_path};
+use crate::{Error, Result};
 
 /// Methods for file operations within the directory.
 impl Directory {
     /// Writes a byte slice to a file at the given path within the directory.
-    /// Panics if the path is absolute or if the write operation fails.
+    /// Panics if the path is absolute, contains a reserved name (see `with_portable_names`),
+    /// or exceeds the platform's length limits, if the content exceeds the maximum file size
+    /// (see `with_max_file_size`), or if the write operation fails.
     pub fn write_bytes<P: AsRef<Path>, C: AsRef<[u8]>>(&self, relative_path: P, content: C) {
         assert_relative_path(relative_path.as_ref());
-        let file_path = self.path.join(relative_path.as_ref());
-        std::fs::write(&file_path, content.as_ref())
+        let relative_path = self.normalize_name(relative_path.as_ref());
+        self.check_path(&relative_path)
+            .and_then(|()| self.check_size(&relative_path, content.as_ref().len()))
+            .unwrap_or_else(|e| panic!("{e}"));
+        let file_path = self.path.join(&relative_path);
+        self.write_file(&relative_path, content.as_ref())
             .unwrap_or_else(|e| panic!("Failed to write to file at {}: {e}", file_path.display()));
     }
 
-    /// Writes a string to a file at the given path within the directory.
+    /// Writes a string to a file at the given path within the directory,
+    /// with the line endings set by `with_newline` and the byte order mark
+    /// set by `with_bom`, if any.
     /// Panics if the path is absolute or if the write operation fails.
     pub fn write_string<P: AsRef<Path>, S: Into<String>>(&self, relative_path: P, content: S) {
-        self.write_bytes(relative_path, content.into().as_bytes());
+        self.write_bytes(relative_path, self.text_bytes(content.into()));
+    }
+
+    /// Reads a UTF-8 text file from the given path within the directory.
+    /// A leading byte order mark is stripped.
+    /// Panics if the path is absolute, if the file cannot be read, or if it is not valid UTF-8.
+    pub fn read_string<P: AsRef<Path>>(&self, relative_path: P) -> String {
+        assert_relative_path(relative_path.as_ref());
+        let file_path = self.path.join(relative_path.as_ref());
+        let bytes = self
+            .read_file(relative_path.as_ref())
+            .unwrap_or_else(|e| panic!("Failed to read file at {}: {e}", file_path.display()));
+        String::from_utf8(bom::strip_bom(&bytes, bom::UTF8_BOM).to_vec())
+            .unwrap_or_else(|e| panic!("Failed to read file at {}: {e}", file_path.display()))
+    }
+
+    /// Reads the raw bytes of a file from the given path within the directory.
+    /// Panics if the path is absolute or if the file cannot be read.
+    pub fn read_bytes<P: AsRef<Path>>(&self, relative_path: P) -> Vec<u8> {
+        assert_relative_path(relative_path.as_ref());
+        let file_path = self.path.join(relative_path.as_ref());
+        self.read_file(relative_path.as_ref())
+            .unwrap_or_else(|e| panic!("Failed to read file at {}: {e}", file_path.display()))
+    }
+
+    /// Reads a JSON file from the given path within the directory and deserializes it.
+    /// The extension is handled as in `write_json`.
+    /// Panics if the path is absolute, if the file cannot be read, or if the deserialization
+    /// fails.
+    #[cfg(feature = "json")]
+    pub fn read_json<P: AsRef<Path>, T: DeserializeOwned>(&self, relative_path: P) -> T {
+        let relative_path = relative_path.as_ref().with_extension("json");
+        serde_json::from_str(&self.read_string(&relative_path)).unwrap_or_else(|e| {
+            panic!(
+                "Failed to deserialize JSON from file at {}: {e}",
+                self.path.join(relative_path).display()
+            )
+        })
+    }
+
+    /// Reads a TOML file from the given path within the directory and deserializes it.
+    /// The extension is handled as in `write_toml`.
+    /// Panics if the path is absolute, if the file cannot be read, or if the deserialization
+    /// fails.
+    #[cfg(feature = "toml")]
+    pub fn read_toml<P: AsRef<Path>, T: DeserializeOwned>(&self, relative_path: P) -> T {
+        let relative_path = relative_path.as_ref().with_extension("toml");
+        toml::from_str(&self.read_string(&relative_path)).unwrap_or_else(|e| {
+            panic!(
+                "Failed to deserialize TOML from file at {}: {e}",
+                self.path.join(relative_path).display()
+            )
+        })
+    }
+
+    /// Returns a buffered iterator over the lines of a UTF-8 text file at the given path
+    /// within the directory, e.g. to consume log output lazily.
+    /// Lines are split at `\n` or `\r\n` without the line ending, and a leading byte order
+    /// mark is stripped. Lines that cannot be read (e.g. invalid UTF-8) are returned as
+    /// `Error::Io`.
+    /// Panics if the path is absolute or if the file cannot be opened.
+    pub fn read_lines<P: AsRef<Path>>(
+        &self,
+        relative_path: P,
+    ) -> impl Iterator<Item = Result<String>> + use<P> {
+        assert_relative_path(relative_path.as_ref());
+        let file_path = self.path.join(relative_path.as_ref());
+        #[cfg(feature = "cap-std")]
+        let file = match self.open_via_handle(
+            relative_path.as_ref(),
+            cap_std::fs::OpenOptions::new().read(true),
+        ) {
+            Some(result) => result,
+            None => std::fs::File::open(&file_path),
+        };
+        #[cfg(not(feature = "cap-std"))]
+        let file = std::fs::File::open(&file_path);
+        let file =
+            file.unwrap_or_else(|e| panic!("Failed to open file at {}: {e}", file_path.display()));
+        std::io::BufReader::new(file)
+            .lines()
+            .enumerate()
+            .map(move |(index, line)| {
+                let mut line = line.map_err(|e| Error::io(&file_path, e))?;
+                if index == 0 && line.starts_with('\u{feff}') {
+                    line.remove(0);
+                }
+                Ok(line)
+            })
     }
 
     /// Writes a serde-serializable object as JSON to a file at the given path within the directory.
     /// Adds the `.json` extension to the file name if not already present (overwrites existing extension).
     /// Panics if the path is absolute or if the serialization or write operation fails.
+    #[cfg(feature = "json")]
     pub fn write_json<P: AsRef<Path>, T: Serialize>(&self, relative_path: P, obj: &T) {
         self.write_string(
             relative_path.as_ref().with_extension("json"),
-            serde_json::to_string_pretty(obj).unwrap_or_else(|e| {
-                panic!(
-                    "Failed to serialize object to JSON for file at {}: {e}",
-                    relative_path.as_ref().display()
-                )
-            }),
+            to_json(relative_path.as_ref(), obj).unwrap_or_else(|e| panic!("{e}")),
         );
     }
 
     /// Writes a serde-serializable object as TOML to a file at the given path within the directory.
     /// Adds the `.toml` extension to the file name if not already present (replaces existing extension).
     /// Panics if the path is absolute or if the serialization or write operation fails.
+    #[cfg(feature = "toml")]
     pub fn write_toml<P: AsRef<Path>, T: Serialize>(&self, relative_path: P, obj: &T) {
         self.write_string(
             relative_path.as_ref().with_extension("toml"),
-            toml::to_string_pretty(obj).unwrap_or_else(|e| {
+            to_toml(relative_path.as_ref(), obj).unwrap_or_else(|e| panic!("{e}")),
+        );
+    }
+
+    /// Decodes base64 input (standard alphabet, with padding) and writes the raw bytes
+    /// to a file at the given path within the directory, e.g. for binary fixtures
+    /// embedded in JSON or YAML test specs. Surrounding whitespace is ignored.
+    /// Panics if the path is absolute, if the input is not valid base64,
+    /// or if the write operation fails.
+    pub fn write_base64<P: AsRef<Path>, S: AsRef<str>>(&self, relative_path: P, encoded: S) {
+        let content = BASE64_STANDARD
+            .decode(encoded.as_ref().trim())
+            .unwrap_or_else(|e| {
                 panic!(
-                    "Failed to serialize object to TOML for file at {}: {e}",
+                    "Failed to decode base64 content for file at {}: {e}",
                     relative_path.as_ref().display()
                 )
-            }),
-        );
+            });
+        self.write_bytes(relative_path, content);
     }
 
     /// Convenience method to write a `.gitignore` file in the directory
     /// that causes all content to be ignored by Git.
     /// Panics if the write operation fails.
     pub fn write_gitignore(&self) {
-        self.write_string(".gitignore", "*\n");
+        self.write_internal(".gitignore", "*\n");
+    }
+
+    /// Writes a file that this crate maintains itself (e.g. the marker or `.gitignore`)
+    /// verbatim, i.e. without the line endings, byte order mark, size limit, and
+    /// statistics that apply to `write_string`, so the file can be parsed as written.
+    /// Panics if the write operation fails.
+    pub(super) fn write_internal(&self, file_name: &str, content: &str) {
+        self.try_write_internal(file_name, content)
+            .unwrap_or_else(|e| {
+                panic!(
+                    "Failed to write to file at {}: {e}",
+                    self.path.join(file_name).display()
+                )
+            });
+    }
+
+    /// Writes a file that this crate maintains itself like `write_internal`,
+    /// but returns an error instead of panicking, e.g. while the directory is dropped.
+    pub(super) fn try_write_internal(&self, file_name: &str, content: &str) -> std::io::Result<()> {
+        self.write_file_unrecorded(Path::new(file_name), content.as_bytes())
+    }
+
+    /// Writes the content to the file at the given path within the directory
+    /// like `write_file_unrecorded`, and records the write in the statistics.
+    fn write_file(&self, relative_path: &Path, content: &[u8]) -> std::io::Result<()> {
+        let result = self.write_file_unrecorded(relative_path, content);
+        self.record_write(content.len(), &result);
+        result
+    }
+
+    /// Writes the content to the file at the given path within the directory,
+    /// through the directory handle if there is one, and syncs it if the directory is durable.
+    fn write_file_unrecorded(&self, relative_path: &Path, content: &[u8]) -> std::io::Result<()> {
+        let file_path = self.path.join(relative_path);
+        #[cfg(feature = "cap-std")]
+        let result = match self.write_via_handle(relative_path, content) {
+            Some(result) => result,
+            None => std::fs::write(&file_path, content),
+        };
+        #[cfg(not(feature = "cap-std"))]
+        let result = std::fs::write(&file_path, content);
+
+        result.and_then(|()| self.sync_if_durable(&file_path))
+    }
+
+    /// Appends to a file at the given path within the directory, which is created if it
+    /// does not exist, while holding a lock on it (see `lock_file`), so concurrent appends
+    /// don't interleave. `content` is called with whether the file is empty, e.g. to start
+    /// it with a header, and returns the bytes to append. Applies the checks of `write_bytes`,
+    /// with the maximum file size applying to the resulting file, writes through the
+    /// directory handle if there is one, and records the write in the statistics.
+    /// Panics like `write_bytes`.
+    #[cfg(feature = "csv")]
+    pub(super) fn append_bytes(&self, relative_path: &Path, content: impl FnOnce(bool) -> Vec<u8>) {
+        assert_relative_path(relative_path);
+        let relative_path = self.normalize_name(relative_path);
+        self.check_path(&relative_path)
+            .unwrap_or_else(|e| panic!("{e}"));
+        let file_path = self.path.join(&relative_path);
+        let io_error = |e| Error::io(&file_path, e);
+        let mut appended = 0;
+        let result = self
+            .open_append_file(&relative_path)
+            .and_then(|file| {
+                file.lock()?;
+                let len = file.metadata()?.len();
+                Ok((file, len))
+            })
+            .map_err(io_error)
+            .and_then(|(mut file, len)| {
+                let content = content(len == 0);
+                self.check_size(&relative_path, len as usize + content.len())?;
+                appended = content.len();
+                file.write_all(&content)
+                    .and_then(|()| self.sync_if_durable(&file_path))
+                    .map_err(io_error)
+            });
+        self.record_write(appended, &result);
+        result.unwrap_or_else(|e| panic!("{e}"));
+    }
+
+    /// Opens the file at the given path within the directory for appending, creating it
+    /// if it does not exist, through the directory handle if there is one.
+    #[cfg(feature = "csv")]
+    fn open_append_file(&self, relative_path: &Path) -> std::io::Result<std::fs::File> {
+        #[cfg(feature = "cap-std")]
+        if let Some(result) = self.open_via_handle(
+            relative_path,
+            cap_std::fs::OpenOptions::new().append(true).create(true),
+        ) {
+            return result;
+        }
+        std::fs::File::options()
+            .append(true)
+            .create(true)
+            .open(self.path.join(relative_path))
+    }
+
+    /// Reads the file at the given path within the directory,
+    /// through the directory handle if there is one.
+    fn read_file(&self, relative_path: &Path) -> std::io::Result<Vec<u8>> {
+        #[cfg(feature = "cap-std")]
+        if let Some(result) = self.read_via_handle(relative_path) {
+            return result;
+        }
+        std::fs::read(self.path.join(relative_path))
+    }
+
+    /// Writes multiple files like `write_bytes`, e.g. to materialize a fixture tree.
+    /// With the `io-uring` feature on Linux, the files are written in batches through
+    /// io_uring if it is available and the directory has no handle.
+    /// Panics like `write_bytes`.
+    pub(super) fn write_batch(&self, files: Vec<(PathBuf, Vec<u8>)>) {
+        let files = files
+            .into_iter()
+            .map(|(relative_path, content)| {
+                assert_relative_path(&relative_path);
+                let relative_path = self.normalize_name(&relative_path).into_owned();
+                self.check_path(&relative_path)
+                    .and_then(|()| self.check_size(&relative_path, content.len()))
+                    .unwrap_or_else(|e| panic!("{e}"));
+                (relative_path, content)
+            })
+            .collect::<Vec<_>>();
+        #[cfg(all(feature = "io-uring", target_os = "linux"))]
+        if self.write_batch_via_uring(&files) {
+            return;
+        }
+        for (relative_path, content) in files {
+            self.write_file(&relative_path, &content)
+                .unwrap_or_else(|e| {
+                    panic!(
+                        "Failed to write to file at {}: {e}",
+                        self.path.join(&relative_path).display()
+                    )
+                });
+        }
+    }
+
+    /// Writes the files through io_uring.
+    /// Returns `false` without writing anything if io_uring is not available
+    /// or the directory has a handle.
+    /// Panics if a write operation fails.
+    #[cfg(all(feature = "io-uring", target_os = "linux"))]
+    fn write_batch_via_uring(&self, files: &[(PathBuf, Vec<u8>)]) -> bool {
+        #[cfg(feature = "cap-std")]
+        if self.handle.is_some() {
+            return false;
+        }
+        let batch = files
+            .iter()
+            .map(|(relative_path, content)| (self.path.join(relative_path), &content[..]))
+            .collect::<Vec<_>>();
+        let Some(results) = uring::write_files(&batch) else {
+            return false;
+        };
+        for ((file_path, content), result) in batch.iter().zip(results) {
+            let result = result.and_then(|()| self.sync_if_durable(file_path));
+            self.record_write(content.len(), &result);
+            result.unwrap_or_else(|e| {
+                panic!("Failed to write to file at {}: {e}", file_path.display())
+            });
+        }
+        true
     }
 }
 
+/// Methods for writing new files within the directory without overwriting existing ones.
+/// These fail with `Error::FileExists` if the target file already exists, so concurrent
+/// runs can't silently clobber each other's outputs. The check and the creation of the
+/// file are a single atomic operation.
+impl Directory {
+    /// Writes a byte slice to a new file at the given path within the directory.
+    /// Returns `Error::FileExists` if the file already exists, `Error::ReservedName`
+    /// if the path contains a reserved name (see `with_portable_names`),
+    /// `Error::PathTooLong` if the path exceeds the platform's length limits,
+    /// `Error::FileTooLarge` if the content exceeds the maximum file size
+    /// (see `with_max_file_size`), or `Error::PathIsAbsolute` if the path is absolute.
+    pub fn write_bytes_new<P: AsRef<Path>, C: AsRef<[u8]>>(
+        &self,
+        relative_path: P,
+        content: C,
+    ) -> Result<()> {
+        validate_relative_path(relative_path.as_ref())?;
+        let relative_path = self.normalize_name(relative_path.as_ref());
+        self.check_path(&relative_path)?;
+        self.check_size(&relative_path, content.as_ref().len())?;
+        let file_path = self.path.join(&relative_path);
+        let result = self.create_new_file(&relative_path).and_then(|mut file| {
+            file.write_all(content.as_ref())
+                .and_then(|()| self.sync_if_durable(&file_path))
+                .map_err(|e| Error::io(&file_path, e))
+        });
+        self.record_write(content.as_ref().len(), &result);
+        result
+    }
+
+    /// Writes a string to a new file at the given path within the directory,
+    /// with the line endings set by `with_newline` and the byte order mark
+    /// set by `with_bom`, if any.
+    /// Returns `Error::FileExists` if the file already exists
+    /// (or the other errors of `write_bytes_new`).
+    pub fn write_string_new<P: AsRef<Path>, S: Into<String>>(
+        &self,
+        relative_path: P,
+        content: S,
+    ) -> Result<()> {
+        self.write_bytes_new(relative_path, self.text_bytes(content.into()))
+    }
+
+    /// Writes a serde-serializable object as JSON to a new file at the given path
+    /// within the directory. The extension is handled as in `write_json`.
+    /// Returns `Error::FileExists` if the file already exists
+    /// (or the other errors of `write_bytes_new`).
+    #[cfg(feature = "json")]
+    pub fn write_json_new<P: AsRef<Path>, T: Serialize>(
+        &self,
+        relative_path: P,
+        obj: &T,
+    ) -> Result<()> {
+        let content = to_json(relative_path.as_ref(), obj)?;
+        self.write_string_new(relative_path.as_ref().with_extension("json"), content)
+    }
+
+    /// Writes a serde-serializable object as TOML to a new file at the given path
+    /// within the directory. The extension is handled as in `write_toml`.
+    /// Returns `Error::FileExists` if the file already exists
+    /// (or the other errors of `write_bytes_new`).
+    #[cfg(feature = "toml")]
+    pub fn write_toml_new<P: AsRef<Path>, T: Serialize>(
+        &self,
+        relative_path: P,
+        obj: &T,
+    ) -> Result<()> {
+        let content = to_toml(relative_path.as_ref(), obj)?;
+        self.write_string_new(relative_path.as_ref().with_extension("toml"), content)
+    }
+
+    /// Atomically creates a new file at the given path within the directory.
+    /// Returns `Error::FileExists` if the file already exists.
+    fn create_new_file(&self, relative_path: &Path) -> Result<std::fs::File> {
+        let file_path = self.path.join(relative_path);
+        #[cfg(feature = "cap-std")]
+        let result = match self.create_new_via_handle(relative_path) {
+            Some(result) => result,
+            None => std::fs::File::create_new(&file_path),
+        };
+        #[cfg(not(feature = "cap-std"))]
+        let result = std::fs::File::create_new(&file_path);
+
+        result.map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => Error::FileExists(file_path),
+            _ => Error::io(&file_path, e),
+        })
+    }
+}
+
+/// Methods for writing files only if their content changes, so the modification times
+/// of unchanged files are preserved, e.g. for build systems that watch the directory.
+/// Each method returns whether the file was written.
+impl Directory {
+    /// Writes a byte slice to a file at the given path within the directory,
+    /// unless the file already has exactly this content.
+    /// Returns whether the file was written.
+    /// Panics like `write_bytes`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let dir = Directory::cargo_target_subdir("write_bytes_if_changed_example");
+    /// assert!(dir.write_bytes_if_changed("output.bin", [1, 2, 3]));
+    /// assert!(!dir.write_bytes_if_changed("output.bin", [1, 2, 3]));
+    /// assert!(dir.write_bytes_if_changed("output.bin", [4, 5, 6]));
+    /// ```
+    pub fn write_bytes_if_changed<P: AsRef<Path>, C: AsRef<[u8]>>(
+        &self,
+        relative_path: P,
+        content: C,
+    ) -> bool {
+        assert_relative_path(relative_path.as_ref());
+        let relative_path = self.normalize_name(relative_path.as_ref());
+        if has_content(&self.path.join(&relative_path), content.as_ref()) {
+            return false;
+        }
+        self.write_bytes(relative_path, content);
+        true
+    }
+
+    /// Writes a string to a file at the given path within the directory like
+    /// `write_string`, unless the file already has exactly this content
+    /// (after applying the line endings and byte order mark).
+    /// Returns whether the file was written.
+    /// Panics like `write_string`.
+    pub fn write_string_if_changed<P: AsRef<Path>, S: Into<String>>(
+        &self,
+        relative_path: P,
+        content: S,
+    ) -> bool {
+        self.write_bytes_if_changed(relative_path, self.text_bytes(content.into()))
+    }
+
+    /// Writes a serde-serializable object as JSON to a file at the given path within
+    /// the directory like `write_json`, unless the file already has exactly this content.
+    /// Returns whether the file was written.
+    /// Panics like `write_json`.
+    #[cfg(feature = "json")]
+    pub fn write_json_if_changed<P: AsRef<Path>, T: Serialize>(
+        &self,
+        relative_path: P,
+        obj: &T,
+    ) -> bool {
+        self.write_string_if_changed(
+            relative_path.as_ref().with_extension("json"),
+            to_json(relative_path.as_ref(), obj).unwrap_or_else(|e| panic!("{e}")),
+        )
+    }
+
+    /// Writes a serde-serializable object as TOML to a file at the given path within
+    /// the directory like `write_toml`, unless the file already has exactly this content.
+    /// Returns whether the file was written.
+    /// Panics like `write_toml`.
+    #[cfg(feature = "toml")]
+    pub fn write_toml_if_changed<P: AsRef<Path>, T: Serialize>(
+        &self,
+        relative_path: P,
+        obj: &T,
+    ) -> bool {
+        self.write_string_if_changed(
+            relative_path.as_ref().with_extension("toml"),
+            to_toml(relative_path.as_ref(), obj).unwrap_or_else(|e| panic!("{e}")),
+        )
+    }
+}
+
+/// Returns whether the file at `path` exists and has exactly the given content.
+/// The file is compared in chunks, and only if its size matches.
+fn has_content(path: &Path, content: &[u8]) -> bool {
+    use std::io::Read;
+
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return false;
+    };
+    if file.metadata().map(|metadata| metadata.len()).ok() != Some(content.len() as u64) {
+        return false;
+    }
+    let mut buffer = vec![0; content.len().min(64 * 1024)];
+    for expected in content.chunks(buffer.len().max(1)) {
+        let buffer = &mut buffer[..expected.len()];
+        if file.read_exact(buffer).is_err() || buffer != expected {
+            return false;
+        }
+    }
+    // The file may have grown since its size was checked.
+    file.read(&mut [0]).is_ok_and(|read| read == 0)
+}
+
+/// Serializes an object as pretty-printed JSON for the file at the given path.
+#[cfg(feature = "json")]
+pub(super) fn to_json<T: Serialize>(relative_path: &Path, obj: &T) -> Result<String> {
+    serde_json::to_string_pretty(obj).map_err(|e| Error::Serialization {
+        format: "JSON",
+        path: relative_path.to_path_buf(),
+        message: e.to_string(),
+    })
+}
+
+/// Serializes an object as pretty-printed TOML for the file at the given path.
+#[cfg(feature = "toml")]
+fn to_toml<T: Serialize>(relative_path: &Path, obj: &T) -> Result<String> {
+    toml::to_string_pretty(obj).map_err(|e| Error::Serialization {
+        format: "TOML",
+        path: relative_path.to_path_buf(),
+        message: e.to_string(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,6 +581,59 @@ mod tests {
         assert_eq!(read_content, file_content);
     }
 
+    #[test]
+    fn read_string() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        std::fs::write(directory.path().join("file.txt"), "Hello, world!").unwrap();
+        std::fs::write(directory.path().join("bom.txt"), "\u{feff}Hello, BOM!").unwrap();
+
+        assert_eq!(directory.read_string("file.txt"), "Hello, world!");
+        assert_eq!(directory.read_string("bom.txt"), "Hello, BOM!");
+    }
+
+    #[test]
+    fn read_bytes() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        std::fs::write(directory.path().join("file.bin"), [0, 1, 255]).unwrap();
+
+        assert_eq!(directory.read_bytes("file.bin"), [0, 1, 255]);
+    }
+
+    #[test]
+    fn read_lines() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_bytes("log.txt", b"\xEF\xBB\xBFfirst\r\nsecond\n\xFF\n");
+
+        let mut lines = directory.read_lines("log.txt");
+
+        assert_eq!(lines.next().unwrap().unwrap(), "first");
+        assert_eq!(lines.next().unwrap().unwrap(), "second");
+        assert!(matches!(lines.next(), Some(Err(Error::Io { .. }))));
+    }
+
+    #[test]
+    fn write_base64() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_base64("file.bin", "AAEC/w==\n");
+
+        let read_content = std::fs::read(directory.path().join("file.bin")).unwrap();
+        assert_eq!(read_content, [0, 1, 2, 255]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to decode base64 content")]
+    fn write_base64_invalid_input() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_base64("file.bin", "not base64!");
+    }
+
     #[test]
     fn write_gitignore() {
         let temp_dir = tempdir().unwrap();
@@ -112,11 +648,13 @@ mod tests {
         assert_eq!(read_content, "*\n");
     }
 
+    #[cfg(any(feature = "json", feature = "toml"))]
     #[derive(Serialize, serde::Deserialize, PartialEq, Debug)]
     struct TestData {
         content: String,
     }
 
+    #[cfg(feature = "json")]
     #[test]
     fn write_json() {
         let temp_dir = tempdir().unwrap();
@@ -143,6 +681,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "toml")]
     #[test]
     fn write_toml() {
         let temp_dir = tempdir().unwrap();
@@ -168,4 +707,165 @@ mod tests {
             assert_eq!(deserialized, testdata);
         }
     }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn read_json() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let testdata = TestData {
+            content: "Hello, JSON!".to_string(),
+        };
+        directory.write_json("data_file", &testdata);
+
+        assert_eq!(directory.read_json::<_, TestData>("data_file"), testdata);
+        assert_eq!(
+            directory.read_json::<_, TestData>("data_file.json"),
+            testdata
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn read_toml() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let testdata = TestData {
+            content: "Hello, TOML!".to_string(),
+        };
+        directory.write_toml("data_file", &testdata);
+
+        assert_eq!(
+            directory.read_toml::<_, TestData>("data_file.toml"),
+            testdata
+        );
+    }
+
+    #[test]
+    fn write_bytes_new() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_bytes_new("file.bin", b"first").unwrap();
+        let result = directory.write_bytes_new("file.bin", b"second");
+
+        assert!(
+            matches!(result, Err(Error::FileExists(path)) if path == directory.path().join("file.bin"))
+        );
+        assert_eq!(
+            std::fs::read(directory.path().join("file.bin")).unwrap(),
+            b"first"
+        );
+    }
+
+    #[test]
+    fn write_bytes_new_absolute_path() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let absolute_path = directory.path().join("file.bin");
+
+        let result = directory.write_bytes_new(&absolute_path, b"content");
+
+        assert!(matches!(result, Err(Error::PathIsAbsolute(path)) if path == absolute_path));
+        assert!(!absolute_path.exists());
+    }
+
+    #[test]
+    fn write_string_if_changed() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let file_path = directory.path().join("file.txt");
+        directory.write_string("file.txt", "content");
+        let old_time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        util::set_modified(&file_path, old_time).unwrap();
+
+        let unchanged = directory.write_string_if_changed("file.txt", "content");
+        let modified = std::fs::metadata(&file_path).unwrap().modified().unwrap();
+        let changed = directory.write_string_if_changed("file.txt", "new content");
+        let created = directory.write_string_if_changed("new.txt", "");
+
+        assert!(!unchanged);
+        assert_eq!(modified, old_time);
+        assert!(changed);
+        assert_eq!(directory.read_string("file.txt"), "new content");
+        assert!(created);
+        assert!(!directory.write_string_if_changed("new.txt", ""));
+    }
+
+    #[test]
+    fn has_content() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("file.bin");
+        let content = (0..200_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        std::fs::write(&file_path, &content).unwrap();
+
+        assert!(super::has_content(&file_path, &content));
+        let mut changed = content.clone();
+        changed[150_000] ^= 1;
+        assert!(!super::has_content(&file_path, &changed));
+        assert!(!super::has_content(&file_path, &content[1..]));
+        assert!(!super::has_content(&temp_dir.path().join("missing"), b""));
+    }
+
+    #[test]
+    fn write_string_new() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_string_new("file.txt", "first").unwrap();
+        let result = directory.write_string_new("file.txt", "second");
+
+        assert!(matches!(result, Err(Error::FileExists(_))));
+        assert_eq!(
+            std::fs::read_to_string(directory.path().join("file.txt")).unwrap(),
+            "first"
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn write_json_new() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let testdata = TestData {
+            content: "Hello, JSON!".to_string(),
+        };
+
+        directory.write_json_new("data_file", &testdata).unwrap();
+        let result = directory.write_json_new("data_file.json", &testdata);
+
+        assert!(matches!(result, Err(Error::FileExists(_))));
+        assert!(directory.path().join("data_file.json").exists());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn write_toml_new() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let testdata = TestData {
+            content: "Hello, TOML!".to_string(),
+        };
+
+        directory.write_toml_new("data_file", &testdata).unwrap();
+        let result = directory.write_toml_new("data_file.toml", &testdata);
+
+        assert!(matches!(result, Err(Error::FileExists(_))));
+        assert!(directory.path().join("data_file.toml").exists());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn write_toml_new_serialization_error() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        let result = directory.write_toml_new("data_file", &"not a table");
+
+        assert!(matches!(
+            result,
+            Err(Error::Serialization { format: "TOML", .. })
+        ));
+        assert!(!directory.path().join("data_file.toml").exists());
+    }
 }
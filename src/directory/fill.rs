@@ -0,0 +1,105 @@
+use super::*;
+
+use fs4::FileExt;
+use std::fs::File;
+
+use crate::{Error, Result};
+
+/// Maximum size of a single throwaway file written by `fill_until_free_below`.
+const MAX_FILL_FILE_SIZE: u64 = 1 << 30;
+
+/// Methods for simulating a full disk, e.g. to test handling of `ENOSPC` errors.
+impl Directory {
+    /// Allocates throwaway files in the directory until the space available on its
+    /// file system drops below `threshold` bytes (or the file system is full).
+    /// The files are removed when the returned guard is dropped.
+    /// Space is allocated without writing data (see `preallocate`), so even large
+    /// file systems are filled quickly. Note that the whole file system is affected,
+    /// including other processes that write to it.
+    /// Returns an error if the available space cannot be determined, or if it does not
+    /// decrease, e.g. on file systems that don't support preallocation.
+    ///
+    /// # Arguments
+    /// * `threshold` - The number of available bytes to fill the file system down to.
+    pub fn fill_until_free_below(&self, threshold: u64) -> Result<FillGuard> {
+        let mut guard = FillGuard { files: Vec::new() };
+        let mut available = self.available_space()?;
+        while available >= threshold {
+            let file_path = self
+                .path
+                .join(format!(".conv-wd.fill.{}", guard.files.len()));
+            let file = File::create_new(&file_path).map_err(|e| Error::io(&file_path, e))?;
+            guard.files.push(file_path.clone());
+            match file.allocate((available - threshold + 1).min(MAX_FILL_FILE_SIZE)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::StorageFull => break,
+                Err(e) => return Err(Error::io(&file_path, e)),
+            }
+            let previous = std::mem::replace(&mut available, self.available_space()?);
+            if available >= previous {
+                return Err(Error::io(
+                    file_path,
+                    std::io::Error::other("allocating space did not reduce the available space"),
+                ));
+            }
+        }
+        Ok(guard)
+    }
+}
+
+/// Guard returned by [`Directory::fill_until_free_below`].
+/// Removes the throwaway files, and thus frees the space again, when dropped.
+#[must_use = "the space is freed again when the guard is dropped"]
+pub struct FillGuard {
+    files: Vec<PathBuf>,
+}
+
+impl FillGuard {
+    /// Returns the paths of the throwaway files.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+}
+
+impl Drop for FillGuard {
+    /// Removes the throwaway files. Errors are ignored, e.g. if the directory
+    /// has already been removed.
+    fn drop(&mut self) {
+        for file in &self.files {
+            let _ = std::fs::remove_file(file);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn fill_until_free_below() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let available = directory.available_space().unwrap();
+        let threshold = available.saturating_sub(4 << 20);
+
+        let guard = directory.fill_until_free_below(threshold).unwrap();
+        let files = guard.files().to_vec();
+
+        assert!(!files.is_empty());
+        assert!(directory.available_space().unwrap() < threshold);
+        drop(guard);
+        assert!(files.iter().all(|file| !file.exists()));
+    }
+
+    #[test]
+    fn fill_until_free_below_already_below() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        let guard = directory.fill_until_free_below(u64::MAX).unwrap();
+
+        assert!(guard.files().is_empty());
+    }
+}
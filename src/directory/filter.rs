@@ -0,0 +1,255 @@
+use super::*;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::{Error, Result};
+
+/// Include and exclude glob patterns that select the entries touched by the bulk
+/// operations of a directory (see `Directory::with_filter`).
+///
+/// Patterns are matched against paths relative to the root of the walked tree, with `/`
+/// as separator on all platforms, and `*` also matches `/` (e.g. `*.log` matches
+/// `logs/run.log`). An entry is selected if it matches an include pattern (or there are
+/// none) and neither it nor one of its parent directories matches an exclude pattern.
+/// Directories are selected if they match an include pattern themselves or contain
+/// selected entries.
+#[derive(Clone, Debug)]
+pub struct Filter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl Filter {
+    /// Creates a new filter from include and exclude patterns.
+    /// Returns `Error::InvalidPattern` if a pattern is not a valid glob.
+    ///
+    /// # Arguments
+    /// * `include` - The patterns of the entries to select, or none to select all entries.
+    /// * `exclude` - The patterns of the entries to leave out, including their contents.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Filter;
+    ///
+    /// let filter = Filter::new(["src/**"], ["*.tmp"]).unwrap();
+    /// assert!(filter.is_match("src/main.rs"));
+    /// assert!(!filter.is_match("src/main.rs.tmp"));
+    /// assert!(!filter.is_match("README.md"));
+    /// ```
+    pub fn new<I, E, S>(include: I, exclude: E) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        E: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let include = glob_set(include)?;
+        Ok(Self {
+            include: (!include.is_empty()).then_some(include),
+            exclude: glob_set(exclude)?,
+        })
+    }
+
+    /// Returns whether the file at the given relative path is selected, i.e. whether it
+    /// matches an include pattern (if any) and no exclude pattern.
+    /// Parent directories are not checked.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path relative to the root of the tree.
+    pub fn is_match<P: AsRef<Path>>(&self, relative_path: P) -> bool {
+        let relative_path = relative_path.as_ref();
+        self.is_included(relative_path) && !self.exclude.is_match(relative_path)
+    }
+
+    /// Returns whether the given relative path matches an include pattern, if any.
+    fn is_included(&self, relative_path: &Path) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|include| include.is_match(relative_path))
+    }
+
+    /// Returns the selected entries among the given relative paths of a tree,
+    /// which are listed in walk order (see `util::walk`).
+    pub(super) fn apply(&self, entries: Vec<PathBuf>) -> Vec<PathBuf> {
+        let mut excluded: Option<PathBuf> = None;
+        let mut candidates = Vec::new();
+        let mut parents = HashSet::new();
+        for entry in entries {
+            // The contents of a directory directly follow it in walk order.
+            if excluded
+                .as_ref()
+                .is_some_and(|excluded| entry.starts_with(excluded))
+            {
+                continue;
+            }
+            if self.exclude.is_match(&entry) {
+                excluded = Some(entry);
+                continue;
+            }
+            let selected = self.is_included(&entry);
+            if selected {
+                parents.extend(entry.ancestors().skip(1).map(Path::to_path_buf));
+            }
+            candidates.push((entry, selected));
+        }
+        candidates
+            .into_iter()
+            .filter(|(entry, selected)| *selected || parents.contains(entry))
+            .map(|(entry, _)| entry)
+            .collect()
+    }
+}
+
+/// Methods for restricting the bulk operations of the directory to some entries.
+impl Directory {
+    /// Creates a new Directory instance from self whose bulk operations only touch the
+    /// entries selected by the given filter: `clean`, `copy_dir_from`, `sync_to`/`sync_from`,
+    /// `bundle_to`, `diff_dir`, and the other operations that walk a tree.
+    ///
+    /// # Arguments
+    /// * `filter` - The include and exclude patterns that select the entries.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::{Directory, Filter};
+    ///
+    /// let dir = Directory::cargo_target_subdir("with_filter_example")
+    ///     .clean()
+    ///     .with_filter(Filter::new(["*.log"], ["keep/**"]).unwrap());
+    /// std::fs::create_dir(dir.path().join("keep")).unwrap();
+    /// dir.write_string("run.log", "");
+    /// dir.write_string("keep/run.log", "");
+    /// dir.write_string("result.txt", "");
+    ///
+    /// let dir = dir.clean();
+    /// assert!(!dir.path().join("run.log").exists());
+    /// assert!(dir.path().join("keep/run.log").exists());
+    /// assert!(dir.path().join("result.txt").exists());
+    /// ```
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Removes the given entries of the directory, which are listed in walk order:
+    /// files and symlinks, and directories that become empty.
+    /// Panics if an entry cannot be removed.
+    pub(super) fn remove_entries(&self, entries: &[PathBuf]) {
+        self.progress_start(entries.len(), || {
+            format!("Removing {}", self.path.display())
+        });
+        for entry in entries.iter().rev() {
+            let entry_path = self.path.join(entry);
+            let result = std::fs::symlink_metadata(&entry_path).and_then(|metadata| {
+                if metadata.is_dir() {
+                    match std::fs::remove_dir(&entry_path) {
+                        Err(e) if e.kind() == std::io::ErrorKind::DirectoryNotEmpty => Ok(()),
+                        result => result,
+                    }
+                } else {
+                    std::fs::remove_file(&entry_path).inspect(|()| self.record_removed(1))
+                }
+            });
+            if let Err(e) = result {
+                self.record_error();
+                panic!("Failed to remove {}: {e}", entry_path.display());
+            }
+            self.progress_inc();
+        }
+        self.progress_finish();
+    }
+}
+
+/// Builds a glob set from the given patterns.
+/// Returns `Error::InvalidPattern` if a pattern is not a valid glob.
+fn glob_set<S: AsRef<str>>(patterns: impl IntoIterator<Item = S>) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.as_ref();
+        let glob = Glob::new(pattern).map_err(|e| Error::InvalidPattern {
+            pattern: pattern.to_string(),
+            message: e.kind().to_string(),
+        })?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| Error::InvalidPattern {
+        pattern: e.glob().unwrap_or_default().to_string(),
+        message: e.kind().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    fn setup() -> (tempfile::TempDir, Directory) {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("source"));
+        std::fs::create_dir_all(directory.path().join("src/cache")).unwrap();
+        directory.write_string("src/main.rs", "fn main() {}");
+        directory.write_string("src/cache/data.rs", "");
+        directory.write_string("src/notes.txt", "");
+        directory.write_string("build.rs", "");
+        (temp_dir, directory)
+    }
+
+    #[test]
+    fn new() {
+        let filter = Filter::new(["*.rs"], ["src/cache"]).unwrap();
+
+        assert!(filter.is_match("build.rs"));
+        assert!(filter.is_match("src/main.rs"));
+        assert!(!filter.is_match("src/notes.txt"));
+        assert!(!filter.is_match("src/cache"));
+        assert!(matches!(
+            Filter::new(["src/[a"], [""; 0]),
+            Err(Error::InvalidPattern { pattern, .. }) if pattern == "src/[a"
+        ));
+    }
+
+    #[test]
+    fn with_filter_copy_dir_from() {
+        let (temp_dir, source) = setup();
+        let directory = Directory::create(temp_dir.path().join("test_dir"))
+            .with_filter(Filter::new(["*.rs"], ["src/cache"]).unwrap());
+
+        let copy = directory.copy_dir_from(&source, "copy");
+
+        assert_eq!(
+            util::walk(&copy).unwrap(),
+            ["build.rs", "src", "src/main.rs"].map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn with_filter_sync_to() {
+        let (temp_dir, source) = setup();
+        let target = Directory::create(temp_dir.path().join("target"));
+        target.write_string("extra.txt", "");
+        let source = source.with_filter(Filter::new(["src/**"], ["*.txt"]).unwrap());
+
+        let report = source.sync_to(&target, SyncPolicy::default());
+
+        assert_eq!(
+            report.copied,
+            ["src/cache/data.rs", "src/main.rs"].map(PathBuf::from)
+        );
+        assert!(report.removed.is_empty());
+    }
+
+    #[test]
+    fn with_filter_clean() {
+        let (_temp_dir, directory) = setup();
+        let directory = directory
+            .with_filter(Filter::new(["src/**"], ["src/main.rs"]).unwrap())
+            .clean();
+
+        assert_eq!(
+            util::walk(directory.path()).unwrap(),
+            ["build.rs", "src", "src/main.rs"].map(PathBuf::from)
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use super::*;
+
+use std::ffi::{OsStr, OsString};
+
+/// Methods that temporarily change process-wide state to point at the directory.
+impl Directory {
+    /// Changes the current working directory of the process to the directory.
+    /// The previous working directory is restored when the returned guard is dropped.
+    /// Panics if the current directory cannot be determined or changed.
+    ///
+    /// The current directory is process-wide state, so while the guard is alive,
+    /// other threads observe the changed directory as well. Tests that call `enter`
+    /// must therefore not run concurrently with tests that rely on the current directory
+    /// (e.g. by using relative paths). Nested guards must be dropped in reverse order.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let dir = Directory::cargo_target_subdir("enter_example");
+    /// {
+    ///     let _guard = dir.enter();
+    ///     std::fs::write("file.txt", "Hello, CWD!").unwrap();
+    /// }
+    /// assert!(dir.path().join("file.txt").exists());
+    /// ```
+    pub fn enter(&self) -> CurrentDirGuard {
+        let previous = std::env::current_dir()
+            .unwrap_or_else(|e| panic!("Failed to determine current directory: {e}"));
+        std::env::set_current_dir(&self.path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to change current directory to {}: {e}",
+                self.path.display()
+            )
+        });
+        CurrentDirGuard { previous }
+    }
+
+    /// Sets the environment variable `name` to the path of the directory.
+    /// The previous value (or its absence) is restored when the returned guard is dropped.
+    /// This is useful for tools under test that locate their working directory
+    /// via an environment variable.
+    ///
+    /// Nested guards for the same variable must be dropped in reverse order.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the environment variable to set.
+    ///
+    /// # Safety
+    /// The same requirements as for `std::env::set_var` apply, both to this call and
+    /// to dropping the returned guard: on platforms other than Windows, no other thread
+    /// may read or write the environment at the same time. This includes creating and
+    /// dropping other `Directory` instances, which read `CONV_WD_ROOT` and `CONV_WD_KEEP`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let dir = Directory::cargo_target_subdir("export_env_example");
+    /// {
+    ///     // SAFETY: The example runs on a single thread.
+    ///     let _guard = unsafe { dir.export_env("MY_TOOL_HOME") };
+    ///     assert_eq!(std::env::var_os("MY_TOOL_HOME").unwrap(), dir.path());
+    /// }
+    /// assert!(std::env::var_os("MY_TOOL_HOME").is_none());
+    /// ```
+    pub unsafe fn export_env<K: AsRef<OsStr>>(&self, name: K) -> EnvVarGuard {
+        let name = name.as_ref().to_os_string();
+        let previous = std::env::var_os(&name);
+        // SAFETY: Guaranteed by the caller.
+        unsafe { std::env::set_var(&name, &self.path) };
+        EnvVarGuard { name, previous }
+    }
+}
+
+/// Guard returned by [`Directory::enter`].
+/// Restores the previous current working directory when dropped.
+#[must_use = "the previous current directory is restored when the guard is dropped"]
+pub struct CurrentDirGuard {
+    previous: PathBuf,
+}
+
+impl Drop for CurrentDirGuard {
+    /// Restores the previous current working directory.
+    /// Panics if the previous directory cannot be restored (unless already panicking).
+    fn drop(&mut self) {
+        if let Err(e) = std::env::set_current_dir(&self.previous)
+            && !std::thread::panicking()
+        {
+            panic!(
+                "Failed to restore current directory to {}: {e}",
+                self.previous.display()
+            );
+        }
+    }
+}
+
+/// Guard returned by [`Directory::export_env`].
+/// Restores the previous value of the environment variable when dropped.
+#[must_use = "the previous value of the environment variable is restored when the guard is dropped"]
+pub struct EnvVarGuard {
+    name: OsString,
+    previous: Option<OsString>,
+}
+
+impl Drop for EnvVarGuard {
+    /// Restores the previous value of the environment variable,
+    /// or removes it if it was not set before.
+    fn drop(&mut self) {
+        // SAFETY: Guaranteed by the caller of `Directory::export_env`,
+        // whose requirements extend to dropping the guard.
+        unsafe {
+            match &self.previous {
+                Some(value) => std::env::set_var(&self.name, value),
+                None => std::env::remove_var(&self.name),
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use super::*;
+
+use cap_std::ambient_authority;
+use cap_std::fs::{Dir, OpenOptions};
+use std::path::Path;
+
+/// Methods for performing relative operations through an open directory handle.
+///
+/// By default, relative operations resolve the full path and then act on it,
+/// which is racy if a path component is replaced with a symlink in between.
+/// With a handle, the basic file operations are performed relative to the open directory
+/// (via `openat`-style calls), and paths that would escape the directory,
+/// e.g. through `..` or symlinks, are rejected. These are the writes of the `write_*`
+/// methods, `read_bytes`, `read_string`, `read_json`, `read_toml`, `read_lines`,
+/// and `lock_file`.
+/// All other operations, e.g. copies, moves, removals, `clean`, and walks of the tree,
+/// still resolve full paths and are not protected against symlink races.
+impl Directory {
+    /// Creates a new Directory instance from self that holds an open handle to the
+    /// directory and performs the basic file operations relative to it (see above).
+    /// Panics if the directory cannot be opened.
+    pub fn with_handle(mut self) -> Self {
+        self.handle = Some(self.open_handle());
+        self
+    }
+
+    /// Returns the open handle to the directory, if any.
+    pub fn handle(&self) -> Option<&Dir> {
+        self.handle.as_ref()
+    }
+
+    /// Opens a handle to the directory.
+    /// Panics if the directory cannot be opened.
+    pub(super) fn open_handle(&self) -> Dir {
+        Dir::open_ambient_dir(&self.path, ambient_authority()).unwrap_or_else(|e| {
+            panic!(
+                "Failed to open handle to directory at {}: {e}",
+                self.path.display()
+            )
+        })
+    }
+
+    /// Atomically creates a new file relative to the open handle, if any.
+    /// Returns `None` if the directory has no handle.
+    pub(super) fn create_new_via_handle(
+        &self,
+        relative_path: &Path,
+    ) -> Option<std::io::Result<std::fs::File>> {
+        self.open_via_handle(
+            relative_path,
+            OpenOptions::new().write(true).create_new(true),
+        )
+    }
+
+    /// Opens a file with the given options relative to the open handle, if any.
+    /// Returns `None` if the directory has no handle.
+    pub(super) fn open_via_handle(
+        &self,
+        relative_path: &Path,
+        options: &OpenOptions,
+    ) -> Option<std::io::Result<std::fs::File>> {
+        self.handle.as_ref().map(|handle| {
+            handle
+                .open_with(relative_path, options)
+                .map(cap_std::fs::File::into_std)
+        })
+    }
+
+    /// Reads a file relative to the open handle, if any.
+    /// Returns `None` if the directory has no handle.
+    pub(super) fn read_via_handle(&self, relative_path: &Path) -> Option<std::io::Result<Vec<u8>>> {
+        self.handle
+            .as_ref()
+            .map(|handle| handle.read(relative_path))
+    }
+
+    /// Writes to a file relative to the open handle, if any.
+    /// Returns `None` if the directory has no handle.
+    pub(super) fn write_via_handle(
+        &self,
+        relative_path: &Path,
+        content: &[u8],
+    ) -> Option<std::io::Result<()>> {
+        self.handle
+            .as_ref()
+            .map(|handle| handle.write(relative_path, content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn with_handle() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_handle();
+
+        directory.write_string("file.txt", "Hello, handle!");
+
+        assert!(directory.handle().is_some());
+        assert_eq!(
+            std::fs::read_to_string(directory.path().join("file.txt")).unwrap(),
+            "Hello, handle!"
+        );
+    }
+
+    #[test]
+    fn clean_reopens_handle() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"))
+            .with_handle()
+            .clean();
+
+        directory.write_string("file.txt", "Hello, handle!");
+
+        assert!(directory.path().join("file.txt").exists());
+    }
+
+    #[test]
+    fn read_and_lock_via_handle() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_handle();
+        directory.write_string("file.txt", "first\nsecond");
+
+        assert_eq!(directory.read_string("file.txt"), "first\nsecond");
+        let lines = directory
+            .read_lines("file.txt")
+            .collect::<crate::Result<Vec<_>>>();
+        assert_eq!(lines.unwrap(), ["first", "second"]);
+        let guard = directory.lock_file("file.lock").unwrap();
+        assert_eq!(guard.path(), directory.path().join("file.lock"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[should_panic(expected = "Failed to read")]
+    fn read_through_symlink_escape_fails() {
+        let temp_dir = tempdir().unwrap();
+        let outside = temp_dir.path().join("outside");
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("file.txt"), "secret").unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_handle();
+        std::os::unix::fs::symlink(&outside, directory.path().join("link")).unwrap();
+
+        directory.read_string("link/file.txt");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[should_panic(expected = "Failed to write")]
+    fn write_through_symlink_escape_fails() {
+        let temp_dir = tempdir().unwrap();
+        let outside = temp_dir.path().join("outside");
+        std::fs::create_dir_all(&outside).unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_handle();
+        std::os::unix::fs::symlink(&outside, directory.path().join("link")).unwrap();
+
+        directory.write_string("link/file.txt", "escaped");
+    }
+}
@@ -0,0 +1,134 @@
+use super::*;
+
+use std::path::Path;
+
+use crate::{Error, Result};
+
+/// Methods for respecting ignore files, e.g. when mirroring a source checkout
+/// into a scratch build directory.
+impl Directory {
+    /// Creates a new Directory instance from self whose recursive operations
+    /// (`copy_dir_from`, `sync_to`/`sync_from`, `diff_dir`, `bundle_to`, ...) skip
+    /// the entries that are matched by `.gitignore`, `.ignore`, and `.git/info/exclude`
+    /// files within the walked tree, like Git and ripgrep do.
+    /// Ignore files outside of the walked tree and global Git excludes are not used,
+    /// so the result does not depend on the environment.
+    /// In `sync_to` and `sync_from`, ignored entries of the target are not removed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let checkout = Directory::cargo_target_subdir("respect_ignore_files_checkout").clean();
+    /// checkout.write_string(".gitignore", "*.log\n");
+    /// checkout.write_string("main.rs", "fn main() {}");
+    /// checkout.write_string("build.log", "...");
+    ///
+    /// let scratch = Directory::cargo_target_subdir("respect_ignore_files_example")
+    ///     .clean()
+    ///     .respect_ignore_files();
+    /// let copy = scratch.copy_dir_from(&checkout, "src");
+    /// assert!(copy.join("main.rs").exists());
+    /// assert!(!copy.join("build.log").exists());
+    /// ```
+    pub fn respect_ignore_files(mut self) -> Self {
+        self.respect_ignore_files = true;
+        self
+    }
+}
+
+/// Returns the entries below `root` that are not ignored, like `util::walk_to_depth`.
+pub(super) fn walk_unignored(root: &Path, max_depth: usize) -> Result<Vec<PathBuf>> {
+    let walk = ignore::WalkBuilder::new(root)
+        .standard_filters(false)
+        .ignore(true)
+        .git_ignore(true)
+        .git_exclude(true)
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build();
+    let mut entries = Vec::new();
+    for entry in walk {
+        let entry = entry.map_err(|e| {
+            let kind = e
+                .io_error()
+                .map_or(std::io::ErrorKind::InvalidData, std::io::Error::kind);
+            Error::io(root, std::io::Error::new(kind, e))
+        })?;
+        if entry.depth() == 0 {
+            continue;
+        }
+        if entry.depth() > max_depth {
+            return Err(Error::DepthExceeded {
+                path: entry.into_path(),
+                max_depth,
+            });
+        }
+        let relative_path = entry
+            .path()
+            .strip_prefix(root)
+            .expect("walked entries are below the root");
+        entries.push(relative_path.to_path_buf());
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    fn setup() -> (tempfile::TempDir, Directory) {
+        let temp_dir = tempdir().unwrap();
+        let source = Directory::create(temp_dir.path().join("source"));
+        std::fs::create_dir_all(source.path().join("src/generated")).unwrap();
+        std::fs::create_dir(source.path().join("target")).unwrap();
+        source.write_string(".gitignore", "/target/\n*.log\n");
+        source.write_string("src/.ignore", "generated/\n");
+        source.write_string("src/main.rs", "fn main() {}");
+        source.write_string("src/generated/code.rs", "");
+        source.write_string("src/run.log", "");
+        source.write_string("target/app", "");
+        (temp_dir, source)
+    }
+
+    #[test]
+    fn respect_ignore_files() {
+        let (temp_dir, source) = setup();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).respect_ignore_files();
+
+        let copy = directory.copy_dir_from(&source, "copy");
+
+        assert_eq!(
+            util::walk(&copy).unwrap(),
+            [".gitignore", "src", "src/.ignore", "src/main.rs"].map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn respect_ignore_files_sync() {
+        let (temp_dir, source) = setup();
+        let target = Directory::create(temp_dir.path().join("target"));
+        target.write_string("extra.log", "");
+        let source = source.respect_ignore_files();
+
+        let report = source.sync_to(&target, SyncPolicy::default());
+
+        assert_eq!(report.copied.len(), 3);
+        assert!(report.removed.is_empty());
+        assert!(target.path().join("extra.log").exists());
+    }
+
+    #[test]
+    fn walk_unignored_max_depth() {
+        let (_temp_dir, source) = setup();
+
+        let result = walk_unignored(source.path(), 1);
+
+        assert!(matches!(
+            result,
+            Err(Error::DepthExceeded { max_depth: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,164 @@
+use super::*;
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Name of the generated index file.
+const INDEX_FILE: &str = "index.html";
+
+/// Maximum size of text files whose content is included as a preview in the index.
+const MAX_PREVIEW_LEN: u64 = 64 * 1024;
+
+/// Methods for making the contents of the directory browsable,
+/// e.g. when it is uploaded as a CI artifact.
+impl Directory {
+    /// Writes an `index.html` file to the directory that lists all entries below it
+    /// with their size and modification time (UTC), and links to the files.
+    /// UTF-8 text files of up to 64 KiB get a collapsed preview of their content.
+    /// An existing index is replaced and not listed itself.
+    /// Panics if the directory tree cannot be read or if the write operation fails.
+    pub fn write_index_html(&self) {
+        let entries = self.walk_tree(&self.path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to read directory tree at {}: {e}",
+                self.path.display()
+            )
+        });
+        let title = escape_html(&self.path.display().to_string());
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n\
+             <body>\n<h1>{title}</h1>\n<table>\n<tr><th>Name</th><th>Size</th><th>Modified</th></tr>\n"
+        );
+        for entry in entries
+            .iter()
+            .filter(|entry| *entry != Path::new(INDEX_FILE))
+        {
+            html.push_str(&self.index_row(entry));
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        self.write_internal(INDEX_FILE, &html);
+    }
+
+    /// Returns the table row of the index for the entry at the given relative path.
+    /// Panics if the metadata of the entry cannot be read.
+    fn index_row(&self, relative_path: &Path) -> String {
+        let entry_path = self.path.join(relative_path);
+        let metadata = std::fs::symlink_metadata(&entry_path)
+            .unwrap_or_else(|e| panic!("Failed to read metadata of {}: {e}", entry_path.display()));
+        let components = relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>();
+        let name = escape_html(&components.join("/"));
+        let modified = metadata.modified().map(format_utc).unwrap_or_default();
+        if metadata.is_dir() {
+            return format!("<tr><td>{name}/</td><td></td><td>{modified}</td></tr>\n");
+        }
+
+        let href = components
+            .iter()
+            .map(|component| percent_encode(component))
+            .collect::<Vec<_>>()
+            .join("/");
+        let preview = (metadata.is_file() && metadata.len() <= MAX_PREVIEW_LEN)
+            .then(|| std::fs::read_to_string(&entry_path).ok())
+            .flatten()
+            .filter(|text| !text.is_empty())
+            .map(|text| {
+                format!(
+                    "<details><summary>Preview</summary><pre>{}</pre></details>",
+                    escape_html(&text)
+                )
+            })
+            .unwrap_or_default();
+        format!(
+            "<tr><td><a href=\"{href}\">{name}</a>{preview}</td><td>{}</td><td>{modified}</td></tr>\n",
+            metadata.len()
+        )
+    }
+}
+
+/// Escapes the characters that have a special meaning in HTML.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Percent-encodes a path component for use in a URL.
+fn percent_encode(component: &str) -> String {
+    component
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Formats a point in time as `YYYY-MM-DD HH:MM:SS` in UTC.
+fn format_utc(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let (days, secs_of_day) = (secs / 86400, secs % 86400);
+    // Converts days since the epoch to a civil date, see
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn write_index_html() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        std::fs::create_dir(directory.path().join("sub dir")).unwrap();
+        directory.write_string("sub dir/a&b.txt", "<content>");
+        directory.write_bytes("data.bin", [0xff, 0x00]);
+
+        directory.write_index_html();
+        directory.write_index_html();
+
+        let html = directory.read_string("index.html");
+        assert!(html.contains("<td><a href=\"data.bin\">data.bin</a></td><td>2</td>"));
+        assert!(html.contains("<td>sub dir/</td>"));
+        assert!(html.contains(
+            "<a href=\"sub%20dir/a%26b.txt\">sub dir/a&amp;b.txt</a>\
+             <details><summary>Preview</summary><pre>&lt;content&gt;</pre></details>"
+        ));
+        assert!(!html.contains("href=\"index.html\""));
+    }
+
+    #[test]
+    fn format_utc() {
+        assert_eq!(super::format_utc(UNIX_EPOCH), "1970-01-01 00:00:00");
+        assert_eq!(
+            super::format_utc(UNIX_EPOCH + Duration::from_secs(951_827_696)),
+            "2000-02-29 12:34:56"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use super::*;
+
+use std::path::Path;
+
+#[cfg(unix)]
+use crate::util::assert_relative_path;
+use crate::util::validate_relative_path;
+use crate::{Error, FilePath, Result};
+
+/// Maximum length of a Unix domain socket path in bytes, excluding the terminating NUL
+/// (the size of `sockaddr_un::sun_path` minus one).
+const MAX_SOCKET_PATH_LEN: usize = if cfg!(any(target_os = "linux", windows)) {
+    107
+} else {
+    103
+};
+
+/// Methods for allocating inter-process communication endpoints inside the directory,
+/// so they are cleaned up together with it.
+impl Directory {
+    /// Creates a named pipe (FIFO) at the given path within the directory.
+    /// Panics if the path is absolute or if the FIFO cannot be created.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the FIFO within the directory.
+    /// * `mode` - The permission bits of the FIFO, e.g. `0o600` (subject to the umask).
+    #[cfg(unix)]
+    pub fn mkfifo<P: AsRef<Path>>(&self, relative_path: P, mode: u32) {
+        use std::os::unix::ffi::OsStrExt;
+
+        assert_relative_path(relative_path.as_ref());
+        let fifo_path = self.path.join(relative_path.as_ref());
+        let result = std::ffi::CString::new(fifo_path.as_os_str().as_bytes())
+            .map_err(std::io::Error::from)
+            .and_then(|c_path| {
+                // SAFETY: `c_path` is a valid NUL-terminated string.
+                match unsafe { libc::mkfifo(c_path.as_ptr(), mode as libc::mode_t) } {
+                    0 => Ok(()),
+                    _ => Err(std::io::Error::last_os_error()),
+                }
+            });
+        result.unwrap_or_else(|e| panic!("Failed to create FIFO at {}: {e}", fifo_path.display()));
+    }
+
+    /// Returns a path for a Unix domain socket with the given name in the directory.
+    /// The socket file is removed when the returned `FilePath` is dropped
+    /// (and with the directory in any case).
+    /// Returns `Error::PathTooLong` if the path exceeds the length limit of `sockaddr_un`
+    /// (107 bytes on Linux and Windows, 103 bytes elsewhere), which would otherwise only
+    /// be detected when binding the socket. Long temporary directory paths (e.g. on macOS)
+    /// are a common cause, so consider creating the directory under a short base path.
+    /// Returns `Error::PathIsAbsolute` if the name is absolute.
+    ///
+    /// # Arguments
+    /// * `name` - The file name of the socket.
+    pub fn socket_path<P: AsRef<Path>>(&self, name: P) -> Result<FilePath> {
+        validate_relative_path(name.as_ref())?;
+        let socket_path = self.path.join(name.as_ref());
+        if socket_path.as_os_str().len() > MAX_SOCKET_PATH_LEN {
+            return Err(Error::PathTooLong {
+                component: name.as_ref().to_string_lossy().into_owned(),
+                path: socket_path,
+                max: MAX_SOCKET_PATH_LEN,
+            });
+        }
+        Ok(FilePath::create(socket_path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[cfg(unix)]
+    #[test]
+    fn mkfifo() {
+        use std::os::unix::fs::FileTypeExt;
+
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.mkfifo("pipe", 0o600);
+
+        let metadata = std::fs::metadata(directory.path().join("pipe")).unwrap();
+        assert!(metadata.file_type().is_fifo());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_path() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("s"));
+
+        let socket_path = directory.socket_path("app.sock").unwrap();
+        let listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+        drop(listener);
+        let path = socket_path.path().to_path_buf();
+        assert!(path.exists());
+        drop(socket_path);
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn socket_path_too_long() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        let result = directory.socket_path("x".repeat(MAX_SOCKET_PATH_LEN));
+
+        assert!(matches!(
+            result,
+            Err(Error::PathTooLong {
+                max: MAX_SOCKET_PATH_LEN,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,173 @@
+use super::*;
+
+use std::path::Path;
+
+use crate::util::assert_relative_path;
+
+/// Methods for linking other directories into the directory.
+impl Directory {
+    /// Creates a directory junction at the given path within the directory that points
+    /// to `target`, e.g. to link a large shared fixture tree into a working directory.
+    /// Unlike symlinks, junctions can be created on Windows without special privileges.
+    /// On other platforms, a symlink to the absolute target path is created instead.
+    /// Removing the directory (on drop or in `clean`) removes the junction itself,
+    /// but never the contents of its target.
+    /// Panics if `relative_link` is absolute, if `target` is not an existing directory
+    /// (relative targets are resolved against the directory), or if the junction
+    /// cannot be created.
+    ///
+    /// # Arguments
+    /// * `relative_link` - The path of the junction within the directory.
+    /// * `target` - The directory the junction points to.
+    pub fn junction<P: AsRef<Path>, Q: AsRef<Path>>(&self, relative_link: P, target: Q) {
+        assert_relative_path(relative_link.as_ref());
+        let link_path = self.path.join(relative_link.as_ref());
+        let target = self.path.join(target.as_ref());
+        if !target.is_dir() {
+            panic!(
+                "Failed to create junction at {}: target {} is not a directory",
+                link_path.display(),
+                target.display()
+            );
+        }
+        std::fs::canonicalize(&target)
+            .and_then(|target| create_junction(&target, &link_path))
+            .unwrap_or_else(|e| {
+                panic!(
+                    "Failed to create junction at {} to {}: {e}",
+                    link_path.display(),
+                    target.display()
+                )
+            });
+    }
+}
+
+/// Creates a symlink at `link_path` to the absolute `target`.
+#[cfg(unix)]
+fn create_junction(target: &Path, link_path: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link_path)
+}
+
+/// Creates a junction (a mount point reparse point) at `link_path` to the absolute `target`.
+/// The junction is created as an empty directory that is then turned into a reparse point.
+#[cfg(windows)]
+pub(super) fn create_junction(target: &Path, link_path: &Path) -> std::io::Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAG_OPEN_REPARSE_POINT,
+    };
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+    use windows_sys::Win32::System::Ioctl::FSCTL_SET_REPARSE_POINT;
+    use windows_sys::Win32::System::SystemServices::IO_REPARSE_TAG_MOUNT_POINT;
+
+    // `canonicalize` returns a verbatim path (`\\?\C:\...`), but junctions store
+    // NT paths (`\??\C:\...`) and can't point to network shares.
+    let target = target.as_os_str().encode_wide().collect::<Vec<_>>();
+    let verbatim_prefix = r"\\?\".encode_utf16().collect::<Vec<_>>();
+    let print_name = target
+        .strip_prefix(verbatim_prefix.as_slice())
+        .unwrap_or(&target);
+    if print_name.starts_with(&"UNC\\".encode_utf16().collect::<Vec<_>>()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "junctions cannot point to network shares",
+        ));
+    }
+    let substitute_name = r"\??\"
+        .encode_utf16()
+        .chain(print_name.iter().copied())
+        .collect::<Vec<_>>();
+
+    // REPARSE_DATA_BUFFER with a MountPointReparseBuffer: the substitute name and the
+    // print name, each followed by a NUL character that is not counted in its length.
+    let substitute_len = (substitute_name.len() * 2) as u16;
+    let print_len = (print_name.len() * 2) as u16;
+    let path_buffer_len = substitute_len + 2 + print_len + 2;
+    let mut buffer = Vec::new();
+    buffer.extend_from_slice(&IO_REPARSE_TAG_MOUNT_POINT.to_le_bytes());
+    buffer.extend_from_slice(&(8 + path_buffer_len).to_le_bytes());
+    buffer.extend_from_slice(&0u16.to_le_bytes());
+    buffer.extend_from_slice(&0u16.to_le_bytes());
+    buffer.extend_from_slice(&substitute_len.to_le_bytes());
+    buffer.extend_from_slice(&(substitute_len + 2).to_le_bytes());
+    buffer.extend_from_slice(&print_len.to_le_bytes());
+    for unit in substitute_name
+        .iter()
+        .chain(&[0])
+        .chain(print_name)
+        .chain(&[0])
+    {
+        buffer.extend_from_slice(&unit.to_le_bytes());
+    }
+
+    std::fs::create_dir(link_path)?;
+    let result = std::fs::OpenOptions::new()
+        .write(true)
+        .custom_flags(FILE_FLAG_OPEN_REPARSE_POINT | FILE_FLAG_BACKUP_SEMANTICS)
+        .open(link_path)
+        .and_then(|file| {
+            let mut bytes_returned = 0;
+            // SAFETY: The handle is valid while `file` is alive, and the input buffer
+            // is a complete REPARSE_DATA_BUFFER of the given size.
+            let success = unsafe {
+                DeviceIoControl(
+                    file.as_raw_handle(),
+                    FSCTL_SET_REPARSE_POINT,
+                    buffer.as_ptr().cast(),
+                    buffer.len() as u32,
+                    std::ptr::null_mut(),
+                    0,
+                    &mut bytes_returned,
+                    std::ptr::null_mut(),
+                )
+            };
+            if success == 0 {
+                Err(std::io::Error::last_os_error())
+            } else {
+                Ok(())
+            }
+        });
+    if result.is_err() {
+        let _ = std::fs::remove_dir(link_path);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn junction() {
+        let temp_dir = tempdir().unwrap();
+        let shared = temp_dir.path().join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        std::fs::write(shared.join("fixture.txt"), "shared content").unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+
+        {
+            let directory = Directory::create(&dir_path);
+            directory.junction("fixtures", &shared);
+            assert_eq!(
+                std::fs::read_to_string(directory.path().join("fixtures/fixture.txt")).unwrap(),
+                "shared content"
+            );
+        }
+
+        assert!(!dir_path.exists());
+        assert!(shared.join("fixture.txt").exists());
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a directory")]
+    fn junction_to_missing_target() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.junction("fixtures", "missing");
+    }
+}
@@ -0,0 +1,56 @@
+use super::*;
+
+use std::path::Path;
+
+use crate::util::assert_relative_path;
+
+/// Methods for embedded key-value databases inside the directory, for tools that need
+/// ordered, persistent scratch storage rather than flat files.
+impl Directory {
+    /// Opens the sled key-value database with the given name within the directory,
+    /// creating it if it does not exist. A sled database is a directory of files,
+    /// which is removed together with the directory; the database must be dropped
+    /// before the directory, so pending writes don't recreate files after the removal.
+    /// Panics if the name is absolute or if the database cannot be opened.
+    ///
+    /// # Arguments
+    /// * `name` - The path of the database directory within the directory.
+    pub fn open_kv_db<P: AsRef<Path>>(&self, name: P) -> sled::Db {
+        assert_relative_path(name.as_ref());
+        let name = self.normalize_name(name.as_ref());
+        self.check_path(&name).unwrap_or_else(|e| panic!("{e}"));
+        let db_path = self.path.join(&name);
+        sled::open(&db_path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to open key-value database at {}: {e}",
+                db_path.display()
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn open_kv_db() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+        let directory = Directory::create(&dir_path);
+
+        let db = directory.open_kv_db("state");
+        db.insert("key", "value").unwrap();
+        db.flush().unwrap();
+        drop(db);
+        let db = directory.open_kv_db("state");
+        let value = db.get("key").unwrap();
+        drop(db);
+
+        assert_eq!(value.as_deref(), Some(&b"value"[..]));
+        drop(directory);
+        assert!(!dir_path.exists());
+    }
+}
@@ -0,0 +1,183 @@
+use super::*;
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Name of the link that points at the most recently created run directory.
+const LATEST_LINK_NAME: &str = "latest";
+
+/// Counter for the temporary link names, so concurrent updates by several threads
+/// of the same process don't collide.
+static NEXT_TEMP_LINK: AtomicU64 = AtomicU64::new(0);
+
+/// Methods for maintaining a `latest` link next to run directories.
+impl Directory {
+    /// Creates or updates a `latest` link in the parent directory that points at self.
+    /// This is useful for numbered or timestamped run directories, so downstream
+    /// scripts can always refer to `<parent>/latest/...`.
+    /// On Unix, the link is a symlink with a relative target (just the directory name),
+    /// so the parent can be moved. It is first created under a temporary name and then
+    /// renamed over the previous link, so readers never observe a missing `latest` link.
+    /// On Windows, a junction to the absolute path of the directory is used instead, since
+    /// creating symlinks requires special privileges, and the previous link is replaced
+    /// non-atomically.
+    /// Panics if the directory has no parent or if the link cannot be created.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let base = Directory::cargo_target_subdir("latest_link_example");
+    /// let run_1 = Directory::create(base.path().join("run_1")).with_latest_link();
+    /// let run_2 = Directory::create(base.path().join("run_2")).with_latest_link();
+    ///
+    /// # #[cfg(unix)]
+    /// assert_eq!(
+    ///     std::fs::read_link(base.path().join("latest")).unwrap(),
+    ///     std::path::Path::new("run_2")
+    /// );
+    /// ```
+    pub fn with_latest_link(self) -> Self {
+        self.update_latest_link();
+        self
+    }
+
+    /// Creates or updates the `latest` link in the parent directory (see `with_latest_link`).
+    /// Panics if the directory has no parent or if the link cannot be created.
+    pub fn update_latest_link(&self) {
+        let (parent, name) = match (self.path.parent(), self.path.file_name()) {
+            (Some(parent), Some(name)) => (parent, name),
+            _ => panic!(
+                "Cannot create a latest link for {}: the directory has no parent",
+                self.path.display()
+            ),
+        };
+        let link_path = parent.join(LATEST_LINK_NAME);
+        let temp_link_path = parent.join(format!(
+            ".{LATEST_LINK_NAME}.tmp-{}-{}",
+            std::process::id(),
+            NEXT_TEMP_LINK.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        if temp_link_path.symlink_metadata().is_ok() {
+            remove_link(&temp_link_path);
+        }
+        create_dir_link(Path::new(name), &temp_link_path);
+        replace_link(&temp_link_path, &link_path);
+        if self.durable {
+            durability::sync_dir(parent).unwrap_or_else(|e| {
+                panic!("Failed to sync directory at {}: {e}", parent.display())
+            });
+        }
+    }
+}
+
+/// Creates a link at `link` that points at the directory `target`, a sibling of `link`:
+/// a symlink to the relative `target` on Unix, and a junction to its absolute path
+/// on Windows.
+fn create_dir_link(target: &Path, link: &Path) {
+    #[cfg(unix)]
+    let result = std::os::unix::fs::symlink(target, link);
+    #[cfg(windows)]
+    let result = std::fs::canonicalize(link.with_file_name(target))
+        .and_then(|target| junction::create_junction(&target, link));
+
+    result.unwrap_or_else(|e| {
+        panic!(
+            "Failed to create link at {} pointing to {}: {e}",
+            link.display(),
+            target.display()
+        )
+    });
+}
+
+/// Moves the link at `from` to `to`, replacing any previous link at `to`.
+fn replace_link(from: &Path, to: &Path) {
+    // Renaming over an existing directory link fails on Windows.
+    #[cfg(windows)]
+    if to.symlink_metadata().is_ok() {
+        remove_link(to);
+    }
+    std::fs::rename(from, to).unwrap_or_else(|e| {
+        panic!(
+            "Failed to move link from {} to {}: {e}",
+            from.display(),
+            to.display()
+        )
+    });
+}
+
+/// Removes the link at `link` without touching its target.
+fn remove_link(link: &Path) {
+    #[cfg(unix)]
+    let result = std::fs::remove_file(link);
+    #[cfg(windows)]
+    let result = std::fs::remove_dir(link);
+
+    result.unwrap_or_else(|e| panic!("Failed to remove link at {}: {e}", link.display()));
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn with_latest_link() {
+        let temp_dir = tempdir().unwrap();
+        let link_path = temp_dir.path().join(LATEST_LINK_NAME);
+
+        let _run_1 = Directory::create(temp_dir.path().join("run_1")).with_latest_link();
+        assert_eq!(std::fs::read_link(&link_path).unwrap(), Path::new("run_1"));
+
+        let run_2 = Directory::create(temp_dir.path().join("run_2")).with_latest_link();
+        assert_eq!(std::fs::read_link(&link_path).unwrap(), Path::new("run_2"));
+
+        run_2.write_string("output.txt", "Hello, latest!");
+        assert_eq!(
+            std::fs::read_to_string(link_path.join("output.txt")).unwrap(),
+            "Hello, latest!"
+        );
+    }
+
+    #[test]
+    fn update_latest_link() {
+        let temp_dir = tempdir().unwrap();
+        let link_path = temp_dir.path().join(LATEST_LINK_NAME);
+
+        let run_1 = Directory::create(temp_dir.path().join("run_1"));
+        let _run_2 = Directory::create(temp_dir.path().join("run_2")).with_latest_link();
+        run_1.update_latest_link();
+
+        assert_eq!(std::fs::read_link(&link_path).unwrap(), Path::new("run_1"));
+        let entries: Vec<_> = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn update_latest_link_concurrently() {
+        let temp_dir = tempdir().unwrap();
+        let runs = (0..8)
+            .map(|i| Directory::create(temp_dir.path().join(format!("run_{i}"))))
+            .collect::<Vec<_>>();
+
+        std::thread::scope(|scope| {
+            for run in &runs {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        run.update_latest_link();
+                    }
+                });
+            }
+        });
+
+        let target = std::fs::read_link(temp_dir.path().join(LATEST_LINK_NAME)).unwrap();
+        assert!(target.to_string_lossy().starts_with("run_"));
+        let entries = std::fs::read_dir(temp_dir.path()).unwrap().count();
+        assert_eq!(entries, runs.len() + 1);
+    }
+}
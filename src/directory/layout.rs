@@ -0,0 +1,220 @@
+use super::*;
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Methods for reading the complete state of a directory, e.g. to validate
+/// a working directory after a pipeline run.
+impl Directory {
+    /// Deserializes the contents of the directory into a value of type `T`,
+    /// the inverse of scaffolding a directory from a struct.
+    ///
+    /// The directory is read as a map from names to values, with one entry per file
+    /// or subdirectory, named after the file name without its extension:
+    /// - `.json` and `.toml` files are parsed.
+    /// - Other files are read as strings if they are valid UTF-8, and as byte arrays otherwise.
+    /// - Subdirectories are read recursively as nested maps.
+    ///
+    /// Hidden entries (whose names start with `.`) are skipped, and symlinks are followed,
+    /// except for symlinks to a directory that contains them (symlink cycles), which are skipped.
+    /// Entries without a corresponding field are ignored, unless `T` denies unknown fields.
+    /// Panics if an entry cannot be read or parsed, if two entries of a directory map to the
+    /// same name, or if the contents cannot be deserialized into `T`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct RunState {
+    ///     summary: std::collections::BTreeMap<String, u32>,
+    ///     log: String,
+    /// }
+    ///
+    /// let dir = Directory::cargo_target_subdir("read_layout_example");
+    /// dir.write_string("summary.json", r#"{"processed": 3}"#);
+    /// dir.write_string("log.txt", "done");
+    /// let state: RunState = dir.read_layout();
+    /// assert_eq!(state.summary["processed"], 3);
+    /// assert_eq!(state.log, "done");
+    /// ```
+    pub fn read_layout<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_value(layout_value(&self.path, &mut Vec::new())).unwrap_or_else(|e| {
+            panic!(
+                "Failed to deserialize the contents of directory {}: {e}",
+                self.path.display()
+            )
+        })
+    }
+}
+
+/// Identity of a directory, used to detect symlink cycles in `read_layout`, which follows symlinks.
+#[cfg(unix)]
+type DirId = (u64, u64);
+
+/// Identity of a directory, used to detect symlink cycles in `read_layout`, which follows symlinks.
+#[cfg(not(unix))]
+type DirId = PathBuf;
+
+/// Returns the identity of the directory at `path` (its device and inode on Unix,
+/// and its canonical path elsewhere), following symlinks.
+fn dir_id(path: &Path) -> std::io::Result<DirId> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        std::fs::metadata(path).map(|metadata| (metadata.dev(), metadata.ino()))
+    }
+    #[cfg(not(unix))]
+    std::fs::canonicalize(path)
+}
+
+/// Reads the directory at `path` as a map of its entries.
+/// `ancestors` holds the identities of the directories that are currently being read,
+/// so symlinks back to them are detected and skipped instead of recursing forever.
+/// Panics if an entry cannot be read or parsed, or if two entries map to the same name.
+fn layout_value(path: &Path, ancestors: &mut Vec<DirId>) -> Value {
+    let read_error =
+        |e: std::io::Error| -> ! { panic!("Failed to read directory at {}: {e}", path.display()) };
+    ancestors.push(dir_id(path).unwrap_or_else(|e| read_error(e)));
+    let entries = std::fs::read_dir(path)
+        .and_then(|entries| entries.collect::<std::io::Result<Vec<_>>>())
+        .unwrap_or_else(|e| read_error(e));
+    let mut map = Map::new();
+    for entry in entries {
+        let entry_path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if file_name.starts_with('.') {
+            continue;
+        }
+        let (name, value) = if entry_path.is_dir() {
+            let id = dir_id(&entry_path).unwrap_or_else(|e| read_error(e));
+            if ancestors.contains(&id) {
+                continue;
+            }
+            (file_name, layout_value(&entry_path, ancestors))
+        } else {
+            file_value(&entry_path)
+        };
+        if map.contains_key(&name) {
+            panic!(
+                "Multiple entries in directory {} map to the name {name}",
+                path.display()
+            );
+        }
+        map.insert(name, value);
+    }
+    ancestors.pop();
+    Value::Object(map)
+}
+
+/// Reads the file at `path` as a name and a value, depending on its extension.
+/// Panics if the file cannot be read or parsed.
+fn file_value(path: &Path) -> (String, Value) {
+    let name = path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    let content = std::fs::read(path)
+        .unwrap_or_else(|e| panic!("Failed to read file at {}: {e}", path.display()));
+    let content = bom::strip_bom(&content, bom::UTF8_BOM);
+    let parse_error = |format: &str, e: &dyn std::fmt::Display| -> ! {
+        panic!(
+            "Failed to parse {format} from file at {}: {e}",
+            path.display()
+        )
+    };
+    let value = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::from_slice(content).unwrap_or_else(|e| parse_error("JSON", &e)),
+        Some("toml") => std::str::from_utf8(content)
+            .map_err(|e| e.to_string())
+            .and_then(|content| toml::from_str(content).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| parse_error("TOML", &e)),
+        _ => match std::str::from_utf8(content) {
+            Ok(text) => Value::String(text.to_string()),
+            Err(_) => Value::Array(content.iter().map(|&byte| Value::from(byte)).collect()),
+        },
+    };
+    (name, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+    use tempfile::tempdir;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Settings {
+        mode: String,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Outputs {
+        result: Vec<u8>,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct State {
+        input: Vec<u32>,
+        settings: Settings,
+        log: String,
+        outputs: Outputs,
+    }
+
+    #[test]
+    fn read_layout() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_gitignore();
+        std::fs::create_dir(directory.path().join("outputs")).unwrap();
+        directory.write_string("input.json", "[1, 2]");
+        directory.write_string("settings.toml", "mode = \"fast\"");
+        directory.write_string("log.txt", "done");
+        directory.write_bytes("outputs/result.bin", [0, 255]);
+
+        let state: State = directory.read_layout();
+
+        assert_eq!(
+            state,
+            State {
+                input: vec![1, 2],
+                settings: Settings {
+                    mode: "fast".to_string()
+                },
+                log: "done".to_string(),
+                outputs: Outputs {
+                    result: vec![0, 255]
+                },
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "map to the name data")]
+    fn read_layout_ambiguous_names() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("data.json", "{}");
+        directory.write_string("data.txt", "");
+
+        directory.read_layout::<BTreeMap<String, Value>>();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_layout_skips_symlink_cycles() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        std::fs::create_dir(directory.path().join("nested")).unwrap();
+        directory.write_string("nested/log.txt", "done");
+        std::os::unix::fs::symlink(directory.path(), directory.path().join("nested/loop")).unwrap();
+
+        let value: Value = directory.read_layout();
+
+        assert_eq!(value, serde_json::json!({ "nested": { "log": "done" } }));
+    }
+}
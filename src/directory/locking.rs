@@ -0,0 +1,119 @@
+use super::*;
+
+use std::fs::File;
+use std::path::Path;
+
+use crate::util::validate_relative_path;
+use crate::{Error, Result};
+
+/// Methods for coordinating access to individual files between processes.
+impl Directory {
+    /// Acquires an exclusive advisory lock on the file at the given path within the
+    /// directory, blocking until the lock is available. The file is created if it does
+    /// not exist (existing content is preserved). The lock is released when the
+    /// returned guard is dropped.
+    /// Advisory locks only coordinate processes that also use them;
+    /// they do not prevent other processes from accessing the file.
+    /// Returns `Error::PathIsAbsolute` if the path is absolute.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file to lock within the directory.
+    pub fn lock_file<P: AsRef<Path>>(&self, relative_path: P) -> Result<FileLockGuard> {
+        validate_relative_path(relative_path.as_ref())?;
+        let file_path = self.path.join(relative_path.as_ref());
+        #[cfg(feature = "cap-std")]
+        let file = match self.open_via_handle(
+            relative_path.as_ref(),
+            cap_std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false),
+        ) {
+            Some(result) => result,
+            None => open_lock_file(&file_path),
+        };
+        #[cfg(not(feature = "cap-std"))]
+        let file = open_lock_file(&file_path);
+        let file = file.map_err(|e| Error::io(&file_path, e))?;
+        file.lock().map_err(|e| Error::io(&file_path, e))?;
+        Ok(FileLockGuard {
+            file,
+            path: file_path,
+        })
+    }
+}
+
+/// Opens the file at the given path for locking, creating it if it does not exist.
+fn open_lock_file(file_path: &Path) -> std::io::Result<File> {
+    std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(file_path)
+}
+
+/// Guard returned by [`Directory::lock_file`].
+/// Holds the locked file open and releases the lock when dropped.
+#[must_use = "the lock is released when the guard is dropped"]
+pub struct FileLockGuard {
+    file: File,
+    path: PathBuf,
+}
+
+impl FileLockGuard {
+    /// Returns the locked file, e.g. for reading or writing its content.
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    /// Returns the path of the locked file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for FileLockGuard {
+    /// Releases the lock. Closing the file would release it as well,
+    /// but unlocking explicitly makes the release independent of other handles.
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn lock_file() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("shared.txt", "shared content");
+
+        let guard = directory.lock_file("shared.txt").unwrap();
+        let other = File::open(directory.path().join("shared.txt")).unwrap();
+
+        assert_eq!(guard.path(), directory.path().join("shared.txt"));
+        assert!(other.try_lock().is_err());
+        drop(guard);
+        assert!(other.try_lock().is_ok());
+        assert_eq!(
+            std::fs::read_to_string(directory.path().join("shared.txt")).unwrap(),
+            "shared content"
+        );
+    }
+
+    #[test]
+    fn lock_file_creates_missing_file() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        let _guard = directory.lock_file("new.lock").unwrap();
+
+        assert!(directory.path().join("new.lock").is_file());
+    }
+}
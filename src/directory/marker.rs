@@ -0,0 +1,449 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::process::ProcessId;
+
+/// Name of the hidden marker file that identifies directories managed by this crate.
+pub(super) const MARKER_FILE_NAME: &str = ".conv-wd.toml";
+
+/// Metadata recorded in the marker file of a managed directory (see `Directory::with_marker`).
+/// This helps to find out where a stray directory came from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Marker {
+    /// Name of the crate that created the directory, if known
+    /// (taken from `CARGO_PKG_NAME`, which Cargo sets for `cargo run` and `cargo test`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crate_name: Option<String>,
+    /// Name of the test that created the directory, if known
+    /// (taken from the name of the creating thread, which the test harness sets to the test name).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_name: Option<String>,
+    /// PID of the process that created the directory.
+    pub pid: u32,
+    /// Start time of the creating process, where available (see `Directory::claim`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<u64>,
+    /// Creation time of the marker in seconds since the Unix epoch.
+    pub created_at: u64,
+    /// Whether the directory is kept when dropped, or was kept when it was dropped
+    /// (after a panic with `keep_on_panic`, or because of `CONV_WD_KEEP`).
+    pub keep: bool,
+    /// Whether the directory is kept when dropped during a panic.
+    #[serde(default)]
+    pub keep_on_panic: bool,
+    /// Arbitrary key-value tags (see `Directory::set_tag`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+}
+
+impl Marker {
+    /// Reads the marker file of the directory at the given path.
+    /// Returns `None` if there is no marker or it cannot be parsed.
+    pub fn read<P: AsRef<Path>>(dir_path: P) -> Option<Self> {
+        let content = std::fs::read_to_string(dir_path.as_ref().join(MARKER_FILE_NAME)).ok()?;
+        Self::parse(&content)
+    }
+
+    /// Formats the marker as the content of a marker file.
+    /// Marker files are TOML, but written and parsed without a TOML library,
+    /// so the lifecycle management doesn't depend on the `toml` feature.
+    fn format(&self) -> String {
+        let mut content = String::new();
+        if let Some(crate_name) = &self.crate_name {
+            content.push_str(&format!("crate_name = {}\n", quote(crate_name)));
+        }
+        if let Some(test_name) = &self.test_name {
+            content.push_str(&format!("test_name = {}\n", quote(test_name)));
+        }
+        content.push_str(&format!("pid = {}\n", self.pid));
+        if let Some(start_time) = self.start_time {
+            content.push_str(&format!("start_time = {start_time}\n"));
+        }
+        content.push_str(&format!("created_at = {}\n", self.created_at));
+        content.push_str(&format!("keep = {}\n", self.keep));
+        content.push_str(&format!("keep_on_panic = {}\n", self.keep_on_panic));
+        if !self.tags.is_empty() {
+            content.push_str("\n[tags]\n");
+            for (key, value) in &self.tags {
+                content.push_str(&format!("{} = {}\n", quote(key), quote(value)));
+            }
+        }
+        content
+    }
+
+    /// Parses the content of a marker file, i.e. the subset of TOML written by `format`
+    /// (with literal strings as well).
+    /// Returns `None` if the content cannot be parsed or a required field is missing.
+    fn parse(content: &str) -> Option<Self> {
+        let mut fields = BTreeMap::new();
+        let mut tags = BTreeMap::new();
+        let mut in_tags = false;
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                in_tags = line == "[tags]";
+                if !in_tags {
+                    return None;
+                }
+                continue;
+            }
+            let (key, rest) = parse_string(line).or_else(|| {
+                let end = line.find([' ', '='])?;
+                Some((line[..end].to_string(), &line[end..]))
+            })?;
+            let value = rest.trim_start().strip_prefix('=')?.trim();
+            if in_tags {
+                tags.insert(key, parse_string(value).filter(|(_, rest)| is_end(rest))?.0);
+            } else {
+                fields.insert(key, value);
+            }
+        }
+
+        let string = |key: &str| match fields.get(key) {
+            Some(value) => parse_string(value)
+                .filter(|(_, rest)| is_end(rest))
+                .map(|(value, _)| Some(value)),
+            None => Some(None),
+        };
+        let integer = |key: &str| fields.get(key).map(|value| value.parse::<u64>().ok());
+        let boolean = |key: &str| fields.get(key).map(|value| value.parse::<bool>().ok());
+        Some(Self {
+            crate_name: string("crate_name")?,
+            test_name: string("test_name")?,
+            pid: integer("pid")??.try_into().ok()?,
+            start_time: integer("start_time").map_or(Some(None), |value| value.map(Some))?,
+            created_at: integer("created_at")??,
+            keep: boolean("keep")??,
+            keep_on_panic: boolean("keep_on_panic").unwrap_or(Some(false))?,
+            tags,
+        })
+    }
+
+    /// Creates a marker for a directory created by the current process and thread.
+    fn for_current_process() -> Self {
+        let owner = ProcessId::current();
+        Self {
+            crate_name: std::env::var("CARGO_PKG_NAME").ok(),
+            test_name: std::thread::current()
+                .name()
+                .filter(|name| *name != "main")
+                .map(String::from),
+            pid: owner.pid,
+            start_time: owner.start_time,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+            keep: false,
+            keep_on_panic: false,
+            tags: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the identity of the process that created the directory.
+    pub(super) fn owner(&self) -> ProcessId {
+        ProcessId {
+            pid: self.pid,
+            start_time: self.start_time,
+        }
+    }
+}
+
+/// Methods for marker files that identify managed directories.
+impl Directory {
+    /// Creates a new Directory instance from self that writes a hidden marker file
+    /// (`.conv-wd.toml`) into the directory. The marker records the creating crate,
+    /// test, and process, the creation time, and the drop policy (see [`Marker`]),
+    /// which allows `Directory::sweep_orphans` to find directories left behind by
+    /// crashed runs and helps to debug stray directories.
+    /// The marker is kept up to date when the drop policy changes or the directory is cleaned.
+    /// Panics if the marker file cannot be written.
+    pub fn with_marker(mut self) -> Self {
+        self.marker = true;
+        self.update_marker();
+        self
+    }
+
+    /// Returns the marker of the directory, if it has one.
+    pub fn marker(&self) -> Option<Marker> {
+        Marker::read(&self.path)
+    }
+
+    /// Returns the marker of the directory, or a new one for the current process
+    /// with the current drop policy if the directory has none yet.
+    pub(super) fn marker_or_new(&self) -> Marker {
+        let mut marker = self.marker().unwrap_or_else(Marker::for_current_process);
+        marker.keep = self.keep_on_drop;
+        marker.keep_on_panic = self.keep_on_panic;
+        marker
+    }
+
+    /// Rewrites the marker file with the current drop policy if the directory has one.
+    /// Other recorded information of an existing marker is preserved.
+    /// Panics if the marker file cannot be written.
+    pub(super) fn update_marker(&self) {
+        if !self.marker {
+            return;
+        }
+        self.write_marker(&self.marker_or_new());
+    }
+
+    /// Records in the marker, if the directory has one, that the directory is kept
+    /// although it is temporary, e.g. after a panic (see `keep_on_panic`) or because of
+    /// `CONV_WD_KEEP`, so `sweep_orphans` does not remove it later.
+    /// Errors are only printed to stderr, since this is called when the directory is dropped.
+    pub(super) fn record_kept(&self) {
+        if !self.marker || self.keep_on_drop {
+            return;
+        }
+        let mut marker = self.marker_or_new();
+        marker.keep = true;
+        if let Err(e) = self.try_write_internal(MARKER_FILE_NAME, &marker.format()) {
+            eprintln!(
+                "Failed to update marker of directory at {}: {e}",
+                self.path.display()
+            );
+        }
+    }
+
+    /// Writes the given marker file into the directory.
+    /// Panics if the marker file cannot be written.
+    pub(super) fn write_marker(&self, marker: &Marker) {
+        self.write_internal(MARKER_FILE_NAME, &marker.format());
+    }
+}
+
+/// Returns the given string as a TOML basic string.
+fn quote(value: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Parses a TOML basic or literal string at the start of the given text.
+/// Returns the string and the remaining text, or `None` if there is no valid string.
+fn parse_string(text: &str) -> Option<(String, &str)> {
+    if let Some(rest) = text.strip_prefix('\'') {
+        let end = rest.find('\'')?;
+        return Some((rest[..end].to_string(), &rest[end + 1..]));
+    }
+    let rest = text.strip_prefix('"')?;
+    let mut value = String::new();
+    let mut chars = rest.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, &rest[index + 1..])),
+            '\\' => {
+                let escaped = match chars.next()?.1 {
+                    'b' => '\u{8}',
+                    't' => '\t',
+                    'n' => '\n',
+                    'f' => '\u{c}',
+                    'r' => '\r',
+                    '"' => '"',
+                    '\\' => '\\',
+                    unicode @ ('u' | 'U') => {
+                        let len = if unicode == 'u' { 4 } else { 8 };
+                        let hex = (0..len)
+                            .map(|_| chars.next().map(|(_, c)| c))
+                            .collect::<Option<String>>()?;
+                        char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                    }
+                    _ => return None,
+                };
+                value.push(escaped);
+            }
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+/// Returns whether the given text is empty apart from whitespace and a comment.
+fn is_end(text: &str) -> bool {
+    let text = text.trim_start();
+    text.is_empty() || text.starts_with('#')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn with_marker() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_marker();
+
+        let marker = directory.marker().unwrap();
+
+        assert_eq!(marker.owner(), ProcessId::current());
+        assert_eq!(marker.crate_name.as_deref(), Some("conv-wd"));
+        assert_eq!(
+            marker.test_name.as_deref(),
+            Some("directory::marker::tests::with_marker")
+        );
+        assert!(marker.created_at > 0);
+        assert!(!marker.keep);
+    }
+
+    #[test]
+    fn marker_follows_drop_policy() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"))
+            .with_marker()
+            .keep_on_panic()
+            .keep()
+            .clean();
+
+        let marker = directory.marker().unwrap();
+
+        assert!(marker.keep);
+        assert!(marker.keep_on_panic);
+    }
+
+    #[test]
+    fn marker_records_keep_after_panic() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+
+        let thread_dir_path = dir_path.clone();
+        let result = std::thread::spawn(move || {
+            let _directory = Directory::create(thread_dir_path)
+                .with_marker()
+                .keep_on_panic();
+            panic!("Failing run");
+        })
+        .join();
+
+        assert!(result.is_err());
+        let marker = Marker::read(&dir_path).unwrap();
+        assert!(marker.keep);
+        assert!(marker.keep_on_panic);
+    }
+
+    #[test]
+    fn mark_failed_records_keep() {
+        let temp_dir = tempdir().unwrap();
+        let mut directory = Directory::create(temp_dir.path().join("test_dir")).with_marker();
+
+        directory.mark_failed();
+
+        assert!(directory.marker().unwrap().keep);
+    }
+
+    #[test]
+    fn record_kept() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_marker();
+
+        directory.record_kept();
+
+        assert!(directory.marker().unwrap().keep);
+    }
+
+    #[test]
+    fn marker_without_bom() {
+        let temp_dir = tempdir().unwrap();
+        let mut directory = Directory::create(temp_dir.path().join("test_dir"))
+            .with_bom()
+            .with_newline(Newline::CrLf)
+            .with_marker();
+
+        directory.set_tag("run", "42");
+
+        let content = std::fs::read(directory.path().join(MARKER_FILE_NAME)).unwrap();
+        assert!(!content.starts_with(bom::UTF8_BOM));
+        assert!(!content.contains(&b'\r'));
+        assert_eq!(directory.marker().unwrap().owner(), ProcessId::current());
+        assert_eq!(directory.get_tag("run").as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn read() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::write(
+            temp_dir.path().join(MARKER_FILE_NAME),
+            "pid = 42\ncreated_at = 1700000000\nkeep = true\n",
+        )
+        .unwrap();
+
+        let marker = Marker::read(temp_dir.path()).unwrap();
+
+        assert_eq!(marker.pid, 42);
+        assert_eq!(marker.start_time, None);
+        assert_eq!(marker.crate_name, None);
+        assert!(marker.keep);
+    }
+
+    #[test]
+    fn format_and_parse() {
+        let marker = Marker {
+            crate_name: Some("conv-wd".to_string()),
+            test_name: Some("tests::\"quoted\" \\ name\n".to_string()),
+            pid: 42,
+            start_time: Some(123),
+            created_at: 1_700_000_000,
+            keep: false,
+            keep_on_panic: true,
+            tags: BTreeMap::from([
+                ("job".to_string(), "ci-1".to_string()),
+                ("with space".to_string(), "\u{7}".to_string()),
+            ]),
+        };
+
+        let content = marker.format();
+
+        assert_eq!(Marker::parse(&content), Some(marker));
+        assert!(content.contains("test_name = \"tests::\\\"quoted\\\" \\\\ name\\n\"\n"));
+        assert!(content.contains("\n[tags]\n\"job\" = \"ci-1\"\n\"with space\" = \"\\u0007\"\n"));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for content in [
+            "created_at = 1\nkeep = true\n",
+            "pid = -1\ncreated_at = 1\nkeep = true\n",
+            "pid = 1\ncreated_at = 1\nkeep = yes\n",
+            "pid = 1\ncreated_at = 1\nkeep = true\ncrate_name = \"unterminated\n",
+            "pid = 1\ncreated_at = 1\nkeep = true\n[other]\n",
+        ] {
+            assert_eq!(Marker::parse(content), None, "{content}");
+        }
+    }
+
+    #[test]
+    fn parse_literal_strings() {
+        let marker = Marker::parse(
+            "crate_name = 'C:\\path' # comment\npid = 1\ncreated_at = 1\nkeep = true\n[tags]\nkey = 'value'\n",
+        )
+        .unwrap();
+
+        assert_eq!(marker.crate_name.as_deref(), Some("C:\\path"));
+        assert_eq!(marker.tags["key"], "value");
+    }
+
+    #[test]
+    fn read_missing_marker() {
+        let temp_dir = tempdir().unwrap();
+
+        assert_eq!(Marker::read(temp_dir.path()), None);
+    }
+}
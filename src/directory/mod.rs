@@ -2,15 +2,141 @@ use std::path::PathBuf;
 
 /// Represents a directory in the file system.
 /// The actual directory is created on the file system when this struct is instantiated.
-/// By default, the directory is persistent, but there are options to make it temporary.
+/// By default, the directory is temporary, but there are options to make it persistent.
+///
+/// Paths of entries within the directory must be relative. Methods that return a `Result`
+/// report absolute paths as `Error::PathIsAbsolute`, while the others panic on them,
+/// just as they panic on I/O errors.
 pub struct Directory {
     path: PathBuf,
     keep_on_drop: bool,
+    keep_on_panic: bool,
+    remove_retry: RetryPolicy,
+    durable: bool,
+    marker: bool,
+    nfc_names: bool,
+    portable_names: bool,
+    max_file_size: Option<u64>,
+    max_depth: Option<usize>,
+    #[cfg(feature = "glob")]
+    filter: Option<Filter>,
+    preserve_metadata: bool,
+    newline: Option<Newline>,
+    bom: bool,
+    stats: Option<Box<stats::Counters>>,
+    created_base: Option<PathBuf>,
+    remove_base_if_created: bool,
+    #[cfg(feature = "ignore")]
+    respect_ignore_files: bool,
+    #[cfg(feature = "cap-std")]
+    handle: Option<cap_std::fs::Dir>,
+    #[cfg(feature = "indicatif")]
+    progress: Option<indicatif::ProgressBar>,
+    #[cfg(feature = "tokio")]
+    background_drop: bool,
 }
 
 mod access;
+mod allocation;
+mod assertions;
+#[cfg(feature = "tokio")]
+mod background;
+mod bom;
+#[cfg(feature = "json")]
+mod bundle;
 mod cargo;
+mod ci;
+mod claim;
+#[cfg(feature = "toml")]
+mod config_files;
 mod constructors;
+mod copy;
+#[cfg(feature = "csv")]
+mod csv;
+mod diff;
+mod dotenv;
 mod drop;
+mod durability;
+#[cfg(feature = "toml")]
+mod edit;
+#[cfg(feature = "encoding")]
+mod encoding;
 mod files;
+mod fill;
+#[cfg(feature = "glob")]
+mod filter;
+mod guards;
+#[cfg(feature = "cap-std")]
+mod handle;
+#[cfg(feature = "ignore")]
+mod ignore_rules;
+mod index;
+mod ipc;
+mod junction;
+#[cfg(feature = "sled")]
+mod kv;
+mod latest;
+#[cfg(all(feature = "json", feature = "toml"))]
+mod layout;
+mod locking;
+mod marker;
+mod newline;
+mod normalization;
+#[cfg(unix)]
+mod ownership;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "parquet")]
+mod parquet;
+mod permissions;
+mod policy;
+mod probes;
+mod progress;
+mod random;
+mod retry;
+#[cfg(feature = "reveal")]
+mod reveal;
+mod root;
+#[cfg(feature = "schema")]
+mod schema;
+mod scoped;
+mod scratch;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod stages;
+mod stats;
+mod summary;
+mod sweep;
+mod sync;
+mod tags;
+mod times;
+mod transfer;
+mod tree;
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+mod uring;
 mod util;
+mod validation;
+mod watch;
+#[cfg(all(feature = "xattr", unix))]
+mod xattrs;
+
+pub use claim::ClaimGuard;
+pub use diff::{DirDiff, FileDiff, LineChange};
+pub use fill::FillGuard;
+#[cfg(feature = "glob")]
+pub use filter::Filter;
+pub use guards::{CurrentDirGuard, EnvVarGuard};
+pub use locking::FileLockGuard;
+pub use marker::Marker;
+pub use newline::Newline;
+#[cfg(feature = "ctrlc")]
+pub(crate) use policy::keep_requested_by_env;
+pub use random::TreeSpec;
+pub use retry::RetryPolicy;
+pub use stages::{Promotion, Stages};
+pub use stats::IoStats;
+pub use summary::Summary;
+pub use sweep::SweepPolicy;
+pub use sync::{SyncPolicy, SyncReport};
+pub use tree::{Tree, TreeEntry};
+pub use watch::Follow;
@@ -0,0 +1,122 @@
+use super::*;
+
+use std::path::Path;
+
+/// Line endings applied to text written with `Directory::write_string` and friends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Newline {
+    /// Unix line endings (`\n`).
+    Lf,
+    /// Windows line endings (`\r\n`).
+    CrLf,
+    /// The line endings of the current platform (`\r\n` on Windows, `\n` elsewhere).
+    Native,
+}
+
+impl Newline {
+    /// Returns the line ending as a string.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Newline::Lf => "\n",
+            Newline::CrLf => "\r\n",
+            Newline::Native if cfg!(windows) => "\r\n",
+            Newline::Native => "\n",
+        }
+    }
+
+    /// Returns the given text with all line endings (`\n` or `\r\n`) replaced by this one.
+    pub fn apply(self, text: &str) -> String {
+        let text = text.replace("\r\n", "\n");
+        match self.as_str() {
+            "\n" => text,
+            newline => text.replace('\n', newline),
+        }
+    }
+}
+
+/// Methods for controlling the line endings of text files written to the directory,
+/// e.g. for files consumed by Windows-only tools. By default, text is written as is.
+impl Directory {
+    /// Creates a new Directory instance from self that converts the line endings of text
+    /// written with `write_string`, `write_string_new`, `write_json`, and `write_toml`.
+    /// With the `csv` feature, `write_csv` and `append_csv_record` end rows with them
+    /// as well, but leave line breaks within quoted fields as they are.
+    /// Files that the directory maintains itself, such as the marker and `.gitignore`,
+    /// are always written with `\n`.
+    ///
+    /// # Arguments
+    /// * `newline` - The line endings to write.
+    pub fn with_newline(mut self, newline: Newline) -> Self {
+        self.newline = Some(newline);
+        self
+    }
+
+    /// Writes a string with the given line endings to a file at the given path within
+    /// the directory, regardless of the directory's newline setting.
+    /// Panics if the path is absolute or if the write operation fails.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    /// * `content` - The text to write.
+    /// * `newline` - The line endings to write.
+    pub fn write_string_with_newline<P: AsRef<Path>, S: AsRef<str>>(
+        &self,
+        relative_path: P,
+        content: S,
+        newline: Newline,
+    ) {
+        self.write_bytes(relative_path, newline.apply(content.as_ref()));
+    }
+
+    /// Returns the given text with the directory's line endings applied, if any.
+    pub(super) fn apply_newline(&self, content: String) -> String {
+        match self.newline {
+            Some(newline) => newline.apply(&content),
+            None => content,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn apply() {
+        assert_eq!(Newline::Lf.apply("a\r\nb\nc"), "a\nb\nc");
+        assert_eq!(Newline::CrLf.apply("a\r\nb\nc\n"), "a\r\nb\r\nc\r\n");
+        assert_eq!(
+            Newline::Native.apply("a\nb"),
+            if cfg!(windows) { "a\r\nb" } else { "a\nb" }
+        );
+    }
+
+    #[test]
+    fn with_newline() {
+        let temp_dir = tempdir().unwrap();
+        let directory =
+            Directory::create(temp_dir.path().join("test_dir")).with_newline(Newline::CrLf);
+
+        directory.write_string("file.txt", "a\nb\n");
+        directory.write_string_new("new.txt", "a\n").unwrap();
+
+        let content = std::fs::read(directory.path().join("file.txt")).unwrap();
+        assert_eq!(content, b"a\r\nb\r\n");
+        let content = std::fs::read(directory.path().join("new.txt")).unwrap();
+        assert_eq!(content, b"a\r\n");
+    }
+
+    #[test]
+    fn write_string_with_newline() {
+        let temp_dir = tempdir().unwrap();
+        let directory =
+            Directory::create(temp_dir.path().join("test_dir")).with_newline(Newline::CrLf);
+
+        directory.write_string_with_newline("file.txt", "a\r\nb", Newline::Lf);
+
+        let content = std::fs::read(directory.path().join("file.txt")).unwrap();
+        assert_eq!(content, b"a\nb");
+    }
+}
@@ -0,0 +1,79 @@
+use super::*;
+
+use std::borrow::Cow;
+use std::path::Path;
+use unicode_normalization::{IsNormalized, UnicodeNormalization, is_nfc_quick};
+
+/// Methods for handling Unicode file names consistently across platforms.
+///
+/// The same logical file name can be encoded in different Unicode normalization forms,
+/// e.g. "é" as one precomposed character (NFC, common on Linux and Windows) or as "e"
+/// followed by a combining accent (NFD, produced by macOS). Such names look identical
+/// but compare as different, which breaks comparisons of trees created on different
+/// platforms.
+impl Directory {
+    /// Creates a new Directory instance from self that normalizes file names to
+    /// Unicode NFC: relative paths passed to the `write_*` methods are normalized
+    /// before writing, and entry names are normalized in `diff_dir` and `bundle_to`.
+    pub fn with_nfc_names(mut self) -> Self {
+        self.nfc_names = true;
+        self
+    }
+
+    /// Returns the given relative path normalized to NFC if name normalization is enabled.
+    /// Paths that are not valid UTF-8 are returned unchanged.
+    pub(super) fn normalize_name<'a>(&self, relative_path: &'a Path) -> Cow<'a, Path> {
+        match relative_path.to_str() {
+            Some(name) if self.nfc_names && is_nfc_quick(name.chars()) != IsNormalized::Yes => {
+                Cow::Owned(PathBuf::from(name.nfc().collect::<String>()))
+            }
+            _ => Cow::Borrowed(relative_path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    const NFC_NAME: &str = "caf\u{e9}.txt";
+    const NFD_NAME: &str = "cafe\u{301}.txt";
+
+    #[test]
+    fn with_nfc_names() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_nfc_names();
+
+        directory.write_string(NFD_NAME, "");
+
+        let names = std::fs::read_dir(directory.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, [NFC_NAME]);
+    }
+
+    #[test]
+    fn normalize_name_disabled() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        assert_eq!(
+            directory.normalize_name(Path::new(NFD_NAME)),
+            Path::new(NFD_NAME)
+        );
+    }
+
+    #[test]
+    fn diff_dir_with_nfc_names() {
+        let temp_dir = tempdir().unwrap();
+        let actual = Directory::create(temp_dir.path().join("actual")).with_nfc_names();
+        let expected = Directory::create(temp_dir.path().join("expected"));
+        actual.write_string(NFC_NAME, "content");
+        expected.write_string(NFD_NAME, "content");
+
+        assert!(actual.diff_dir(&expected).is_equal());
+    }
+}
@@ -0,0 +1,125 @@
+use super::*;
+
+use std::os::unix::fs::lchown;
+use std::path::Path;
+
+use crate::util::validate_relative_path;
+use crate::{Error, Result};
+
+/// Methods for changing the owner and group of entries on Unix, e.g. for provisioning
+/// tools that run as root and hand prepared working directories off to service users.
+///
+/// Symlinks themselves are changed, not their targets, so entries outside of the
+/// directory are never touched. Changing the owner usually requires root privileges
+/// (or `CAP_CHOWN`), while the group can be changed to any group the user is a member of.
+impl Directory {
+    /// Changes the owner and group of the entry at the given path within the directory,
+    /// like `chown` and `chgrp`.
+    /// Returns `Error::PathIsAbsolute` if the path is absolute, or `Error::Io` if the
+    /// ownership cannot be changed, e.g. because of missing privileges.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the entry within the directory.
+    /// * `uid` - The new owner, or `None` to keep the owner.
+    /// * `gid` - The new group, or `None` to keep the group.
+    pub fn set_owner<P: AsRef<Path>>(
+        &self,
+        relative_path: P,
+        uid: Option<u32>,
+        gid: Option<u32>,
+    ) -> Result<()> {
+        let relative_path = relative_path.as_ref();
+        validate_relative_path(relative_path)?;
+        let entry_path = self.path.join(relative_path);
+        lchown(&entry_path, uid, gid).map_err(|e| Error::io(entry_path, e))
+    }
+
+    /// Changes the owner and group of the entry at the given path within the directory
+    /// and of all entries below it, like `chown -R`. Use `""` for the whole directory.
+    /// The entries below it are walked like in the other bulk operations, i.e. with the
+    /// filter and the maximum depth of the directory (see `with_filter` and `with_max_depth`).
+    /// Returns `Error::PathIsAbsolute` if the path is absolute, or the first error
+    /// of walking the tree or changing the ownership of an entry.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the entry within the directory.
+    /// * `uid` - The new owner, or `None` to keep the owners.
+    /// * `gid` - The new group, or `None` to keep the groups.
+    pub fn set_owner_recursive<P: AsRef<Path>>(
+        &self,
+        relative_path: P,
+        uid: Option<u32>,
+        gid: Option<u32>,
+    ) -> Result<()> {
+        let relative_path = relative_path.as_ref();
+        self.set_owner(relative_path, uid, gid)?;
+        let root = self.path.join(relative_path);
+        if !std::fs::symlink_metadata(&root)
+            .map_err(|e| Error::io(&root, e))?
+            .is_dir()
+        {
+            return Ok(());
+        }
+        for entry in self.walk_tree(&root)? {
+            let entry_path = root.join(entry);
+            lchown(&entry_path, uid, gid).map_err(|e| Error::io(entry_path, e))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::os::unix::fs::MetadataExt;
+    use tempfile::tempdir;
+
+    #[test]
+    fn set_owner() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("file.txt", "");
+        let metadata = std::fs::metadata(directory.path().join("file.txt")).unwrap();
+
+        // Changing the group to the current group is allowed without privileges.
+        directory
+            .set_owner("file.txt", None, Some(metadata.gid()))
+            .unwrap();
+        let result = directory.set_owner("missing.txt", None, Some(metadata.gid()));
+
+        assert!(matches!(result, Err(Error::Io { .. })));
+    }
+
+    #[test]
+    fn set_owner_recursive() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        std::fs::create_dir_all(directory.path().join("a/b")).unwrap();
+        directory.write_string("a/b/file.txt", "");
+        std::os::unix::fs::symlink(temp_dir.path(), directory.path().join("a/link")).unwrap();
+        let metadata = std::fs::metadata(directory.path()).unwrap();
+
+        directory
+            .set_owner_recursive("", Some(metadata.uid()), Some(metadata.gid()))
+            .unwrap();
+
+        for entry in ["a", "a/b", "a/b/file.txt", "a/link"] {
+            let entry_metadata = std::fs::symlink_metadata(directory.path().join(entry)).unwrap();
+            assert_eq!(
+                (entry_metadata.uid(), entry_metadata.gid()),
+                (metadata.uid(), metadata.gid())
+            );
+        }
+    }
+
+    #[test]
+    fn set_owner_rejects_absolute_path() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        let result = directory.set_owner_recursive(temp_dir.path(), None, None);
+
+        assert!(matches!(result, Err(Error::PathIsAbsolute(_))));
+    }
+}
@@ -0,0 +1,82 @@
+//! Parallel removal of directory trees.
+//!
+//! Removing a tree with hundreds of thousands of files one file at a time is slow,
+//! especially on network and virtualized file systems where each call has a high latency.
+//! The entries of each directory are therefore removed in parallel on the rayon thread pool.
+
+use rayon::prelude::*;
+use std::io;
+use std::path::Path;
+
+/// Removes the directory at `path` and all of its contents, like `std::fs::remove_dir_all`.
+/// Symlinks are removed without following them, so nothing outside the tree is touched.
+/// If some entries cannot be removed, the remaining entries are still removed
+/// and the first error is returned.
+pub(super) fn remove_dir_all(path: &Path) -> io::Result<()> {
+    let entries = std::fs::read_dir(path)?.collect::<io::Result<Vec<_>>>()?;
+    entries
+        .into_par_iter()
+        .map(|entry| {
+            if entry.file_type()?.is_dir() {
+                remove_dir_all(&entry.path())
+            } else {
+                remove_entry(&entry.path())
+            }
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .collect::<io::Result<()>>()?;
+    std::fs::remove_dir(path)
+}
+
+/// Removes a single non-directory entry.
+/// On Windows, symlinks to directories and junctions need `remove_dir` instead.
+fn remove_entry(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        #[cfg(windows)]
+        Err(e) => std::fs::remove_dir(path).map_err(|_| e),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn remove_tree() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("root");
+        for i in 0..10 {
+            let subdir = root.join(format!("dir{i}/nested"));
+            std::fs::create_dir_all(&subdir).unwrap();
+            for j in 0..10 {
+                std::fs::write(subdir.join(format!("file{j}.txt")), b"content").unwrap();
+            }
+        }
+
+        remove_dir_all(&root).unwrap();
+
+        assert!(!root.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn does_not_follow_symlinks() {
+        let temp_dir = tempdir().unwrap();
+        let outside = temp_dir.path().join("outside");
+        std::fs::create_dir(&outside).unwrap();
+        std::fs::write(outside.join("file.txt"), b"content").unwrap();
+        let root = temp_dir.path().join("root");
+        std::fs::create_dir(&root).unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+        remove_dir_all(&root).unwrap();
+
+        assert!(!root.exists());
+        assert!(outside.join("file.txt").exists());
+    }
+}
@@ -0,0 +1,91 @@
+use super::*;
+
+use ::parquet::arrow::ArrowWriter;
+use arrow_array::RecordBatch;
+use std::path::Path;
+
+use crate::util::assert_relative_path;
+
+/// Methods for writing columnar data as Parquet files, so data pipelines can keep
+/// their artifacts under the management of the directory.
+impl Directory {
+    /// Writes Arrow record batches to a Parquet file at the given path within the directory.
+    /// The schema of the file is taken from the first batch; all batches must share it.
+    /// Panics if the path is absolute, if `batches` is empty, if the batches have different
+    /// schemas, or if the write operation fails.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    /// * `batches` - The record batches to write, in order.
+    pub fn write_parquet<P: AsRef<Path>>(&self, relative_path: P, batches: &[RecordBatch]) {
+        assert_relative_path(relative_path.as_ref());
+        let relative_path = self.normalize_name(relative_path.as_ref());
+        self.check_path(&relative_path)
+            .unwrap_or_else(|e| panic!("{e}"));
+        let file_path = self.path.join(&relative_path);
+        let Some(first) = batches.first() else {
+            panic!(
+                "Failed to write Parquet file at {}: no record batches",
+                file_path.display()
+            );
+        };
+        let write = || -> ::parquet::errors::Result<()> {
+            let file = std::fs::File::create(&file_path)?;
+            let mut writer = ArrowWriter::try_new(file, first.schema(), None)?;
+            for batch in batches {
+                writer.write(batch)?;
+            }
+            writer.close()?;
+            Ok(self.sync_if_durable(&file_path)?)
+        };
+        write().unwrap_or_else(|e| {
+            panic!(
+                "Failed to write Parquet file at {}: {e}",
+                file_path.display()
+            )
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ::parquet::file::reader::{FileReader, SerializedFileReader};
+    use arrow_array::{ArrayRef, Int32Array, StringArray};
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    fn batch(ids: Vec<i32>, names: Vec<&str>) -> RecordBatch {
+        RecordBatch::try_from_iter([
+            ("id", Arc::new(Int32Array::from(ids)) as ArrayRef),
+            ("name", Arc::new(StringArray::from(names)) as ArrayRef),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn write_parquet() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_parquet(
+            "data.parquet",
+            &[batch(vec![1, 2], vec!["a", "b"]), batch(vec![3], vec!["c"])],
+        );
+
+        let file = std::fs::File::open(directory.path().join("data.parquet")).unwrap();
+        let metadata = SerializedFileReader::new(file).unwrap().metadata().clone();
+        assert_eq!(metadata.file_metadata().num_rows(), 3);
+        assert_eq!(metadata.file_metadata().schema_descr().num_columns(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "no record batches")]
+    fn write_parquet_no_batches() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_parquet("data.parquet", &[]);
+    }
+}
@@ -0,0 +1,84 @@
+use super::*;
+
+use std::path::Path;
+
+use crate::util::assert_relative_path;
+
+/// Methods for changing the permissions of entries within the directory.
+impl Directory {
+    /// Makes the file at the given path within the directory executable, e.g. a generated
+    /// shell script or Git hook. On Unix, the executable bit is set for everyone who may
+    /// read the file, like `chmod +x` does for a file with the default umask.
+    /// On other platforms, whether a file is executable depends on its extension,
+    /// so the file is left unchanged.
+    /// Panics if the path is absolute or if the permissions cannot be changed,
+    /// e.g. because the file does not exist.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let dir = Directory::cargo_target_subdir("make_executable_example");
+    /// dir.write_string("pre-commit", "#!/bin/sh\nexit 0\n");
+    /// dir.make_executable("pre-commit");
+    /// ```
+    pub fn make_executable<P: AsRef<Path>>(&self, relative_path: P) {
+        let relative_path = relative_path.as_ref();
+        assert_relative_path(relative_path);
+        let file_path = self.path.join(relative_path);
+        #[cfg(unix)]
+        let result = std::fs::metadata(&file_path).and_then(|metadata| {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = metadata.permissions().mode();
+            std::fs::set_permissions(
+                &file_path,
+                std::fs::Permissions::from_mode(mode | (mode & 0o444) >> 2),
+            )
+        });
+        #[cfg(not(unix))]
+        let result = std::fs::metadata(&file_path).map(|_| ());
+        result.unwrap_or_else(|e| {
+            panic!(
+                "Failed to make file at {} executable: {e}",
+                file_path.display()
+            )
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[cfg(unix)]
+    #[test]
+    fn make_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("run.sh", "#!/bin/sh\n");
+        let file_path = directory.path().join("run.sh");
+        std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+        directory.make_executable("run.sh");
+
+        let mode = std::fs::metadata(&file_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o750);
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to make file at")]
+    fn make_executable_missing_file() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.make_executable("missing.sh");
+    }
+}
@@ -0,0 +1,207 @@
+use super::*;
+
+/// Environment variable that, if set to `1`, `true`, or `yes`,
+/// causes all directories to be kept when they are dropped.
+const KEEP_ENV_VAR: &str = "CONV_WD_KEEP";
+
+/// Methods for changing the drop policy at runtime.
+impl Directory {
+    /// Marks the directory as belonging to a failed run.
+    /// A temporary directory is converted into a persistent one, so it is not removed
+    /// when dropped, and its path is printed to stderr for later inspection.
+    /// Directories that are already persistent are not affected.
+    pub fn mark_failed(&mut self) {
+        if self.keep_on_drop {
+            return;
+        }
+        #[cfg(feature = "ctrlc")]
+        crate::signal::unregister(&self.path);
+        self.keep_on_drop = true;
+        self.update_marker();
+        eprintln!(
+            "Keeping directory {} for inspection after failure",
+            self.path.display()
+        );
+    }
+
+    /// Creates a new Directory instance from self that, when it is removed on drop,
+    /// also removes the parent directories that were created along with it.
+    ///
+    /// By default, only the directory itself is removed, even if the constructor had to
+    /// create missing parent directories. With this option, the created parents are removed
+    /// as well, from the innermost one up to the first created component (the base).
+    /// Parents that existed before are never removed, and neither are created parents
+    /// that are no longer empty, e.g. because they are shared with other directories.
+    /// This does not affect `remove_async` and `with_background_drop`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let base = Directory::cargo_target_subdir("remove_base_if_created_example");
+    /// let nested = base.path().join("created/by/create");
+    /// drop(Directory::create(&nested).remove_base_if_created());
+    /// assert!(!base.path().join("created").exists());
+    /// assert!(base.path().exists());
+    /// ```
+    pub fn remove_base_if_created(mut self) -> Self {
+        self.remove_base_if_created = true;
+        self
+    }
+
+    /// Removes the parent directories that were created along with the directory,
+    /// if requested with `remove_base_if_created`. Stops at the first parent that
+    /// cannot be removed, e.g. because it is not empty.
+    pub(super) fn remove_created_parents(&self) {
+        let Some(base) = self
+            .created_base
+            .as_ref()
+            .filter(|_| self.remove_base_if_created)
+        else {
+            return;
+        };
+        for parent in self.path.ancestors().skip(1) {
+            if !parent.starts_with(base) || std::fs::remove_dir(parent).is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Returns whether the directory will be kept when dropped.
+    /// This is the case if it is persistent or if the `CONV_WD_KEEP`
+    /// environment variable requests to keep all directories.
+    pub fn is_kept(&self) -> bool {
+        self.keep_on_drop || keep_requested_by_env()
+    }
+}
+
+/// Returns whether the `CONV_WD_KEEP` environment variable requests to keep all directories.
+pub(crate) fn keep_requested_by_env() -> bool {
+    std::env::var(KEEP_ENV_VAR).is_ok_and(|value| is_truthy(&value))
+}
+
+/// Returns whether the given environment variable value is considered as enabled.
+pub(super) fn is_truthy(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "1" | "true" | "yes"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn mark_failed() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("failed_dir");
+
+        {
+            let mut directory = Directory::create(&dir_path);
+            directory.mark_failed();
+            assert!(directory.is_kept());
+        }
+        assert!(dir_path.exists());
+        assert!(dir_path.is_dir());
+    }
+
+    #[test]
+    fn drop_keeps_created_parents_by_default() {
+        let temp_dir = tempdir().unwrap();
+
+        drop(Directory::create(temp_dir.path().join("a/b/leaf")));
+
+        assert!(!temp_dir.path().join("a/b/leaf").exists());
+        assert!(temp_dir.path().join("a/b").is_dir());
+    }
+
+    #[test]
+    fn remove_base_if_created() {
+        let temp_dir = tempdir().unwrap();
+
+        drop(Directory::create(temp_dir.path().join("a/b/leaf")).remove_base_if_created());
+
+        assert!(!temp_dir.path().join("a").exists());
+        assert!(temp_dir.path().is_dir());
+    }
+
+    #[test]
+    fn remove_base_if_created_keeps_existing_parents() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::create_dir(temp_dir.path().join("a")).unwrap();
+
+        drop(Directory::create(temp_dir.path().join("a/b/leaf")).remove_base_if_created());
+
+        assert!(!temp_dir.path().join("a/b").exists());
+        assert!(temp_dir.path().join("a").is_dir());
+    }
+
+    #[test]
+    fn remove_base_if_created_with_existing_directory() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("a/leaf")).unwrap();
+
+        drop(Directory::create(temp_dir.path().join("a/leaf")).remove_base_if_created());
+
+        assert!(!temp_dir.path().join("a/leaf").exists());
+        assert!(temp_dir.path().join("a").is_dir());
+    }
+
+    #[test]
+    fn remove_base_if_created_keeps_non_empty_parents() {
+        let temp_dir = tempdir().unwrap();
+
+        let directory =
+            Directory::create(temp_dir.path().join("a/b/leaf")).remove_base_if_created();
+        std::fs::write(temp_dir.path().join("a/shared.txt"), "").unwrap();
+        drop(directory);
+
+        assert!(!temp_dir.path().join("a/b").exists());
+        assert!(temp_dir.path().join("a/shared.txt").is_file());
+    }
+
+    #[test]
+    fn remove_base_if_created_with_create_new() {
+        let temp_dir = tempdir().unwrap();
+
+        let directory = Directory::create_new(temp_dir.path().join("a/leaf")).unwrap();
+        drop(directory.remove_base_if_created());
+
+        assert!(!temp_dir.path().join("a").exists());
+    }
+
+    #[test]
+    fn remove_base_if_created_when_kept() {
+        let temp_dir = tempdir().unwrap();
+
+        drop(
+            Directory::create(temp_dir.path().join("a/leaf"))
+                .remove_base_if_created()
+                .keep(),
+        );
+
+        assert!(temp_dir.path().join("a/leaf").is_dir());
+    }
+
+    #[test]
+    fn is_kept() {
+        let temp_dir = tempdir().unwrap();
+
+        let persistent = Directory::create(temp_dir.path().join("persistent")).keep();
+
+        assert!(persistent.is_kept());
+    }
+
+    #[test]
+    fn truthy_values() {
+        for value in ["1", "true", "TRUE", "yes", " Yes "] {
+            assert!(is_truthy(value), "{value:?} should be truthy");
+        }
+        for value in ["", "0", "false", "no", "keep"] {
+            assert!(!is_truthy(value), "{value:?} should not be truthy");
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use super::*;
+
+use crate::{Error, Result};
+
+/// Methods for empirically detecting properties of the underlying file system,
+/// so cross-platform test suites can skip or adapt platform-specific scenarios.
+impl Directory {
+    /// Returns whether the file system of the directory distinguishes file names
+    /// that only differ in case. This is detected by creating a probe file with a
+    /// lowercase name and checking whether it is found under its uppercase name,
+    /// since case sensitivity can differ between volumes (and even directories)
+    /// of the same platform.
+    /// Panics if the probe file cannot be created or removed.
+    pub fn is_case_sensitive(&self) -> bool {
+        let name = format!(".conv-wd-case-probe-{}", std::process::id());
+        let probe_path = self.path.join(&name);
+        std::fs::File::create_new(&probe_path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to create probe file at {}: {e}",
+                probe_path.display()
+            )
+        });
+        let case_sensitive =
+            std::fs::symlink_metadata(self.path.join(name.to_uppercase())).is_err();
+        std::fs::remove_file(&probe_path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to remove probe file at {}: {e}",
+                probe_path.display()
+            )
+        });
+        case_sensitive
+    }
+
+    /// Returns the number of bytes available to the current user on the file system
+    /// of the directory (via `statvfs` on Unix and `GetDiskFreeSpaceEx` on Windows),
+    /// e.g. to refuse to start a pipeline when there is not enough headroom.
+    /// Returns `Error::Io` if the available space cannot be determined.
+    pub fn available_space(&self) -> Result<u64> {
+        fs4::available_space(&self.path).map_err(|e| Error::io(&self.path, e))
+    }
+
+    /// Returns whether files can be created in the directory, e.g. to fail early with
+    /// a clear message when a tool is pointed at a read-only mount.
+    /// This is detected by actually creating and removing a probe file, since permissions
+    /// alone don't reflect read-only mounts, ACLs, or quotas.
+    /// Panics if the probe file was created but cannot be removed.
+    pub fn is_writable(&self) -> bool {
+        let Ok(probe) = tempfile::Builder::new()
+            .prefix(".conv-wd-write-probe-")
+            .tempfile_in(&self.path)
+        else {
+            return false;
+        };
+        let probe_path = probe.path().to_path_buf();
+        probe.close().unwrap_or_else(|e| {
+            panic!(
+                "Failed to remove probe file at {}: {e}",
+                probe_path.display()
+            )
+        });
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn is_case_sensitive() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("file.txt", "");
+        let expected = !directory.path().join("FILE.TXT").exists();
+
+        assert_eq!(directory.is_case_sensitive(), expected);
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn available_space() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        let available = directory.available_space().unwrap();
+
+        assert!(available > 0);
+        assert!(available <= fs4::total_space(directory.path()).unwrap());
+    }
+
+    #[test]
+    fn is_writable() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        assert!(directory.is_writable());
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn is_writable_read_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let set_mode = |mode| {
+            std::fs::set_permissions(directory.path(), std::fs::Permissions::from_mode(mode))
+                .unwrap()
+        };
+        set_mode(0o500);
+
+        // Permissions don't apply to root, so only check that the probe is consistent.
+        let writable = directory.is_writable();
+        let expected = std::fs::File::create(directory.path().join("file.txt")).is_ok();
+        set_mode(0o700);
+
+        assert_eq!(writable, expected);
+    }
+}
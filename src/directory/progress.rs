@@ -0,0 +1,155 @@
+use super::*;
+
+#[cfg(feature = "indicatif")]
+use indicatif::ProgressBar;
+#[cfg(feature = "indicatif")]
+use std::path::Path;
+
+/// Methods for reporting the progress of long-running operations on huge trees.
+#[cfg(feature = "indicatif")]
+impl Directory {
+    /// Creates a new Directory instance from self that reports the progress of
+    /// long-running operations (e.g. `clean`) on the given progress bar.
+    /// The length and position of the bar are reset at the start of each operation,
+    /// and the bar is finished when the operation completes.
+    ///
+    /// # Arguments
+    /// * `bar` - The progress bar to drive, e.g. `ProgressBar::new(0)`.
+    pub fn with_progress(mut self, bar: ProgressBar) -> Self {
+        self.progress = Some(bar);
+        self
+    }
+
+    /// Returns the progress bar of the directory, if any.
+    pub fn progress(&self) -> Option<&ProgressBar> {
+        self.progress.as_ref()
+    }
+
+    /// Removes all files within the directory one by one while advancing the progress bar,
+    /// leaving only the (empty) subdirectories behind.
+    /// Does nothing if the directory has no progress bar.
+    /// Panics if a file cannot be removed.
+    pub(super) fn remove_files_with_progress(&self) {
+        if self.progress.is_none() {
+            return;
+        }
+        let mut files = Vec::new();
+        collect_files(&self.path, &mut files);
+        self.progress_start(files.len(), || format!("Removing {}", self.path.display()));
+        for file in files {
+            match std::fs::remove_file(&file) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    self.record_error();
+                    panic!("Failed to remove file at {}: {e}", file.display())
+                }
+                Ok(()) => {
+                    self.record_removed(1);
+                    self.progress_inc();
+                }
+                Err(_) => self.progress_inc(),
+            }
+        }
+        self.progress_finish();
+    }
+}
+
+/// Internal hooks for long-running operations.
+/// Without the `indicatif` feature (or without a progress bar), these do nothing.
+impl Directory {
+    /// Starts reporting an operation with `len` steps on the progress bar.
+    pub(super) fn progress_start(&self, len: usize, message: impl FnOnce() -> String) {
+        #[cfg(feature = "indicatif")]
+        if let Some(bar) = &self.progress {
+            bar.reset();
+            bar.set_length(len as u64);
+            bar.set_message(message());
+        }
+        #[cfg(not(feature = "indicatif"))]
+        let _ = (len, message);
+    }
+
+    /// Advances the progress bar by one step.
+    pub(super) fn progress_inc(&self) {
+        #[cfg(feature = "indicatif")]
+        if let Some(bar) = &self.progress {
+            bar.inc(1);
+        }
+    }
+
+    /// Advances the progress bar by the given number of steps, e.g. bytes.
+    pub(super) fn progress_inc_by(&self, steps: u64) {
+        #[cfg(feature = "indicatif")]
+        if let Some(bar) = &self.progress {
+            bar.inc(steps);
+        }
+        #[cfg(not(feature = "indicatif"))]
+        let _ = steps;
+    }
+
+    /// Finishes the progress bar at the end of an operation.
+    pub(super) fn progress_finish(&self) {
+        #[cfg(feature = "indicatif")]
+        if let Some(bar) = &self.progress {
+            bar.finish();
+        }
+    }
+}
+
+/// Appends the paths of all non-directory entries below `path` to `files`.
+/// Symlinks are collected as files and not followed.
+/// Entries that cannot be read are skipped, since the subsequent removal reports them.
+#[cfg(feature = "indicatif")]
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.filter_map(Result::ok) {
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            collect_files(&entry.path(), files);
+        } else {
+            files.push(entry.path());
+        }
+    }
+}
+
+#[cfg(all(test, feature = "indicatif"))]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn clean_with_progress() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+        std::fs::create_dir_all(dir_path.join("subdir")).unwrap();
+        std::fs::write(dir_path.join("file.txt"), b"").unwrap();
+        std::fs::write(dir_path.join("subdir/nested.txt"), b"").unwrap();
+
+        let directory = Directory::create(&dir_path)
+            .with_progress(ProgressBar::hidden())
+            .clean();
+
+        let bar = directory.progress().unwrap();
+        assert!(bar.is_finished());
+        assert_eq!(bar.length(), Some(2));
+        assert_eq!(bar.position(), 2);
+        assert!(std::fs::read_dir(&dir_path).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn copy_file_from_with_progress() {
+        let temp_dir = tempdir().unwrap();
+        let source = temp_dir.path().join("source.bin");
+        std::fs::write(&source, vec![0; 1000]).unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"))
+            .with_progress(ProgressBar::hidden());
+
+        directory.copy_file_from(&source, "copy.bin");
+
+        let bar = directory.progress().unwrap();
+        assert!(bar.is_finished());
+        assert_eq!(bar.length(), Some(1000));
+        assert_eq!(bar.position(), 1000);
+    }
+}
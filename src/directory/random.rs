@@ -0,0 +1,281 @@
+use super::*;
+
+use std::hash::{BuildHasher, RandomState};
+use std::path::Path;
+
+/// Specification of a tree of random files for `Directory::generate_random_tree`.
+/// The files are distributed round-robin across the subdirectories `dir0`, `dir1`, ...
+/// (or placed directly in the directory if `subdirs` is zero) and named `file0.bin`,
+/// `file1.bin`, ... Their sizes are uniformly distributed between `min_file_size`
+/// and `max_file_size` (inclusive).
+/// If a `seed` is given, the generated tree is byte-identical across runs and machines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TreeSpec {
+    /// Number of files to generate.
+    pub files: usize,
+    /// Number of subdirectories to distribute the files across.
+    pub subdirs: usize,
+    /// Minimum size of each file in bytes.
+    pub min_file_size: u64,
+    /// Maximum size of each file in bytes.
+    pub max_file_size: u64,
+    /// Seed for the random content, or `None` for different content on every call.
+    pub seed: Option<u64>,
+}
+
+impl Default for TreeSpec {
+    /// Returns a spec for 10 files of up to 1 KiB directly in the directory.
+    fn default() -> Self {
+        Self {
+            files: 10,
+            subdirs: 0,
+            min_file_size: 0,
+            max_file_size: 1024,
+            seed: None,
+        }
+    }
+}
+
+/// Methods for filling the directory with random test data, e.g. for load and fuzz tests.
+///
+/// The seeded variants use a fixed generator (SplitMix64) that does not depend on the
+/// platform or on the version of any dependency, so the same seed always produces
+/// the same bytes, e.g. for reproducible benchmark inputs.
+impl Directory {
+    /// Writes `len` random bytes to a file at the given path within the directory.
+    /// Panics if the path is absolute or if the write operation fails.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    /// * `len` - The number of random bytes to write.
+    pub fn write_random_bytes<P: AsRef<Path>>(&self, relative_path: P, len: usize) {
+        self.write_bytes(relative_path, Rng::from_entropy().bytes(len));
+    }
+
+    /// Writes `len` pseudo-random bytes derived from `seed` to a file at the given path
+    /// within the directory. The same seed always produces the same content.
+    /// Panics if the path is absolute or if the write operation fails.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    /// * `len` - The number of random bytes to write.
+    /// * `seed` - The seed for the random content.
+    pub fn write_random_bytes_seeded<P: AsRef<Path>>(
+        &self,
+        relative_path: P,
+        len: usize,
+        seed: u64,
+    ) {
+        self.write_bytes(relative_path, Rng::new(seed).bytes(len));
+    }
+
+    /// Generates a tree of files with random content according to the given spec.
+    /// Returns the paths of the generated files relative to the directory.
+    /// Panics if `min_file_size` is greater than `max_file_size`,
+    /// or if a subdirectory or file cannot be written.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::{Directory, TreeSpec};
+    ///
+    /// let dir = Directory::cargo_target_subdir("generate_random_tree_example").clean();
+    /// let files = dir.generate_random_tree(&TreeSpec {
+    ///     files: 6,
+    ///     subdirs: 3,
+    ///     ..TreeSpec::default()
+    /// });
+    /// assert_eq!(files.len(), 6);
+    /// assert!(dir.path().join("dir2/file5.bin").is_file());
+    /// ```
+    pub fn generate_random_tree(&self, spec: &TreeSpec) -> Vec<PathBuf> {
+        assert!(
+            spec.min_file_size <= spec.max_file_size,
+            "Minimum file size {} exceeds maximum file size {}",
+            spec.min_file_size,
+            spec.max_file_size
+        );
+        let mut rng = spec.seed.map_or_else(Rng::from_entropy, Rng::new);
+        for i in 0..spec.subdirs {
+            let subdir = self.path.join(format!("dir{i}"));
+            std::fs::create_dir_all(&subdir).unwrap_or_else(|e| {
+                panic!("Failed to create directory at {}: {e}", subdir.display())
+            });
+        }
+        let files = (0..spec.files)
+            .map(|i| {
+                let file_name = format!("file{i}.bin");
+                let relative_path = match spec.subdirs {
+                    0 => PathBuf::from(file_name),
+                    subdirs => Path::new(&format!("dir{}", i % subdirs)).join(file_name),
+                };
+                let len = rng.in_range(spec.min_file_size, spec.max_file_size);
+                (relative_path, rng.bytes(len as usize))
+            })
+            .collect::<Vec<_>>();
+        let paths = files.iter().map(|(path, _)| path.clone()).collect();
+        self.write_batch(files);
+        paths
+    }
+}
+
+/// Small, fast pseudo-random number generator (SplitMix64) for test data.
+/// Not suitable for cryptographic purposes.
+pub(super) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator with the given seed.
+    pub(super) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Creates a generator with a seed that differs between calls and processes.
+    pub(super) fn from_entropy() -> Self {
+        Self::new(RandomState::new().hash_one(std::time::SystemTime::now()))
+    }
+
+    /// Returns the next random number.
+    pub(super) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random number between `min` and `max` (inclusive).
+    pub(super) fn in_range(&mut self, min: u64, max: u64) -> u64 {
+        match (max - min).checked_add(1) {
+            Some(span) => min + self.next_u64() % span,
+            None => self.next_u64(),
+        }
+    }
+
+    /// Returns `len` random bytes.
+    pub(super) fn bytes(&mut self, len: usize) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(len + 8);
+        while bytes.len() < len {
+            bytes.extend_from_slice(&self.next_u64().to_le_bytes());
+        }
+        bytes.truncate(len);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn write_random_bytes() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_random_bytes("first.bin", 1000);
+        directory.write_random_bytes("second.bin", 1000);
+
+        let first = std::fs::read(directory.path().join("first.bin")).unwrap();
+        let second = std::fs::read(directory.path().join("second.bin")).unwrap();
+        assert_eq!(first.len(), 1000);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn generate_random_tree() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let spec = TreeSpec {
+            files: 7,
+            subdirs: 3,
+            min_file_size: 10,
+            max_file_size: 20,
+            seed: None,
+        };
+
+        let files = directory.generate_random_tree(&spec);
+
+        assert_eq!(files.len(), 7);
+        assert_eq!(files[4], Path::new("dir1/file4.bin"));
+        for file in files {
+            let len = std::fs::metadata(directory.path().join(file))
+                .unwrap()
+                .len();
+            assert!((10..=20).contains(&len));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds maximum file size")]
+    fn generate_random_tree_invalid_sizes() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.generate_random_tree(&TreeSpec {
+            min_file_size: 2,
+            max_file_size: 1,
+            ..TreeSpec::default()
+        });
+    }
+
+    #[test]
+    fn write_random_bytes_seeded() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_random_bytes_seeded("first.bin", 1000, 42);
+        directory.write_random_bytes_seeded("second.bin", 1000, 42);
+        directory.write_random_bytes_seeded("third.bin", 1000, 43);
+
+        let first = std::fs::read(directory.path().join("first.bin")).unwrap();
+        let second = std::fs::read(directory.path().join("second.bin")).unwrap();
+        let third = std::fs::read(directory.path().join("third.bin")).unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, third);
+    }
+
+    #[test]
+    fn generate_random_tree_seeded() {
+        let temp_dir = tempdir().unwrap();
+        let first = Directory::create(temp_dir.path().join("first"));
+        let second = Directory::create(temp_dir.path().join("second"));
+        let spec = TreeSpec {
+            files: 5,
+            subdirs: 2,
+            seed: Some(7),
+            ..TreeSpec::default()
+        };
+
+        let files = first.generate_random_tree(&spec);
+        second.generate_random_tree(&spec);
+
+        for file in files {
+            assert_eq!(
+                std::fs::read(first.path().join(&file)).unwrap(),
+                std::fs::read(second.path().join(&file)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn rng_is_stable() {
+        // Changing these values breaks reproducibility of previously generated data.
+        let mut rng = Rng::new(0);
+
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+    }
+
+    #[test]
+    fn rng_in_range() {
+        let mut rng = Rng::new(42);
+
+        for _ in 0..100 {
+            assert!((5..=7).contains(&rng.in_range(5, 7)));
+        }
+        assert_eq!(rng.in_range(3, 3), 3);
+        rng.in_range(0, u64::MAX);
+    }
+}
@@ -0,0 +1,133 @@
+use std::time::{Duration, Instant};
+
+/// Policy for retrying file system operations that may fail spuriously.
+/// On Windows, removing a directory can fail briefly while an antivirus scanner
+/// or search indexer holds files open, so removals are retried with exponential backoff.
+/// Retrying stops after `max_attempts` attempts or once `max_duration` has elapsed,
+/// whichever comes first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled after each failed attempt.
+    pub initial_backoff: Duration,
+    /// Upper bound for the total time spent on all attempts.
+    pub max_duration: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates a new retry policy.
+    ///
+    /// # Arguments
+    /// * `max_attempts` - Maximum number of attempts, including the first one.
+    /// * `initial_backoff` - Delay before the first retry; doubled after each failed attempt.
+    /// * `max_duration` - Upper bound for the total time spent on all attempts.
+    pub const fn new(max_attempts: u32, initial_backoff: Duration, max_duration: Duration) -> Self {
+        Self {
+            max_attempts,
+            initial_backoff,
+            max_duration,
+        }
+    }
+
+    /// Creates a policy that makes a single attempt without retrying.
+    pub const fn none() -> Self {
+        Self::new(1, Duration::ZERO, Duration::ZERO)
+    }
+
+    /// Runs the given operation until it succeeds or the policy is exhausted.
+    /// Returns the result of the last attempt.
+    pub(crate) fn run<T, E>(&self, mut operation: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+        let start = Instant::now();
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            let result = operation();
+            if result.is_ok()
+                || attempt >= self.max_attempts
+                || start.elapsed() + backoff > self.max_duration
+            {
+                return result;
+            }
+            std::thread::sleep(backoff);
+            backoff = backoff.saturating_mul(2);
+            attempt += 1;
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Returns the default policy for the current platform.
+    /// On Windows, up to 10 attempts are made within 2 seconds, starting with a
+    /// backoff of 10 ms. On other platforms, failures are not retried.
+    fn default() -> Self {
+        if cfg!(windows) {
+            Self::new(10, Duration::from_millis(10), Duration::from_secs(2))
+        } else {
+            Self::none()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_until_success() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(1), Duration::from_secs(1));
+        let mut attempts = 0;
+
+        let result = policy.run(|| {
+            attempts += 1;
+            if attempts < 3 {
+                Err(attempts)
+            } else {
+                Ok(attempts)
+            }
+        });
+
+        assert_eq!(result, Ok(3));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn run_exhausts_attempts() {
+        let policy = RetryPolicy::new(4, Duration::from_millis(1), Duration::from_secs(1));
+        let mut attempts = 0;
+
+        let result: Result<(), u32> = policy.run(|| {
+            attempts += 1;
+            Err(attempts)
+        });
+
+        assert_eq!(result, Err(4));
+        assert_eq!(attempts, 4);
+    }
+
+    #[test]
+    fn run_respects_max_duration() {
+        let policy = RetryPolicy::new(100, Duration::from_millis(20), Duration::from_millis(50));
+        let mut attempts = 0;
+
+        let result: Result<(), u32> = policy.run(|| {
+            attempts += 1;
+            Err(attempts)
+        });
+
+        assert!(result.is_err());
+        assert!(attempts < 100);
+    }
+
+    #[test]
+    fn none() {
+        let mut attempts = 0;
+
+        let result: Result<(), u32> = RetryPolicy::none().run(|| {
+            attempts += 1;
+            Err(attempts)
+        });
+
+        assert_eq!(result, Err(1));
+    }
+}
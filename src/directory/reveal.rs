@@ -0,0 +1,52 @@
+use super::*;
+
+use std::path::Path;
+use std::process::Command;
+
+/// Methods for interacting with the desktop environment.
+impl Directory {
+    /// Opens the directory in the system file explorer
+    /// (Finder on macOS, Explorer on Windows, `xdg-open` elsewhere).
+    /// This method waits for the launcher command, which returns as soon as the
+    /// explorer has been started. Its exit status is ignored, since e.g. Explorer
+    /// reports failure even when it opened the directory.
+    /// This is handy for jumping to a directory retained by `keep_on_panic`.
+    /// Panics if the file explorer cannot be launched.
+    pub fn reveal(&self) {
+        reveal_command(&self.path).status().unwrap_or_else(|e| {
+            panic!(
+                "Failed to open directory at {} in file explorer: {e}",
+                self.path.display()
+            )
+        });
+    }
+}
+
+/// Returns the command that opens the given path in the system file explorer.
+fn reveal_command(path: &Path) -> Command {
+    let program = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    let mut command = Command::new(program);
+    command.arg(path);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reveal_command() {
+        let command = super::reveal_command(Path::new("some/dir"));
+
+        assert!(
+            ["open", "explorer", "xdg-open"].contains(&command.get_program().to_str().unwrap())
+        );
+        assert_eq!(command.get_args().collect::<Vec<_>>(), vec!["some/dir"]);
+    }
+}
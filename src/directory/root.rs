@@ -0,0 +1,33 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Environment variable that redirects the constructors that resolve their base
+/// directory themselves (`cargo_*` and `ci_*`) to an alternative root,
+/// e.g. a RAM disk or a per-job scratch volume.
+const ROOT_VAR: &str = "CONV_WD_ROOT";
+
+/// Returns the root set by `CONV_WD_ROOT` if it is set and not empty,
+/// and `default()` otherwise.
+pub(super) fn base_or_root(default: impl FnOnce() -> PathBuf) -> PathBuf {
+    root_from(std::env::var_os(ROOT_VAR)).unwrap_or_else(default)
+}
+
+/// Returns the root for the given value of `CONV_WD_ROOT`.
+fn root_from(value: Option<OsString>) -> Option<PathBuf> {
+    value.filter(|value| !value.is_empty()).map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_from() {
+        assert_eq!(
+            super::root_from(Some(OsString::from("/mnt/ramdisk"))),
+            Some(PathBuf::from("/mnt/ramdisk"))
+        );
+        assert_eq!(super::root_from(Some(OsString::new())), None);
+        assert_eq!(super::root_from(None), None);
+    }
+}
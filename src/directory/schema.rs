@@ -0,0 +1,128 @@
+use super::*;
+
+use crate::{Error, Result};
+use serde::Serialize;
+use std::path::Path;
+
+/// Methods for writing JSON artifacts that must conform to a contract.
+impl Directory {
+    /// Writes a serde-serializable object as JSON to a file at the given path within
+    /// the directory like `write_json`, but validates the JSON against a JSON Schema
+    /// first. Nothing is written if the validation fails.
+    /// Returns `Error::SchemaViolation` with all violations if the JSON does not conform
+    /// to the schema, or `Error::Serialization` if the object cannot be serialized.
+    /// Panics if the schema itself is invalid, if the path is absolute,
+    /// or if the write operation fails.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    /// * `obj` - The object to serialize.
+    /// * `schema` - The JSON Schema that the serialized object must conform to.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::{Directory, Error};
+    ///
+    /// let schema = serde_json::json!({
+    ///     "type": "object",
+    ///     "required": ["version"],
+    ///     "properties": { "version": { "type": "integer" } }
+    /// });
+    /// let dir = Directory::cargo_target_subdir("write_json_validated_example");
+    ///
+    /// let valid = serde_json::json!({ "version": 1 });
+    /// dir.write_json_validated("artifact", &valid, &schema).unwrap();
+    /// let invalid = serde_json::json!({ "version": "1" });
+    /// let result = dir.write_json_validated("invalid", &invalid, &schema);
+    /// assert!(matches!(result, Err(Error::SchemaViolation { .. })));
+    /// ```
+    pub fn write_json_validated<P: AsRef<Path>, T: Serialize>(
+        &self,
+        relative_path: P,
+        obj: &T,
+        schema: &serde_json::Value,
+    ) -> Result<()> {
+        let relative_path = relative_path.as_ref().with_extension("json");
+        let validator = jsonschema::validator_for(schema)
+            .unwrap_or_else(|e| panic!("Invalid JSON Schema: {e}"));
+        let instance = serde_json::to_value(obj).map_err(|e| Error::Serialization {
+            format: "JSON",
+            path: relative_path.clone(),
+            message: e.to_string(),
+        })?;
+        let violations = validator
+            .iter_errors(&instance)
+            .map(|e| format!("{}: {e}", display_location(&e.instance_path().to_string())))
+            .collect::<Vec<_>>();
+        if !violations.is_empty() {
+            return Err(Error::SchemaViolation {
+                path: self.path.join(relative_path),
+                violations,
+            });
+        }
+        self.write_string(&relative_path, files::to_json(&relative_path, obj)?);
+        Ok(())
+    }
+}
+
+/// Returns the given JSON pointer, or `/` for the root of the document.
+fn display_location(pointer: &str) -> &str {
+    if pointer.is_empty() { "/" } else { pointer }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn schema() -> serde_json::Value {
+        json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": { "type": "string" },
+                "count": { "type": "integer", "minimum": 0 }
+            }
+        })
+    }
+
+    #[test]
+    fn write_json_validated() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory
+            .write_json_validated("data", &json!({ "name": "a", "count": 1 }), &schema())
+            .unwrap();
+
+        assert_eq!(
+            directory.read_json::<_, serde_json::Value>("data"),
+            json!({ "name": "a", "count": 1 })
+        );
+    }
+
+    #[test]
+    fn write_json_validated_violation() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        let result = directory.write_json_validated("data", &json!({ "count": -1 }), &schema());
+
+        let Err(Error::SchemaViolation { path, violations }) = result else {
+            panic!("Expected a schema violation, got {result:?}");
+        };
+        assert_eq!(path, directory.path().join("data.json"));
+        assert_eq!(violations.len(), 2);
+        assert!(
+            violations.iter().any(|v| v.starts_with("/: ")),
+            "{violations:?}"
+        );
+        assert!(
+            violations.iter().any(|v| v.starts_with("/count: ")),
+            "{violations:?}"
+        );
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,215 @@
+use super::*;
+
+use crate::{Error, Result};
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+
+/// Scoped constructors that confine a directory to the execution of a closure.
+///
+/// Since the closure only borrows the directory, it cannot be leaked
+/// (e.g. with `std::mem::forget`) or made persistent, so the directory is
+/// guaranteed to be removed once the closure returns, fails, or panics.
+impl Directory {
+    /// Creates a directory at the given path, passes it to `f`, and removes it afterwards,
+    /// regardless of the drop policy. If `f` panics, the directory is removed before the
+    /// panic is resumed.
+    /// Returns the result of `f`, or `Error::Io` if `f` succeeded but the directory
+    /// could not be removed.
+    /// Panics if the directory cannot be created.
+    ///
+    /// # Arguments
+    /// * `path` - The path where the directory should be created.
+    /// * `f` - The closure that works with the directory.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let path = std::env::temp_dir().join("with_temp_example");
+    /// let content = Directory::with_temp(&path, |dir| {
+    ///     dir.write_string("file.txt", "Hello, scope!");
+    ///     Ok(std::fs::read_to_string(dir.path().join("file.txt")).unwrap())
+    /// })
+    /// .unwrap();
+    /// assert_eq!(content, "Hello, scope!");
+    /// assert!(!path.exists());
+    /// ```
+    pub fn with_temp<P, T, F>(path: P, f: F) -> Result<T>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(&Directory) -> Result<T>,
+    {
+        let directory = Self::create(path);
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| f(&directory)));
+        let removal = directory.remove_scoped();
+        match result {
+            Ok(result) => result.and_then(|value| removal.map(|()| value)),
+            Err(payload) => std::panic::resume_unwind(payload),
+        }
+    }
+
+    /// Async counterpart of `with_temp`: creates a directory at the given path, awaits
+    /// the future returned by `f`, and removes the directory afterwards on the blocking
+    /// thread pool of the current tokio runtime, regardless of the drop policy.
+    /// If the future panics, the directory is removed before the panic is resumed.
+    /// If the returned future itself is dropped before completion, the directory is
+    /// removed synchronously according to its drop policy.
+    /// Returns the result of the future, or `Error::Io` if it succeeded but the directory
+    /// could not be removed.
+    /// Panics if the directory cannot be created or if not called from within a tokio runtime.
+    ///
+    /// The future must be `'static` in its use of the directory, so `f` receives a handle
+    /// to the directory that never removes it when dropped. Cleanup is up to this function.
+    ///
+    /// # Arguments
+    /// * `path` - The path where the directory should be created.
+    /// * `f` - The closure that returns the future that works with the directory.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let path = std::env::temp_dir().join("with_temp_async_example");
+    /// Directory::with_temp_async(&path, |dir| async move {
+    ///     dir.write_string("file.txt", "Hello, async scope!");
+    ///     Ok(())
+    /// })
+    /// .await
+    /// .unwrap();
+    /// assert!(!path.exists());
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn with_temp_async<P, T, F, Fut>(path: P, f: F) -> Result<T>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(Directory) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        use std::task::Poll;
+
+        let directory = Self::create(path);
+        let mut handle = Self::new_unchecked(&directory.path);
+        handle.keep_on_drop = true;
+        let mut future = std::pin::pin!(f(handle));
+        let result = std::future::poll_fn(|cx| {
+            match std::panic::catch_unwind(AssertUnwindSafe(|| future.as_mut().poll(cx))) {
+                Ok(Poll::Ready(result)) => Poll::Ready(Ok(result)),
+                Ok(Poll::Pending) => Poll::Pending,
+                Err(payload) => Poll::Ready(Err(payload)),
+            }
+        })
+        .await;
+        let removal = directory.remove_async().await;
+        match result {
+            Ok(result) => result.and_then(|value| removal.map(|()| value)),
+            Err(payload) => std::panic::resume_unwind(payload),
+        }
+    }
+
+    /// Removes the directory at the end of its scope, regardless of the drop policy.
+    /// Failed attempts are retried according to the directory's retry policy.
+    pub(super) fn remove_scoped(mut self) -> Result<()> {
+        #[cfg(feature = "ctrlc")]
+        crate::signal::unregister(&self.path);
+        // Dropping the instance closes any open handle without removing the directory.
+        self.keep_on_drop = true;
+        let path = self.path.clone();
+        let policy = self.remove_retry;
+        drop(self);
+
+        util::remove_dir_with_retry(&path, &policy).map_err(|e| Error::io(path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn with_temp() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+
+        let value = Directory::with_temp(&dir_path, |dir| {
+            dir.write_string("file.txt", "content");
+            assert!(dir.path().join("file.txt").exists());
+            Ok(42)
+        })
+        .unwrap();
+
+        assert_eq!(value, 42);
+        assert!(!dir_path.exists());
+    }
+
+    #[test]
+    fn with_temp_error() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+
+        let result = Directory::with_temp(&dir_path, |dir| -> Result<()> {
+            Err(Error::FileExists(dir.path().join("file.txt")))
+        });
+
+        assert!(matches!(result, Err(Error::FileExists(_))));
+        assert!(!dir_path.exists());
+    }
+
+    #[test]
+    fn with_temp_panic() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+
+        let result = std::panic::catch_unwind(|| {
+            Directory::with_temp(&dir_path, |dir| -> Result<()> {
+                dir.write_string("file.txt", "content");
+                panic!("closure failed");
+            })
+        });
+
+        assert!(result.is_err());
+        assert!(!dir_path.exists());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn with_temp_async() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+
+        let value = Directory::with_temp_async(&dir_path, |dir| async move {
+            tokio::task::yield_now().await;
+            dir.write_string("file.txt", "content");
+            Ok(dir.path().join("file.txt").exists())
+        })
+        .await
+        .unwrap();
+
+        assert!(value);
+        assert!(!dir_path.exists());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn with_temp_async_panic() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+        let task_path = dir_path.clone();
+
+        let result = tokio::spawn(async move {
+            Directory::with_temp_async::<_, (), _, _>(&task_path, |dir| async move {
+                dir.write_string("file.txt", "content");
+                tokio::task::yield_now().await;
+                panic!("future failed");
+            })
+            .await
+        })
+        .await;
+
+        assert!(result.unwrap_err().is_panic());
+        assert!(!dir_path.exists());
+    }
+}
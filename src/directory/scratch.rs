@@ -0,0 +1,101 @@
+use super::*;
+
+use std::fs::File;
+use tempfile::NamedTempFile;
+
+/// Methods for scratch files that don't outlive their handles, e.g. for intermediate
+/// files within a persistent working directory.
+impl Directory {
+    /// Opens an unnamed temporary file inside the directory for reading and writing.
+    /// On Linux, the file is created with `O_TMPFILE` where supported, so it never has a name.
+    /// Elsewhere, a randomly named file is created and immediately unlinked (Unix),
+    /// or marked to be deleted when closed (Windows).
+    /// In all cases, the file is gone once the returned handle is closed.
+    /// Panics if the file cannot be created.
+    pub fn anonymous_file(&self) -> File {
+        tempfile::tempfile_in(&self.path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to create anonymous file in {}: {e}",
+                self.path.display()
+            )
+        })
+    }
+
+    /// Creates a uniquely named file in the directory whose name starts with `prefix`,
+    /// e.g. for intermediate files that other processes need to open by path.
+    /// The file is deleted when the returned handle is dropped, independently
+    /// of the lifecycle of the directory.
+    /// Panics if the file cannot be created.
+    ///
+    /// # Arguments
+    /// * `prefix` - The prefix of the file name, followed by random characters.
+    pub fn temp_file(&self, prefix: &str) -> NamedTempFile {
+        tempfile::Builder::new()
+            .prefix(prefix)
+            .tempfile_in(&self.path)
+            .unwrap_or_else(|e| {
+                panic!(
+                    "Failed to create temporary file in {}: {e}",
+                    self.path.display()
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::{Read, Seek, SeekFrom, Write};
+    use tempfile::tempdir;
+
+    #[test]
+    fn anonymous_file() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        let mut file = directory.anonymous_file();
+        file.write_all(b"Scratch data").unwrap();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut content = String::new();
+        file.read_to_string(&mut content).unwrap();
+
+        assert_eq!(content, "Scratch data");
+        #[cfg(unix)]
+        assert!(
+            std::fs::read_dir(directory.path())
+                .unwrap()
+                .next()
+                .is_none()
+        );
+        drop(file);
+        assert!(
+            std::fs::read_dir(directory.path())
+                .unwrap()
+                .next()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn temp_file() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).keep();
+
+        let file = directory.temp_file("intermediate");
+        let file_path = file.path().to_path_buf();
+        std::fs::write(&file_path, "data").unwrap();
+
+        assert_eq!(file_path.parent().unwrap(), directory.path());
+        assert!(
+            file_path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("intermediate")
+        );
+        drop(file);
+        assert!(!file_path.exists());
+        assert!(directory.path().exists());
+    }
+}
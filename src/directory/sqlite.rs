@@ -0,0 +1,70 @@
+use super::*;
+
+use std::path::Path;
+
+use crate::util::assert_relative_path;
+
+/// Methods for SQLite databases inside the directory, e.g. for tools that keep their
+/// run state in a small database in their working directory.
+impl Directory {
+    /// Opens the SQLite database at the given path within the directory,
+    /// creating it if it does not exist.
+    /// The database file (and its journal) is removed together with the directory;
+    /// on Windows, the connection must be closed before the directory is dropped.
+    /// Panics if the path is absolute or if the database cannot be opened.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the database file within the directory.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let dir = Directory::cargo_target_subdir("sqlite_example");
+    /// let connection = dir.sqlite("state.db");
+    /// connection.execute("CREATE TABLE runs (id INTEGER)", ()).unwrap();
+    /// ```
+    pub fn sqlite<P: AsRef<Path>>(&self, relative_path: P) -> rusqlite::Connection {
+        assert_relative_path(relative_path.as_ref());
+        let relative_path = self.normalize_name(relative_path.as_ref());
+        self.check_path(&relative_path)
+            .unwrap_or_else(|e| panic!("{e}"));
+        let db_path = self.path.join(&relative_path);
+        rusqlite::Connection::open(&db_path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to open SQLite database at {}: {e}",
+                db_path.display()
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn sqlite() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+        let directory = Directory::create(&dir_path);
+
+        let connection = directory.sqlite("state.db");
+        connection
+            .execute_batch("CREATE TABLE runs (id INTEGER); INSERT INTO runs VALUES (42);")
+            .unwrap();
+        drop(connection);
+        let connection = directory.sqlite("state.db");
+        let id: i64 = connection
+            .query_row("SELECT id FROM runs", (), |row| row.get(0))
+            .unwrap();
+        drop(connection);
+
+        assert_eq!(id, 42);
+        assert!(dir_path.join("state.db").exists());
+        drop(directory);
+        assert!(!dir_path.exists());
+    }
+}
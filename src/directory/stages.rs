@@ -0,0 +1,202 @@
+use super::*;
+
+use crate::util::assert_relative_path;
+use std::path::Path;
+
+/// Ordered stage directories of a pipeline (e.g. `raw`, `normalized`, `final`),
+/// created by `Directory::stages`.
+/// Files move through the stages by being promoted from one stage to the next,
+/// and every promotion is recorded.
+pub struct Stages {
+    stages: Vec<(String, Directory)>,
+    promotions: Vec<Promotion>,
+}
+
+/// Record of a file that was promoted from one stage to the next.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Promotion {
+    /// The path of the file, relative to the stage directories.
+    pub path: PathBuf,
+    /// The name of the stage the file was moved from.
+    pub from: String,
+    /// The name of the stage the file was moved to.
+    pub to: String,
+}
+
+/// Methods for pipeline-stage layouts, e.g. for ETL-style data jobs.
+impl Directory {
+    /// Creates a subdirectory for each of the given stages, in order, and returns
+    /// handles to them. The stage directories are part of the directory's tree,
+    /// so they are removed along with it.
+    /// Panics if a stage name is not a relative path, if a stage name is repeated,
+    /// or if a stage directory cannot be created.
+    ///
+    /// # Arguments
+    /// * `names` - The names of the stages, from the first to the last.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let dir = Directory::cargo_target_subdir("stages_example");
+    /// let mut stages = dir.stages(["raw", "normalized", "final"]);
+    /// stages.stage("raw").write_string("data.csv", "a,b");
+    /// stages.promote("raw", "data.csv");
+    /// stages.promote("normalized", "data.csv");
+    /// assert!(dir.path().join("final/data.csv").exists());
+    /// assert_eq!(stages.promotions().len(), 2);
+    /// ```
+    pub fn stages<I, S>(&self, names: I) -> Stages
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut stages: Vec<(String, Directory)> = Vec::new();
+        for name in names {
+            let name = name.as_ref();
+            assert_relative_path(Path::new(name));
+            if stages.iter().any(|(existing, _)| existing == name) {
+                panic!("Stage {name} is repeated in {}", self.path.display());
+            }
+            stages.push((name.to_string(), Self::create(self.path.join(name)).keep()));
+        }
+        Stages {
+            stages,
+            promotions: Vec::new(),
+        }
+    }
+}
+
+impl Stages {
+    /// Returns the directory of the given stage.
+    /// Panics if there is no stage with the given name.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the stage.
+    pub fn stage(&self, name: &str) -> &Directory {
+        &self.stages[self.position(name)].1
+    }
+
+    /// Returns the names of the stages in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.stages.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Moves a file from the given stage to the next one and records the promotion.
+    /// Missing parent directories in the next stage are created, and an existing file
+    /// at the destination is replaced.
+    /// Returns the new path of the file.
+    /// Panics if `relative_path` is not a relative path, if there is no stage with the
+    /// given name, if it is the last stage, or if the file cannot be moved.
+    ///
+    /// # Arguments
+    /// * `from` - The name of the stage that currently contains the file.
+    /// * `relative_path` - The path of the file, relative to the stage directories.
+    pub fn promote<P: AsRef<Path>>(&mut self, from: &str, relative_path: P) -> PathBuf {
+        let relative_path = relative_path.as_ref();
+        assert_relative_path(relative_path);
+        let index = self.position(from);
+        let Some((to, target)) = self.stages.get(index + 1) else {
+            panic!(
+                "Cannot promote {} from the last stage {from}",
+                relative_path.display()
+            );
+        };
+        let source_path = self.stages[index].1.path().join(relative_path);
+        let target_path = target.path().join(relative_path);
+        if let Some(parent) = target_path.parent() {
+            std::fs::create_dir_all(parent).unwrap_or_else(|e| {
+                panic!("Failed to create directory at {}: {e}", parent.display())
+            });
+        }
+        transfer::move_path(&source_path, &target_path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to move {} to {}: {e}",
+                source_path.display(),
+                target_path.display()
+            )
+        });
+        self.promotions.push(Promotion {
+            path: relative_path.to_path_buf(),
+            from: from.to_string(),
+            to: to.clone(),
+        });
+        target_path
+    }
+
+    /// Returns the promotions so far, in the order in which they happened.
+    pub fn promotions(&self) -> &[Promotion] {
+        &self.promotions
+    }
+
+    /// Returns the index of the given stage.
+    /// Panics if there is no stage with the given name.
+    fn position(&self, name: &str) -> usize {
+        self.stages
+            .iter()
+            .position(|(existing, _)| existing == name)
+            .unwrap_or_else(|| panic!("There is no stage named {name}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn stages() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        let stages = directory.stages(["raw", "final"]);
+
+        assert_eq!(stages.names().collect::<Vec<_>>(), ["raw", "final"]);
+        assert_eq!(stages.stage("raw").path(), directory.path().join("raw"));
+        assert!(directory.path().join("final").is_dir());
+    }
+
+    #[test]
+    #[should_panic(expected = "is repeated")]
+    fn stages_repeated() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.stages(["raw", "raw"]);
+    }
+
+    #[test]
+    fn promote() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let mut stages = directory.stages(["raw", "normalized", "final"]);
+        std::fs::create_dir(directory.path().join("raw/nested")).unwrap();
+        stages.stage("raw").write_string("nested/data.csv", "a,b");
+
+        let path = stages.promote("raw", "nested/data.csv");
+
+        assert_eq!(path, directory.path().join("normalized/nested/data.csv"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a,b");
+        assert!(!directory.path().join("raw/nested/data.csv").exists());
+        assert_eq!(
+            stages.promotions(),
+            [Promotion {
+                path: PathBuf::from("nested/data.csv"),
+                from: "raw".to_string(),
+                to: "normalized".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "from the last stage")]
+    fn promote_last_stage() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let mut stages = directory.stages(["raw", "final"]);
+        stages.stage("final").write_string("data.csv", "");
+
+        stages.promote("final", "data.csv");
+    }
+}
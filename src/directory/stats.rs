@@ -0,0 +1,201 @@
+use super::*;
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters of all directories that track statistics, see `Directory::process_stats`.
+static PROCESS_COUNTERS: Counters = Counters::new();
+
+/// Statistics of the file operations of a directory, see `Directory::with_stats`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IoStats {
+    /// Number of files written by the `write_*` methods.
+    pub files_written: u64,
+    /// Number of bytes written by the `write_*` methods.
+    pub bytes_written: u64,
+    /// Number of files removed when the directory was cleaned or removed.
+    pub files_removed: u64,
+    /// Number of failed writes and removals.
+    pub errors: u64,
+}
+
+/// Atomic counters behind `IoStats`, so they can be updated through shared references.
+#[derive(Debug, Default)]
+pub(super) struct Counters {
+    files_written: AtomicU64,
+    bytes_written: AtomicU64,
+    files_removed: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl Counters {
+    const fn new() -> Self {
+        Self {
+            files_written: AtomicU64::new(0),
+            bytes_written: AtomicU64::new(0),
+            files_removed: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+        }
+    }
+
+    fn snapshot(&self) -> IoStats {
+        IoStats {
+            files_written: self.files_written.load(Ordering::Relaxed),
+            bytes_written: self.bytes_written.load(Ordering::Relaxed),
+            files_removed: self.files_removed.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Methods for tracking I/O statistics, e.g. to assert I/O budgets in performance tests.
+///
+/// Tracking is opt-in, since counting the files of a tree before it is removed
+/// requires an additional traversal.
+impl Directory {
+    /// Creates a new Directory instance from self that tracks statistics of its file
+    /// operations, which are returned by `stats` and added up in `process_stats`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let dir = Directory::cargo_target_subdir("with_stats_example").with_stats();
+    /// dir.write_string("file.txt", "Hello");
+    /// assert_eq!(dir.stats().files_written, 1);
+    /// assert_eq!(dir.stats().bytes_written, 5);
+    /// ```
+    pub fn with_stats(mut self) -> Self {
+        self.stats.get_or_insert_with(Box::default);
+        self
+    }
+
+    /// Returns the statistics of the file operations of the directory so far.
+    /// All counters are zero unless tracking was enabled with `with_stats`.
+    pub fn stats(&self) -> IoStats {
+        self.stats
+            .as_ref()
+            .map(|counters| counters.snapshot())
+            .unwrap_or_default()
+    }
+
+    /// Returns the statistics of all directories in the process that track them
+    /// (see `with_stats`), including the ones that were already dropped.
+    pub fn process_stats() -> IoStats {
+        PROCESS_COUNTERS.snapshot()
+    }
+
+    /// Records the outcome of writing a file with `len` bytes.
+    pub(super) fn record_write<T, E>(&self, len: usize, result: &std::result::Result<T, E>) {
+        match result {
+            Ok(_) => self.record(|counters| {
+                counters.files_written.fetch_add(1, Ordering::Relaxed);
+                counters
+                    .bytes_written
+                    .fetch_add(len as u64, Ordering::Relaxed);
+            }),
+            Err(_) => self.record_error(),
+        }
+    }
+
+    /// Records the removal of `files` files.
+    pub(super) fn record_removed(&self, files: u64) {
+        self.record(|counters| {
+            counters.files_removed.fetch_add(files, Ordering::Relaxed);
+        });
+    }
+
+    /// Records a failed operation.
+    pub(super) fn record_error(&self) {
+        self.record(|counters| {
+            counters.errors.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    /// Returns the number of files in the tree of the directory if statistics are tracked,
+    /// e.g. before the tree is removed. Entries that cannot be read are not counted.
+    pub(super) fn count_files_for_stats(&self) -> u64 {
+        if self.stats.is_none() {
+            return 0;
+        }
+        count_files(&self.path)
+    }
+
+    /// Applies `update` to the counters of the directory and of the process,
+    /// if statistics are tracked.
+    fn record(&self, update: impl Fn(&Counters)) {
+        if let Some(counters) = &self.stats {
+            update(counters);
+            update(&PROCESS_COUNTERS);
+        }
+    }
+}
+
+/// Returns the number of files and symlinks below `path`.
+fn count_files(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(std::result::Result::ok)
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => count_files(&entry.path()),
+            _ => 1,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn stats() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_stats();
+        std::fs::create_dir(directory.path().join("subdir")).unwrap();
+
+        directory.write_bytes("subdir/file.bin", [0; 10]);
+        directory.write_string_new("file.txt", "Hello").unwrap();
+        let _ = directory.write_string_new("file.txt", "Hello");
+        let directory = directory.clean();
+
+        assert_eq!(
+            directory.stats(),
+            IoStats {
+                files_written: 2,
+                bytes_written: 15,
+                files_removed: 2,
+                errors: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn stats_disabled() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.write_string("file.txt", "Hello");
+
+        assert_eq!(directory.stats(), IoStats::default());
+    }
+
+    #[test]
+    fn process_stats() {
+        let temp_dir = tempdir().unwrap();
+        let before = Directory::process_stats();
+
+        {
+            let directory = Directory::create(temp_dir.path().join("test_dir")).with_stats();
+            directory.write_string("file.txt", "Hello");
+        }
+
+        // Other tests may write concurrently, so only lower bounds can be checked.
+        let after = Directory::process_stats();
+        assert!(after.files_written > before.files_written);
+        assert!(after.files_removed > before.files_removed);
+    }
+}
@@ -0,0 +1,122 @@
+use super::*;
+
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Overview of a directory tree, as returned by `Directory::summary`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// Number of files (including symlinks, which are not followed).
+    pub files: u64,
+    /// Number of subdirectories.
+    pub dirs: u64,
+    /// Total size of all files in bytes.
+    pub total_bytes: u64,
+    /// Most recent modification time of an entry, if there are any entries.
+    pub newest_mtime: Option<SystemTime>,
+    /// Path of the most deeply nested entry, relative to the directory.
+    /// The first one in sorted order if there are several at the same depth.
+    pub deepest_path: Option<PathBuf>,
+}
+
+/// Methods for inspecting the tree of the directory.
+impl Directory {
+    /// Returns an overview of the tree of the directory, computed in a single walk,
+    /// e.g. for logging or assertions.
+    /// Panics if the tree cannot be read.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let dir = Directory::cargo_target_subdir("summary_example").clean();
+    /// std::fs::create_dir(dir.path().join("logs")).unwrap();
+    /// dir.write_string("logs/run.log", "done");
+    /// dir.write_string("result.txt", "ok");
+    ///
+    /// let summary = dir.summary();
+    /// assert_eq!((summary.files, summary.dirs, summary.total_bytes), (2, 1, 6));
+    /// assert_eq!(summary.deepest_path.unwrap(), std::path::Path::new("logs/run.log"));
+    /// ```
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary::default();
+        let mut max_depth = 0;
+        summarize(&self.path, Path::new(""), 1, &mut summary, &mut max_depth).unwrap_or_else(|e| {
+            panic!(
+                "Failed to read directory tree at {}: {e}",
+                self.path.display()
+            )
+        });
+        summary
+    }
+}
+
+/// Adds the entries of `root.join(relative_dir)`, which are at the given depth,
+/// to the summary.
+fn summarize(
+    root: &Path,
+    relative_dir: &Path,
+    depth: usize,
+    summary: &mut Summary,
+    max_depth: &mut usize,
+) -> std::io::Result<()> {
+    let mut entries =
+        std::fs::read_dir(root.join(relative_dir))?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let relative_path = relative_dir.join(entry.file_name());
+        let metadata = entry.metadata()?;
+        if let Ok(modified) = metadata.modified() {
+            summary.newest_mtime = summary.newest_mtime.max(Some(modified));
+        }
+        if depth > *max_depth {
+            *max_depth = depth;
+            summary.deepest_path = Some(relative_path.clone());
+        }
+        if metadata.is_dir() {
+            summary.dirs += 1;
+            summarize(root, &relative_path, depth + 1, summary, max_depth)?;
+        } else {
+            summary.files += 1;
+            summary.total_bytes += metadata.len();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn summary() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        std::fs::create_dir_all(directory.path().join("a/b")).unwrap();
+        std::fs::create_dir(directory.path().join("c")).unwrap();
+        directory.write_bytes("a/b/deep.bin", [0; 10]);
+        directory.write_bytes("a/b/other.bin", [0; 5]);
+        directory.write_string("top.txt", "abc");
+
+        let summary = directory.summary();
+
+        assert_eq!(summary.files, 3);
+        assert_eq!(summary.dirs, 3);
+        assert_eq!(summary.total_bytes, 18);
+        assert_eq!(summary.deepest_path, Some(PathBuf::from("a/b/deep.bin")));
+        let newest = std::fs::metadata(directory.path().join("top.txt"))
+            .and_then(|metadata| metadata.modified())
+            .unwrap();
+        assert!(summary.newest_mtime.unwrap() >= newest);
+    }
+
+    #[test]
+    fn summary_empty() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        assert_eq!(directory.summary(), Summary::default());
+    }
+}
@@ -0,0 +1,143 @@
+use super::*;
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::{Error, Result};
+
+/// Policy for `Directory::sweep_orphans`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SweepPolicy {
+    /// Only orphans whose marker file was last modified at least this long ago are removed.
+    pub min_age: Duration,
+    /// Only report orphans without removing them.
+    pub dry_run: bool,
+}
+
+/// Methods for cleaning up directories left behind by previous runs.
+impl Directory {
+    /// Scans the direct subdirectories of `base` for temporary directories that were
+    /// created with a marker file (see `with_marker`) by processes that are no longer
+    /// running, e.g. because a test run was killed, and removes them.
+    /// Directories marked to be kept (see `Marker::keep`, which also covers directories
+    /// kept after a panic, by `mark_failed`, or by `CONV_WD_KEEP`) and directories without
+    /// a readable marker are never touched.
+    /// Returns the paths of the removed orphans (or of the orphans that would be
+    /// removed in a dry run).
+    ///
+    /// # Arguments
+    /// * `base` - The directory to scan for orphans.
+    /// * `policy` - The policy that controls which orphans are removed.
+    pub fn sweep_orphans<P: AsRef<Path>>(base: P, policy: SweepPolicy) -> Result<Vec<PathBuf>> {
+        let base = base.as_ref();
+        let mut orphans = Vec::new();
+        for entry in std::fs::read_dir(base).map_err(|e| Error::io(base, e))? {
+            let entry = entry.map_err(|e| Error::io(base, e))?;
+            let path = entry.path();
+            if !entry.file_type().is_ok_and(|file_type| file_type.is_dir())
+                || !is_orphan(&path, policy.min_age)
+            {
+                continue;
+            }
+            if !policy.dry_run {
+                match std::fs::remove_dir_all(&path) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                        return Err(Error::io(&path, e));
+                    }
+                    _ => {}
+                }
+            }
+            orphans.push(path);
+        }
+        orphans.sort();
+        Ok(orphans)
+    }
+}
+
+/// Returns whether the directory at the given path is an orphaned temporary directory
+/// whose marker is at least `min_age` old. Directories whose marker records that they
+/// are kept, including retentions after a panic, are never orphans.
+fn is_orphan(path: &Path, min_age: Duration) -> bool {
+    let Some(marker) = Marker::read(path) else {
+        return false;
+    };
+    let old_enough = std::fs::metadata(path.join(marker::MARKER_FILE_NAME))
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| modified.elapsed().unwrap_or_default() >= min_age);
+    !marker.keep && old_enough && !marker.owner().is_alive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::process::ProcessId;
+    use tempfile::tempdir;
+
+    /// Creates a directory with a marker of a process that is no longer running.
+    fn create_orphan(path: &Path, keep: bool) {
+        let current = ProcessId::current();
+        std::fs::create_dir_all(path).unwrap();
+        std::fs::write(
+            path.join(marker::MARKER_FILE_NAME),
+            format!(
+                "pid = {}\nstart_time = {}\ncreated_at = 0\nkeep = {keep}\n",
+                current.pid,
+                current.start_time.unwrap_or(0) + 1
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn sweep_orphans() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path();
+        create_orphan(&base.join("orphan"), false);
+        create_orphan(&base.join("kept"), true);
+        std::fs::create_dir_all(base.join("unmarked")).unwrap();
+        let live = Directory::create(base.join("live")).with_marker();
+
+        let removed = Directory::sweep_orphans(base, SweepPolicy::default()).unwrap();
+
+        #[cfg(any(target_os = "linux", windows))]
+        assert_eq!(removed, vec![base.join("orphan")]);
+        assert!(!removed.contains(&base.join("kept")));
+        assert!(base.join("kept").exists());
+        assert!(base.join("unmarked").exists());
+        assert!(live.path().exists());
+    }
+
+    #[test]
+    fn sweep_orphans_dry_run() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path();
+        create_orphan(&base.join("orphan"), false);
+        let policy = SweepPolicy {
+            dry_run: true,
+            ..SweepPolicy::default()
+        };
+
+        let orphans = Directory::sweep_orphans(base, policy).unwrap();
+
+        #[cfg(any(target_os = "linux", windows))]
+        assert_eq!(orphans, vec![base.join("orphan")]);
+        assert!(base.join("orphan").exists());
+    }
+
+    #[test]
+    fn sweep_orphans_min_age() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path();
+        create_orphan(&base.join("orphan"), false);
+        let policy = SweepPolicy {
+            min_age: Duration::from_secs(3600),
+            ..SweepPolicy::default()
+        };
+
+        let removed = Directory::sweep_orphans(base, policy).unwrap();
+
+        assert!(removed.is_empty());
+        assert!(base.join("orphan").exists());
+    }
+}
@@ -0,0 +1,436 @@
+use super::*;
+
+use std::collections::BTreeSet;
+use std::fs::Metadata;
+use std::path::Path;
+
+/// Policy for `Directory::sync_to` and `Directory::sync_from`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SyncPolicy {
+    /// Compare the content of files of equal size instead of their modification times.
+    /// Slower, but also detects changes that preserve the modification time.
+    pub compare_content: bool,
+    /// Keep entries in the target that do not exist in the source.
+    pub keep_extraneous: bool,
+}
+
+/// Summary of a sync operation.
+/// All paths are relative to the roots of the trees.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Files and symlinks that were copied because they were new or changed.
+    pub copied: Vec<PathBuf>,
+    /// Number of files that were skipped because they were unchanged.
+    pub skipped: usize,
+    /// Entries that were removed from the target because they do not exist in the source.
+    pub removed: Vec<PathBuf>,
+}
+
+/// Methods for incrementally mirroring trees, e.g. to populate a working directory
+/// from a large fixture on every test run.
+///
+/// Files are considered unchanged if their size and modification time match
+/// (or their content, see `SyncPolicy::compare_content`), and unchanged files are skipped.
+/// Copied files get the permissions and modification time of their source, so a subsequent
+/// sync only touches the files that changed in between. With `preserve_metadata`,
+/// directories also get the permissions and modification times of their source.
+impl Directory {
+    /// Mirrors the tree of the directory into `target`, which is created if needed.
+    /// Panics if an entry cannot be read, copied, or removed.
+    ///
+    /// # Arguments
+    /// * `target` - The root of the tree to update.
+    /// * `policy` - The policy that controls how changes are detected and applied.
+    pub fn sync_to<P: AsRef<Path>>(&self, target: P, policy: SyncPolicy) -> SyncReport {
+        self.sync(&self.path, target.as_ref(), policy)
+    }
+
+    /// Mirrors the tree at `source`, e.g. a fixture, into the directory.
+    /// Panics if an entry cannot be read, copied, or removed.
+    ///
+    /// # Arguments
+    /// * `source` - The root of the tree to mirror.
+    /// * `policy` - The policy that controls how changes are detected and applied.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::{Directory, SyncPolicy};
+    ///
+    /// let fixture = Directory::cargo_target_subdir("sync_from_example/fixture");
+    /// fixture.write_string("input.txt", "data");
+    /// let dir = Directory::cargo_target_subdir("sync_from_example/work");
+    ///
+    /// dir.sync_from(&fixture, SyncPolicy::default());
+    /// let report = dir.sync_from(&fixture, SyncPolicy::default());
+    /// assert!(report.copied.is_empty());
+    /// assert_eq!(report.skipped, 1);
+    /// ```
+    pub fn sync_from<P: AsRef<Path>>(&self, source: P, policy: SyncPolicy) -> SyncReport {
+        self.sync(source.as_ref(), &self.path, policy)
+    }
+
+    /// Mirrors the tree at `source` into `target`.
+    /// Panics if an entry cannot be read, compared, copied, or removed.
+    fn sync(&self, source: &Path, target: &Path, policy: SyncPolicy) -> SyncReport {
+        let entries = self.walk_tree(source).unwrap_or_else(|e| {
+            panic!("Failed to read directory tree at {}: {e}", source.display())
+        });
+        create_dir_for_sync(target);
+        let mut report = SyncReport::default();
+
+        self.progress_start(entries.len(), || {
+            format!("Syncing {} to {}", source.display(), target.display())
+        });
+        for entry in &entries {
+            let source_path = source.join(entry);
+            let target_path = target.join(entry);
+            let metadata = metadata_for_sync(&source_path);
+            let target_metadata = std::fs::symlink_metadata(&target_path).ok();
+            if target_metadata
+                .as_ref()
+                .is_some_and(|target_metadata| target_metadata.file_type() != metadata.file_type())
+            {
+                remove_for_sync(&target_path);
+            }
+
+            if metadata.is_dir() {
+                create_dir_for_sync(&target_path);
+            } else if target_metadata.is_some_and(|target_metadata| {
+                is_unchanged(
+                    &source_path,
+                    &metadata,
+                    &target_path,
+                    &target_metadata,
+                    policy,
+                )
+                .unwrap_or_else(|e| {
+                    panic!(
+                        "Failed to compare {} with {}: {e}",
+                        source_path.display(),
+                        target_path.display()
+                    )
+                })
+            }) {
+                report.skipped += 1;
+            } else {
+                copy_for_sync(
+                    &source_path,
+                    &metadata,
+                    &target_path,
+                    self.preserve_metadata,
+                );
+                report.copied.push(entry.clone());
+            }
+            self.progress_inc();
+        }
+        self.progress_finish();
+        if self.preserve_metadata {
+            transfer::copy_dir_metadata(source, target, &entries).unwrap_or_else(|e| {
+                panic!(
+                    "Failed to copy the metadata of {} to {}: {e}",
+                    source.display(),
+                    target.display()
+                )
+            });
+        }
+
+        if !policy.keep_extraneous {
+            let entries = entries.into_iter().collect::<BTreeSet<_>>();
+            let target_entries = self.walk_tree(target).unwrap_or_else(|e| {
+                panic!("Failed to read directory tree at {}: {e}", target.display())
+            });
+            for entry in target_entries {
+                // Entries below a removed directory are sorted right after it.
+                if !entries.contains(&entry)
+                    && !report
+                        .removed
+                        .last()
+                        .is_some_and(|removed| entry.starts_with(removed))
+                {
+                    remove_for_sync(&target.join(&entry));
+                    report.removed.push(entry);
+                }
+            }
+        }
+        report
+    }
+}
+
+/// Returns whether the target file or symlink is up to date with the source.
+/// With `SyncPolicy::compare_content`, files are compared in chunks (see
+/// `transfer::contents_equal`), so large files are not loaded into memory.
+/// Returns an error if a symlink or file cannot be read.
+fn is_unchanged(
+    source_path: &Path,
+    metadata: &Metadata,
+    target_path: &Path,
+    target_metadata: &Metadata,
+    policy: SyncPolicy,
+) -> std::io::Result<bool> {
+    if metadata.is_symlink() {
+        return Ok(std::fs::read_link(source_path)? == std::fs::read_link(target_path)?);
+    }
+    if metadata.len() != target_metadata.len() {
+        return Ok(false);
+    }
+    if policy.compare_content {
+        transfer::contents_equal(source_path, target_path)
+    } else {
+        Ok(metadata.modified().ok() == target_metadata.modified().ok())
+    }
+}
+
+/// Copies a file or symlink and applies the modification time of the source,
+/// and with `preserve_metadata` and the `xattr` feature, its extended attributes.
+/// An existing target is replaced, also if it is read-only.
+/// Panics if the entry cannot be copied.
+fn copy_for_sync(
+    source_path: &Path,
+    metadata: &Metadata,
+    target_path: &Path,
+    #[cfg_attr(not(all(feature = "xattr", unix)), allow(unused_variables))] preserve_metadata: bool,
+) {
+    let result = if metadata.is_symlink() {
+        std::fs::read_link(source_path).and_then(|link_target| {
+            remove_if_exists(target_path)?;
+            create_symlink(&link_target, source_path, target_path)
+        })
+    } else {
+        // Copies get the permissions of their source, and read-only files can't be overwritten.
+        remove_if_exists(target_path)
+            .and_then(|()| copy::copy_file(source_path, target_path, &mut |_| {}))
+            .and_then(|()| {
+                #[cfg(all(feature = "xattr", unix))]
+                if preserve_metadata {
+                    xattrs::copy_xattrs(source_path, target_path)?;
+                }
+                util::set_modified(target_path, metadata.modified()?)
+            })
+    };
+    result.unwrap_or_else(|e| {
+        panic!(
+            "Failed to copy {} to {}: {e}",
+            source_path.display(),
+            target_path.display()
+        )
+    });
+}
+
+/// Creates a symlink at `target_path` that points to `link_target`,
+/// like the symlink at `source_path`.
+#[cfg(unix)]
+pub(super) fn create_symlink(
+    link_target: &Path,
+    _source_path: &Path,
+    target_path: &Path,
+) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(link_target, target_path)
+}
+
+/// Creates a symlink at `target_path` that points to `link_target`,
+/// like the symlink at `source_path`.
+/// Windows distinguishes between symlinks to files and to directories.
+#[cfg(windows)]
+pub(super) fn create_symlink(
+    link_target: &Path,
+    source_path: &Path,
+    target_path: &Path,
+) -> std::io::Result<()> {
+    if source_path.is_dir() {
+        std::os::windows::fs::symlink_dir(link_target, target_path)
+    } else {
+        std::os::windows::fs::symlink_file(link_target, target_path)
+    }
+}
+
+/// Removes a file, symlink, or directory tree if it exists.
+pub(super) fn remove_if_exists(path: &Path) -> std::io::Result<()> {
+    let result = match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => std::fs::remove_dir_all(path),
+        Ok(_) => std::fs::remove_file(path),
+        Err(e) => Err(e),
+    };
+    match result {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// Removes an entry from the target tree.
+/// Panics if the entry cannot be removed.
+fn remove_for_sync(path: &Path) {
+    remove_if_exists(path).unwrap_or_else(|e| panic!("Failed to remove {}: {e}", path.display()));
+}
+
+/// Creates a directory in the target tree.
+/// Panics if the directory cannot be created.
+fn create_dir_for_sync(path: &Path) {
+    std::fs::create_dir_all(path)
+        .unwrap_or_else(|e| panic!("Failed to create directory at {}: {e}", path.display()));
+}
+
+/// Returns the metadata of a source entry without following symlinks.
+/// Panics if the metadata cannot be read.
+fn metadata_for_sync(path: &Path) -> Metadata {
+    std::fs::symlink_metadata(path)
+        .unwrap_or_else(|e| panic!("Failed to read metadata of {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    fn setup() -> (tempfile::TempDir, Directory) {
+        let temp_dir = tempdir().unwrap();
+        let source = Directory::create(temp_dir.path().join("source"));
+        std::fs::create_dir(source.path().join("subdir")).unwrap();
+        source.write_string("file.txt", "content");
+        source.write_string("subdir/nested.txt", "nested");
+        (temp_dir, source)
+    }
+
+    #[test]
+    fn sync_to() {
+        let (temp_dir, source) = setup();
+        let target = temp_dir.path().join("target");
+
+        let report = source.sync_to(&target, SyncPolicy::default());
+
+        assert_eq!(
+            report.copied,
+            ["file.txt", "subdir/nested.txt"].map(PathBuf::from)
+        );
+        assert!(source.diff_dir(&target).is_equal());
+    }
+
+    #[test]
+    fn sync_to_skips_unchanged_files() {
+        let (temp_dir, source) = setup();
+        let target = temp_dir.path().join("target");
+        source.sync_to(&target, SyncPolicy::default());
+        source.write_string("file.txt", "changed content");
+
+        let report = source.sync_to(&target, SyncPolicy::default());
+
+        assert_eq!(report.copied, [PathBuf::from("file.txt")]);
+        assert_eq!(report.skipped, 1);
+        assert!(source.diff_dir(&target).is_equal());
+    }
+
+    #[test]
+    fn sync_to_read_only_file() {
+        let (temp_dir, source) = setup();
+        let target = temp_dir.path().join("target");
+        let file_path = source.path().join("file.txt");
+        let set_readonly = |readonly| {
+            let mut permissions = std::fs::metadata(&file_path).unwrap().permissions();
+            permissions.set_readonly(readonly);
+            std::fs::set_permissions(&file_path, permissions).unwrap();
+        };
+        set_readonly(true);
+        source.sync_to(&target, SyncPolicy::default());
+        set_readonly(false);
+        source.write_string("file.txt", "changed content");
+        set_readonly(true);
+
+        let report = source.sync_to(&target, SyncPolicy::default());
+
+        assert_eq!(report.copied, [PathBuf::from("file.txt")]);
+        assert_eq!(
+            std::fs::read_to_string(target.join("file.txt")).unwrap(),
+            "changed content"
+        );
+        assert!(
+            std::fs::metadata(target.join("file.txt"))
+                .unwrap()
+                .permissions()
+                .readonly()
+        );
+    }
+
+    #[test]
+    fn sync_to_removes_extraneous_entries() {
+        let (temp_dir, source) = setup();
+        let target = Directory::create(temp_dir.path().join("target"));
+        std::fs::create_dir_all(target.path().join("extra/nested")).unwrap();
+        target.write_string("extra/nested/file.txt", "");
+        target.write_string("extra.txt", "");
+
+        let report = source.sync_to(&target, SyncPolicy::default());
+
+        assert_eq!(report.removed, ["extra", "extra.txt"].map(PathBuf::from));
+        assert!(source.diff_dir(&target).is_equal());
+    }
+
+    #[test]
+    fn sync_to_keeps_extraneous_entries() {
+        let (temp_dir, source) = setup();
+        let target = Directory::create(temp_dir.path().join("target"));
+        target.write_string("extra.txt", "");
+        let policy = SyncPolicy {
+            keep_extraneous: true,
+            ..SyncPolicy::default()
+        };
+
+        let report = source.sync_to(&target, policy);
+
+        assert!(report.removed.is_empty());
+        assert!(target.path().join("extra.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sync_to_symlink_cycle() {
+        let (temp_dir, source) = setup();
+        std::os::unix::fs::symlink("..", source.path().join("subdir/loop")).unwrap();
+        let target = temp_dir.path().join("target");
+
+        let report = source.sync_to(&target, SyncPolicy::default());
+
+        assert_eq!(report.copied.len(), 3);
+        assert_eq!(
+            std::fs::read_link(target.join("subdir/loop")).unwrap(),
+            Path::new("..")
+        );
+    }
+
+    #[test]
+    fn sync_from_compare_content() {
+        let (temp_dir, source) = setup();
+        let target = Directory::create(temp_dir.path().join("target"));
+        target.write_string("file.txt", "content");
+        let policy = SyncPolicy {
+            compare_content: true,
+            ..SyncPolicy::default()
+        };
+
+        let report = target.sync_from(&source, policy);
+
+        assert_eq!(report.copied, [PathBuf::from("subdir/nested.txt")]);
+        assert_eq!(report.skipped, 1);
+    }
+
+    #[test]
+    fn sync_from_compare_content_same_size() {
+        let (temp_dir, source) = setup();
+        let target = Directory::create(temp_dir.path().join("target"));
+        target.sync_from(&source, SyncPolicy::default());
+        target.write_string("file.txt", "CONTENT");
+        let mtime = std::fs::metadata(source.path().join("file.txt"))
+            .unwrap()
+            .modified()
+            .unwrap();
+        util::set_modified(&target.path().join("file.txt"), mtime).unwrap();
+        let policy = SyncPolicy {
+            compare_content: true,
+            ..SyncPolicy::default()
+        };
+
+        let report = target.sync_from(&source, policy);
+
+        assert_eq!(report.copied, [PathBuf::from("file.txt")]);
+        assert_eq!(target.read_string("file.txt"), "content");
+    }
+}
@@ -0,0 +1,97 @@
+use super::*;
+
+use std::path::Path;
+
+use crate::{Error, Result};
+
+/// Methods for annotating directories with key-value tags stored in the marker file.
+impl Directory {
+    /// Sets a tag on the directory, e.g. `dataset=v3` or `status=incomplete`.
+    /// Tags are persisted in the marker file (see `with_marker`),
+    /// which is created if the directory does not have one yet.
+    /// Panics if the marker file cannot be written.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the tag.
+    /// * `value` - The value of the tag; replaces any previous value.
+    pub fn set_tag<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) {
+        self.marker = true;
+        let mut marker = self.marker_or_new();
+        marker.tags.insert(key.into(), value.into());
+        self.write_marker(&marker);
+    }
+
+    /// Returns the value of the tag with the given key, if it is set.
+    pub fn get_tag(&self, key: &str) -> Option<String> {
+        self.marker()?.tags.remove(key)
+    }
+
+    /// Returns the paths of all direct subdirectories of `base` whose marker
+    /// has the tag `key` set to `value`, sorted by path.
+    ///
+    /// # Arguments
+    /// * `base` - The directory to search.
+    /// * `key` - The key of the tag.
+    /// * `value` - The required value of the tag.
+    pub fn find_by_tag<P: AsRef<Path>>(base: P, key: &str, value: &str) -> Result<Vec<PathBuf>> {
+        let base = base.as_ref();
+        let mut matches = Vec::new();
+        for entry in std::fs::read_dir(base).map_err(|e| Error::io(base, e))? {
+            let path = entry.map_err(|e| Error::io(base, e))?.path();
+            if Marker::read(&path)
+                .is_some_and(|marker| marker.tags.get(key).is_some_and(|v| v == value))
+            {
+                matches.push(path);
+            }
+        }
+        matches.sort();
+        Ok(matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn set_tag() {
+        let temp_dir = tempdir().unwrap();
+        let mut directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.set_tag("dataset", "v3");
+        directory.set_tag("status", "incomplete");
+        directory.set_tag("status", "complete");
+
+        assert_eq!(directory.get_tag("dataset").as_deref(), Some("v3"));
+        assert_eq!(directory.get_tag("status").as_deref(), Some("complete"));
+        assert_eq!(directory.get_tag("missing"), None);
+    }
+
+    #[test]
+    fn tags_survive_policy_changes() {
+        let temp_dir = tempdir().unwrap();
+        let mut directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.set_tag("dataset", "v3");
+
+        let directory = directory.keep();
+
+        assert!(directory.marker().unwrap().keep);
+        assert_eq!(directory.get_tag("dataset").as_deref(), Some("v3"));
+    }
+
+    #[test]
+    fn find_by_tag() {
+        let temp_dir = tempdir().unwrap();
+        let mut first = Directory::create(temp_dir.path().join("first"));
+        let mut second = Directory::create(temp_dir.path().join("second"));
+        let _untagged = Directory::create(temp_dir.path().join("untagged"));
+        first.set_tag("status", "complete");
+        second.set_tag("status", "incomplete");
+
+        let found = Directory::find_by_tag(temp_dir.path(), "status", "complete").unwrap();
+
+        assert_eq!(found, vec![first.path_buf()]);
+    }
+}
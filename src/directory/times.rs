@@ -0,0 +1,117 @@
+use super::*;
+
+use std::fs::FileTimes;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::util::assert_relative_path;
+
+/// Methods for setting the timestamps of entries within the directory, e.g. to construct
+/// trees with specific patterns of old and new files for testing freshness logic.
+/// Symlinks are followed.
+impl Directory {
+    /// Sets the modification time of the file or directory at the given path within
+    /// the directory.
+    /// Panics if the path is absolute or if the time cannot be set,
+    /// e.g. because the entry does not exist.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the entry within the directory.
+    /// * `mtime` - The new modification time.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let dir = Directory::cargo_target_subdir("set_mtime_example");
+    /// dir.write_string("stale.txt", "");
+    /// let day_ago = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+    /// dir.set_mtime("stale.txt", day_ago);
+    ///
+    /// let modified = std::fs::metadata(dir.path().join("stale.txt")).unwrap().modified();
+    /// assert_eq!(modified.unwrap(), day_ago);
+    /// ```
+    pub fn set_mtime<P: AsRef<Path>>(&self, relative_path: P, mtime: SystemTime) {
+        self.set_file_times(relative_path.as_ref(), FileTimes::new().set_modified(mtime));
+    }
+
+    /// Sets the access and modification times of the file or directory at the given path
+    /// within the directory.
+    /// Panics if the path is absolute or if the times cannot be set,
+    /// e.g. because the entry does not exist.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the entry within the directory.
+    /// * `atime` - The new access time.
+    /// * `mtime` - The new modification time.
+    pub fn set_times<P: AsRef<Path>>(
+        &self,
+        relative_path: P,
+        atime: SystemTime,
+        mtime: SystemTime,
+    ) {
+        self.set_file_times(
+            relative_path.as_ref(),
+            FileTimes::new().set_accessed(atime).set_modified(mtime),
+        );
+    }
+
+    /// Sets the given timestamps of the entry at the given relative path.
+    /// Panics if the path is absolute or if the timestamps cannot be set.
+    fn set_file_times(&self, relative_path: &Path, times: FileTimes) {
+        assert_relative_path(relative_path);
+        let entry_path = self.path.join(relative_path);
+        util::set_times(&entry_path, times).unwrap_or_else(|e| {
+            panic!(
+                "Failed to set the timestamps of {}: {e}",
+                entry_path.display()
+            )
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::{Duration, UNIX_EPOCH};
+    use tempfile::tempdir;
+
+    #[test]
+    fn set_mtime() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        std::fs::create_dir(directory.path().join("subdir")).unwrap();
+        let mtime = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+
+        directory.set_mtime("subdir", mtime);
+
+        let metadata = std::fs::metadata(directory.path().join("subdir")).unwrap();
+        assert_eq!(metadata.modified().unwrap(), mtime);
+    }
+
+    #[test]
+    fn set_times() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("file.txt", "");
+        let atime = UNIX_EPOCH + Duration::from_secs(1_500_000_000);
+        let mtime = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+
+        directory.set_times("file.txt", atime, mtime);
+
+        let metadata = std::fs::metadata(directory.path().join("file.txt")).unwrap();
+        assert_eq!(metadata.accessed().unwrap(), atime);
+        assert_eq!(metadata.modified().unwrap(), mtime);
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to set the timestamps of")]
+    fn set_mtime_missing_entry() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.set_mtime("missing.txt", SystemTime::now());
+    }
+}
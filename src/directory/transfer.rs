@@ -0,0 +1,566 @@
+use super::*;
+
+use crate::Error;
+use crate::util::assert_relative_path;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Size of the chunks in which copies are compared with their source.
+const VERIFY_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// Methods for moving and copying entries into and out of the directory.
+///
+/// With the `reflink` feature, files are copied as copy-on-write clones (reflinks) on file
+/// systems that support them (e.g. btrfs, XFS, APFS, and ReFS), which is near-instant even
+/// for large files, and are copied regularly otherwise.
+///
+/// Copies get the permissions of their source. With `preserve_metadata`, they also get
+/// the modification times of their source, and directories get the permissions and
+/// modification times of their source as well.
+///
+/// Entries are moved by renaming them if possible. If the source and the destination are on different
+/// file systems (e.g. a working directory on tmpfs and a source on another mount),
+/// the entry is copied instead, the copy is verified against the source, and only then
+/// is the source removed.
+impl Directory {
+    /// Moves the file or directory tree at `source` to the given path within the directory.
+    /// Missing parent directories are created, and an existing file at the destination
+    /// is replaced.
+    /// Returns the new path of the entry.
+    /// Panics if the path is absolute or if the entry cannot be moved.
+    ///
+    /// # Arguments
+    /// * `source` - The path of the file or directory to move.
+    /// * `relative_path` - The destination within the directory.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let source = Directory::cargo_target_subdir("move_into_example/source");
+    /// source.write_string("input.txt", "data");
+    /// let dir = Directory::cargo_target_subdir("move_into_example/work");
+    ///
+    /// let moved = dir.move_into(source.path().join("input.txt"), "inputs/input.txt");
+    /// assert_eq!(std::fs::read_to_string(moved).unwrap(), "data");
+    /// ```
+    pub fn move_into<S: AsRef<Path>, P: AsRef<Path>>(
+        &self,
+        source: S,
+        relative_path: P,
+    ) -> PathBuf {
+        let relative_path = relative_path.as_ref();
+        assert_relative_path(relative_path);
+        let target = self.path.join(relative_path);
+        move_for_transfer(source.as_ref(), &target);
+        target
+    }
+
+    /// Copies the file at `source` to the given path within the directory.
+    /// Missing parent directories are created, and an existing file at the destination
+    /// is replaced.
+    /// The progress is reported in bytes (see `with_progress`).
+    /// Returns the path of the copy.
+    /// Panics if the path is absolute or if the file cannot be copied.
+    ///
+    /// # Arguments
+    /// * `source` - The path of the file to copy.
+    /// * `relative_path` - The destination within the directory.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let fixture = Directory::cargo_target_subdir("copy_file_from_example/fixture");
+    /// fixture.write_string("input.txt", "data");
+    /// let dir = Directory::cargo_target_subdir("copy_file_from_example/work");
+    ///
+    /// let copy = dir.copy_file_from(fixture.path().join("input.txt"), "input.txt");
+    /// assert_eq!(std::fs::read_to_string(copy).unwrap(), "data");
+    /// ```
+    pub fn copy_file_from<S: AsRef<Path>, P: AsRef<Path>>(
+        &self,
+        source: S,
+        relative_path: P,
+    ) -> PathBuf {
+        let relative_path = relative_path.as_ref();
+        assert_relative_path(relative_path);
+        let source = source.as_ref();
+        let target = self.path.join(relative_path);
+        let result = create_parent(&target)
+            .and_then(|()| std::fs::metadata(source))
+            .and_then(|metadata| {
+                self.progress_start(metadata.len() as usize, || {
+                    format!("Copying {} to {}", source.display(), target.display())
+                });
+                let result = copy_file(source, &metadata, &target, &mut |bytes| {
+                    self.progress_inc_by(bytes)
+                })
+                .and_then(|()| {
+                    if self.preserve_metadata {
+                        #[cfg(all(feature = "xattr", unix))]
+                        xattrs::copy_xattrs(source, &target)?;
+                        util::set_modified(&target, metadata.modified()?)?;
+                    }
+                    Ok(())
+                });
+                self.progress_finish();
+                result
+            });
+        result.unwrap_or_else(|e| {
+            panic!(
+                "Failed to copy {} to {}: {e}",
+                source.display(),
+                target.display()
+            )
+        });
+        target
+    }
+
+    /// Copies the directory tree at `source`, e.g. a fixture, to the given path within
+    /// the directory, which is created if needed. Symlinks are copied as symlinks.
+    /// The progress is reported per entry (see `with_progress`).
+    /// Returns the path of the copy.
+    /// Panics if the path is absolute, if the tree is nested deeper than the maximum depth
+    /// (see `with_max_depth`), or if an entry cannot be copied.
+    ///
+    /// # Arguments
+    /// * `source` - The root of the tree to copy.
+    /// * `relative_path` - The destination within the directory.
+    pub fn copy_dir_from<S: AsRef<Path>, P: AsRef<Path>>(
+        &self,
+        source: S,
+        relative_path: P,
+    ) -> PathBuf {
+        let relative_path = relative_path.as_ref();
+        assert_relative_path(relative_path);
+        let source = source.as_ref();
+        let target = self.path.join(relative_path);
+        let result = std::fs::metadata(source)
+            .map_err(|e| Error::io(source, e))
+            .and_then(|metadata| {
+                if metadata.is_dir() {
+                    self.walk_tree(source)
+                } else {
+                    Err(Error::io(
+                        source,
+                        std::io::Error::new(
+                            std::io::ErrorKind::NotADirectory,
+                            "the source is not a directory",
+                        ),
+                    ))
+                }
+            })
+            .and_then(|entries| {
+                self.progress_start(entries.len(), || {
+                    format!("Copying {} to {}", source.display(), target.display())
+                });
+                let result = copy_entries(
+                    source,
+                    &target,
+                    &entries,
+                    self.preserve_metadata,
+                    &mut || self.progress_inc(),
+                );
+                self.progress_finish();
+                result.map_err(|e| Error::io(&target, e))
+            });
+        result.unwrap_or_else(|e| {
+            panic!(
+                "Failed to copy {} to {}: {e}",
+                source.display(),
+                target.display()
+            )
+        });
+        target
+    }
+
+    /// Moves the file or directory tree at the given path within the directory to `target`.
+    /// Missing parent directories are created, and an existing file at `target`
+    /// is replaced.
+    /// Panics if the path is absolute or if the entry cannot be moved.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file or directory within the directory.
+    /// * `target` - The destination outside of the directory.
+    pub fn move_out<P: AsRef<Path>, T: AsRef<Path>>(&self, relative_path: P, target: T) {
+        let relative_path = relative_path.as_ref();
+        assert_relative_path(relative_path);
+        move_for_transfer(&self.path.join(relative_path), target.as_ref());
+    }
+
+    /// Creates a new Directory instance from self whose copies (`copy_file_from`,
+    /// `copy_dir_from`, `sync_to`, and `sync_from`) retain the modification times of their
+    /// sources, and whose copied directories also retain their permissions, e.g. for
+    /// trees that are later packaged or rsynced. With the `xattr` feature, the extended
+    /// attributes of files and directories are copied as well. With `bundle_to`, the permissions and
+    /// modification times are recorded in the bundle, and `from_bundle` restores them.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let fixture = Directory::cargo_target_subdir("preserve_metadata_example/fixture");
+    /// fixture.write_string("input.txt", "data");
+    /// let dir = Directory::cargo_target_subdir("preserve_metadata_example/work")
+    ///     .preserve_metadata();
+    ///
+    /// let copy = dir.copy_file_from(fixture.path().join("input.txt"), "input.txt");
+    /// let modified = |path| std::fs::metadata(path).unwrap().modified().unwrap();
+    /// assert_eq!(modified(copy), modified(fixture.path().join("input.txt")));
+    /// ```
+    pub fn preserve_metadata(mut self) -> Self {
+        self.preserve_metadata = true;
+        self
+    }
+}
+
+/// Moves an entry, creating the parent directories of the target first.
+/// Panics if the entry cannot be moved.
+fn move_for_transfer(source: &Path, target: &Path) {
+    let result = create_parent(target).and_then(|()| move_path(source, target));
+    result.unwrap_or_else(|e| {
+        panic!(
+            "Failed to move {} to {}: {e}",
+            source.display(),
+            target.display()
+        )
+    });
+}
+
+/// Creates the parent directories of `path` if needed.
+fn create_parent(path: &Path) -> std::io::Result<()> {
+    match path.parent() {
+        Some(parent) => std::fs::create_dir_all(parent),
+        None => Ok(()),
+    }
+}
+
+/// Renames `source` to `target`, falling back to copying, verifying, and removing
+/// the source if they are on different file systems.
+/// If the fallback fails, the partial copy is removed and the source is left untouched.
+pub(super) fn move_path(source: &Path, target: &Path) -> std::io::Result<()> {
+    match std::fs::rename(source, target) {
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            move_across_devices(source, target)
+        }
+        result => result,
+    }
+}
+
+/// Moves an entry by copying it, verifying the copy, and removing the source.
+fn move_across_devices(source: &Path, target: &Path) -> std::io::Result<()> {
+    let result = copy_entry(source, target).and_then(|()| verify_copy(source, target));
+    if let Err(e) = result {
+        let _ = sync::remove_if_exists(target);
+        return Err(e);
+    }
+    sync::remove_if_exists(source)
+}
+
+/// Copies a file, symlink, or directory tree for a move, preserving its metadata.
+fn copy_entry(source: &Path, target: &Path) -> std::io::Result<()> {
+    let metadata = std::fs::symlink_metadata(source)?;
+    if !metadata.is_dir() {
+        return copy_single(source, &metadata, target, true);
+    }
+    copy_entries(source, target, &util::walk(source)?, true, &mut || {})
+}
+
+/// Copies the given entries of the tree at `source` to `target`, which is created
+/// if needed, and calls `on_entry` after each entry.
+/// Optionally preserves the modification times of files and the metadata of directories.
+fn copy_entries(
+    source: &Path,
+    target: &Path,
+    entries: &[PathBuf],
+    preserve_metadata: bool,
+    on_entry: &mut dyn FnMut(),
+) -> std::io::Result<()> {
+    std::fs::create_dir_all(target)?;
+    for entry in entries {
+        let source_path = source.join(entry);
+        let target_path = target.join(entry);
+        let metadata = std::fs::symlink_metadata(&source_path)?;
+        if metadata.is_dir() {
+            std::fs::create_dir_all(&target_path)?;
+        } else {
+            copy_single(&source_path, &metadata, &target_path, preserve_metadata)?;
+        }
+        on_entry();
+    }
+    if preserve_metadata {
+        copy_dir_metadata(source, target, entries)?;
+    }
+    Ok(())
+}
+
+/// Copies a file or symlink, optionally preserving the modification time
+/// (and with the `xattr` feature, the extended attributes) of a file.
+fn copy_single(
+    source: &Path,
+    metadata: &std::fs::Metadata,
+    target: &Path,
+    preserve_metadata: bool,
+) -> std::io::Result<()> {
+    if metadata.is_symlink() {
+        sync::remove_if_exists(target)?;
+        return sync::create_symlink(&std::fs::read_link(source)?, source, target);
+    }
+    copy_file(source, metadata, target, &mut |_| {})?;
+    if preserve_metadata {
+        #[cfg(all(feature = "xattr", unix))]
+        xattrs::copy_xattrs(source, target)?;
+        util::set_modified(target, metadata.modified()?)?;
+    }
+    Ok(())
+}
+
+/// Applies the permissions and modification times of the directories of the tree at
+/// `source` (its root and the directories among the given entries) to their copies
+/// at `target`. The deepest directories are updated first, since updating a directory
+/// changes the modification time of its parent on some platforms.
+pub(super) fn copy_dir_metadata(
+    source: &Path,
+    target: &Path,
+    entries: &[PathBuf],
+) -> std::io::Result<()> {
+    for entry in entries.iter().rev().chain([&PathBuf::new()]) {
+        let metadata = std::fs::symlink_metadata(source.join(entry))?;
+        if metadata.is_dir() {
+            let target_path = target.join(entry);
+            #[cfg(all(feature = "xattr", unix))]
+            xattrs::copy_xattrs(&source.join(entry), &target_path)?;
+            util::set_modified(&target_path, metadata.modified()?)?;
+            std::fs::set_permissions(&target_path, metadata.permissions())?;
+        }
+    }
+    Ok(())
+}
+
+/// Copies a file as a copy-on-write clone if the `reflink` feature is enabled and the file
+/// system supports it (`FICLONE` on Linux, `clonefile` on macOS, block cloning on Windows),
+/// and with the kernel-accelerated copy functions of the platform otherwise
+/// (see `copy::copy_file`), calling `on_chunk` with the number of bytes copied.
+/// An existing file at `target` is replaced, and the copy gets the permissions
+/// of the source.
+fn copy_file(
+    source: &Path,
+    #[cfg_attr(not(feature = "reflink"), allow(unused_variables))] metadata: &std::fs::Metadata,
+    target: &Path,
+    on_chunk: &mut dyn FnMut(u64),
+) -> std::io::Result<()> {
+    // Clones can only be created as new files, and read-only files can't be overwritten.
+    match std::fs::remove_file(target) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    #[cfg(feature = "reflink")]
+    if reflink_copy::reflink(source, target).is_ok() {
+        on_chunk(metadata.len());
+        return std::fs::set_permissions(target, metadata.permissions());
+    }
+    copy::copy_file(source, target, on_chunk)
+}
+
+/// Returns an error if the copy at `target` differs from `source`.
+fn verify_copy(source: &Path, target: &Path) -> std::io::Result<()> {
+    let mut paths = vec![(source.to_path_buf(), target.to_path_buf())];
+    if std::fs::symlink_metadata(source)?.is_dir() {
+        paths.extend(
+            util::walk(source)?
+                .into_iter()
+                .map(|entry| (source.join(&entry), target.join(entry))),
+        );
+    }
+    for (source_path, target_path) in paths {
+        let metadata = std::fs::symlink_metadata(&source_path)?;
+        let equal = if metadata.is_symlink() {
+            std::fs::read_link(&source_path)? == std::fs::read_link(&target_path)?
+        } else if metadata.is_dir() {
+            std::fs::symlink_metadata(&target_path)?.is_dir()
+        } else {
+            contents_equal(&source_path, &target_path)?
+        };
+        if !equal {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "the copy at {} differs from the source",
+                    target_path.display()
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether the files at `a` and `b` have the same content, reading them in chunks.
+pub(super) fn contents_equal(a: &Path, b: &Path) -> std::io::Result<bool> {
+    let (mut a, mut b) = (File::open(a)?, File::open(b)?);
+    if a.metadata()?.len() != b.metadata()?.len() {
+        return Ok(false);
+    }
+    let (mut chunk_a, mut chunk_b) = (Vec::new(), Vec::new());
+    loop {
+        chunk_a.clear();
+        chunk_b.clear();
+        let read = a
+            .by_ref()
+            .take(VERIFY_CHUNK_SIZE)
+            .read_to_end(&mut chunk_a)?;
+        b.by_ref()
+            .take(VERIFY_CHUNK_SIZE)
+            .read_to_end(&mut chunk_b)?;
+        if chunk_a != chunk_b {
+            return Ok(false);
+        }
+        if read == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn move_into() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let source = temp_dir.path().join("source.txt");
+        std::fs::write(&source, "data").unwrap();
+
+        let moved = directory.move_into(&source, "inputs/source.txt");
+
+        assert_eq!(moved, directory.path().join("inputs/source.txt"));
+        assert_eq!(std::fs::read_to_string(moved).unwrap(), "data");
+        assert!(!source.exists());
+    }
+
+    #[test]
+    fn copy_file_from() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let source = temp_dir.path().join("source.txt");
+        std::fs::write(&source, "data").unwrap();
+        directory.write_string("copy.txt", "old data");
+
+        let copy = directory.copy_file_from(&source, "copy.txt");
+
+        assert_eq!(std::fs::read_to_string(copy).unwrap(), "data");
+        assert!(source.exists());
+    }
+
+    #[test]
+    fn copy_dir_from() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let source = Directory::create(temp_dir.path().join("fixture"));
+        std::fs::create_dir(source.path().join("subdir")).unwrap();
+        source.write_string("file.txt", "content");
+        source.write_string("subdir/nested.txt", "nested");
+
+        let copy = directory.copy_dir_from(&source, "inputs/fixture");
+
+        assert!(source.diff_dir(&copy).is_equal());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copy_dir_from_symlink_cycle() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let source = Directory::create(temp_dir.path().join("fixture"));
+        std::fs::create_dir(source.path().join("subdir")).unwrap();
+        std::os::unix::fs::symlink("..", source.path().join("subdir/loop")).unwrap();
+
+        let copy = directory.copy_dir_from(&source, "fixture");
+
+        assert_eq!(
+            std::fs::read_link(copy.join("subdir/loop")).unwrap(),
+            Path::new("..")
+        );
+    }
+
+    #[test]
+    fn copy_dir_from_preserve_metadata() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).preserve_metadata();
+        let source = Directory::create(temp_dir.path().join("fixture"));
+        std::fs::create_dir(source.path().join("subdir")).unwrap();
+        source.write_string("subdir/nested.txt", "nested");
+        let mtime = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        util::set_modified(&source.path().join("subdir/nested.txt"), mtime).unwrap();
+        util::set_modified(&source.path().join("subdir"), mtime).unwrap();
+        let mut permissions = std::fs::metadata(source.path().join("subdir/nested.txt"))
+            .unwrap()
+            .permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(source.path().join("subdir/nested.txt"), permissions).unwrap();
+
+        let copy = directory.copy_dir_from(&source, "fixture");
+
+        let metadata = std::fs::metadata(copy.join("subdir/nested.txt")).unwrap();
+        assert_eq!(metadata.modified().unwrap(), mtime);
+        assert!(metadata.permissions().readonly());
+        let dir_metadata = std::fs::metadata(copy.join("subdir")).unwrap();
+        assert_eq!(dir_metadata.modified().unwrap(), mtime);
+    }
+
+    #[test]
+    fn move_out() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        std::fs::create_dir(directory.path().join("results")).unwrap();
+        directory.write_string("results/out.txt", "result");
+        let target = temp_dir.path().join("archive/results");
+
+        directory.move_out("results", &target);
+
+        assert_eq!(
+            std::fs::read_to_string(target.join("out.txt")).unwrap(),
+            "result"
+        );
+        assert!(!directory.path().join("results").exists());
+    }
+
+    #[test]
+    fn move_across_devices() {
+        let temp_dir = tempdir().unwrap();
+        let source = temp_dir.path().join("source");
+        std::fs::create_dir_all(source.join("subdir")).unwrap();
+        std::fs::write(source.join("file.txt"), "content").unwrap();
+        std::fs::write(source.join("subdir/large.bin"), vec![7; 200 * 1024]).unwrap();
+        let target = temp_dir.path().join("target");
+
+        super::move_across_devices(&source, &target).unwrap();
+
+        assert!(!source.exists());
+        assert_eq!(
+            std::fs::read_to_string(target.join("file.txt")).unwrap(),
+            "content"
+        );
+        assert_eq!(
+            std::fs::read(target.join("subdir/large.bin")).unwrap(),
+            vec![7; 200 * 1024]
+        );
+    }
+
+    #[test]
+    fn verify_copy_detects_differences() {
+        let temp_dir = tempdir().unwrap();
+        let source = temp_dir.path().join("source.txt");
+        let target = temp_dir.path().join("target.txt");
+        std::fs::write(&source, "content").unwrap();
+        std::fs::write(&target, "CONTENT").unwrap();
+
+        let result = verify_copy(&source, &target);
+
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+}
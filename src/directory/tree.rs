@@ -0,0 +1,178 @@
+use super::*;
+
+use std::path::Path;
+
+/// A tree of files and subdirectories to create in a directory with `Directory::build`,
+/// usually written inline with the `tree!` macro.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Tree {
+    entries: Vec<(&'static str, TreeEntry)>,
+}
+
+/// An entry of a `Tree`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TreeEntry {
+    /// A file with the given content.
+    File(Vec<u8>),
+    /// A subdirectory with the given entries.
+    Dir(Tree),
+}
+
+impl Tree {
+    /// Creates a new Tree instance with the given entries.
+    ///
+    /// # Arguments
+    /// * `entries` - The names and entries of the tree, in the order of creation.
+    pub fn new(entries: Vec<(&'static str, TreeEntry)>) -> Self {
+        Self { entries }
+    }
+}
+
+impl TreeEntry {
+    /// Creates a file entry with the given content, e.g. a string or a byte slice.
+    pub fn file<C: AsRef<[u8]>>(content: C) -> Self {
+        Self::File(content.as_ref().to_vec())
+    }
+}
+
+/// Builds a `Tree` inline, e.g. for test fixtures.
+/// Each entry maps a name (a string literal, which may contain `/`) to either the content
+/// of a file (anything that implements `AsRef<[u8]>`) or, in braces, a subdirectory.
+///
+/// ```rust
+/// let tree = conv_wd::tree! {
+///     "config.toml" => "name = \"demo\"",
+///     "data" => {
+///         "a.bin" => [0u8, 1, 2],
+///         "empty" => {},
+///     },
+/// };
+/// ```
+#[macro_export]
+macro_rules! tree {
+    (@entries [$($entries:expr,)*]) => {
+        $crate::Tree::new(vec![$($entries,)*])
+    };
+    (@entries [$($entries:expr,)*] $name:literal => { $($dir:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::tree!(
+            @entries [$($entries,)* ($name, $crate::TreeEntry::Dir($crate::tree!($($dir)*))),]
+            $($($rest)*)?
+        )
+    };
+    (@entries [$($entries:expr,)*] $name:literal => $content:expr $(, $($rest:tt)*)?) => {
+        $crate::tree!(
+            @entries [$($entries,)* ($name, $crate::TreeEntry::file($content)),]
+            $($($rest)*)?
+        )
+    };
+    ($($tokens:tt)*) => {
+        $crate::tree!(@entries [] $($tokens)*)
+    };
+}
+
+/// Methods for creating fixture trees.
+impl Directory {
+    /// Creates the files and subdirectories of the given tree in the directory.
+    /// Existing files are overwritten, and missing parent directories are created.
+    /// Panics if a name is an absolute path or if a write operation fails.
+    ///
+    /// # Arguments
+    /// * `tree` - The tree to create, e.g. built with the `tree!` macro.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::{Directory, tree};
+    ///
+    /// let dir = Directory::cargo_target_subdir("build_example");
+    /// dir.build(tree! {
+    ///     "config.toml" => "name = \"demo\"",
+    ///     "data" => { "a.bin" => b"\x00\x01" },
+    /// });
+    /// assert!(dir.path().join("data/a.bin").exists());
+    /// ```
+    pub fn build(&self, tree: Tree) {
+        let mut files = Vec::new();
+        self.build_below(Path::new(""), tree, &mut files);
+        self.write_batch(files);
+    }
+
+    /// Creates the subdirectories of the given tree below `relative_dir`
+    /// and collects its files, which are written afterwards in one batch.
+    /// Panics if a name is an absolute path or if a directory cannot be created.
+    fn build_below(&self, relative_dir: &Path, tree: Tree, files: &mut Vec<(PathBuf, Vec<u8>)>) {
+        for (name, entry) in tree.entries {
+            crate::util::assert_relative_path(Path::new(name));
+            let relative_path = relative_dir.join(name);
+            match entry {
+                TreeEntry::File(content) => {
+                    if let Some(parent) = relative_path.parent() {
+                        self.create_dir_for_build(parent);
+                    }
+                    files.push((relative_path, content));
+                }
+                TreeEntry::Dir(tree) => {
+                    self.create_dir_for_build(&relative_path);
+                    self.build_below(&relative_path, tree, files);
+                }
+            }
+        }
+    }
+
+    /// Creates the subdirectory at `relative_path` and its missing parents.
+    /// Panics if a directory cannot be created.
+    fn create_dir_for_build(&self, relative_path: &Path) {
+        let path = self.path.join(relative_path);
+        std::fs::create_dir_all(&path)
+            .unwrap_or_else(|e| panic!("Failed to create directory at {}: {e}", path.display()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn tree_macro() {
+        let content = String::from("text");
+
+        let tree = crate::tree! {
+            "a.txt" => content,
+            "dir" => { "b.bin" => [1u8, 2] },
+        };
+
+        assert_eq!(
+            tree,
+            Tree::new(vec![
+                ("a.txt", TreeEntry::File(b"text".to_vec())),
+                (
+                    "dir",
+                    TreeEntry::Dir(Tree::new(vec![("b.bin", TreeEntry::File(vec![1, 2]))]))
+                ),
+            ])
+        );
+        assert_eq!(crate::tree! {}, Tree::default());
+    }
+
+    #[test]
+    fn build() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        directory.build(crate::tree! {
+            "config.toml" => "key = 1",
+            "data" => {
+                "nested/a.bin" => b"\x00\x01",
+                "empty" => {},
+            },
+        });
+
+        assert_eq!(directory.read_string("config.toml"), "key = 1");
+        assert_eq!(
+            std::fs::read(directory.path().join("data/nested/a.bin")).unwrap(),
+            [0, 1]
+        );
+        assert!(directory.path().join("data/empty").is_dir());
+    }
+}
@@ -0,0 +1,322 @@
+//! Batch file operations through io_uring on Linux (experimental).
+//!
+//! Materializing or removing a tree with hundreds of thousands of entries costs at least
+//! one system call per entry and operation. With io_uring, the operations are queued
+//! and each batch of them is submitted with a single system call.
+//! If io_uring is not available (e.g. on kernels before 5.11 or when it is blocked by a
+//! seccomp policy), the functions return `None` and the callers fall back to the
+//! standard library.
+
+use io_uring::{IoUring, Probe, opcode, squeue, types};
+use std::cmp::Reverse;
+use std::ffi::CString;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+
+/// Number of operations that are submitted at once.
+const BATCH_SIZE: usize = 256;
+
+/// Maximum number of bytes that are written by a single operation.
+/// The rest of larger files is written with the standard library.
+const MAX_WRITE_LEN: usize = 1 << 30;
+
+/// Writes the contents to the files at the given paths, creating or truncating them,
+/// like `std::fs::write` for each of them.
+/// Returns the result for each file, or `None` if io_uring is not available.
+pub(super) fn write_files(files: &[(PathBuf, &[u8])]) -> Option<Vec<io::Result<()>>> {
+    let mut ring = ring()?;
+    let results = files
+        .chunks(BATCH_SIZE)
+        .flat_map(|chunk| {
+            write_chunk(&mut ring, chunk).unwrap_or_else(|_| {
+                chunk
+                    .iter()
+                    .map(|(path, content)| std::fs::write(path, content))
+                    .collect()
+            })
+        })
+        .collect();
+    Some(results)
+}
+
+/// Removes the directory at `path` and all of its contents, like `std::fs::remove_dir_all`,
+/// or returns `None` if io_uring is not available.
+/// Symlinks are removed without following them, so nothing outside the tree is touched.
+/// If some entries cannot be removed, the remaining entries are still removed
+/// and the first error is returned.
+pub(super) fn remove_dir_all(path: &Path) -> Option<io::Result<()>> {
+    let mut ring = ring()?;
+    Some(remove_with_ring(&mut ring, path))
+}
+
+/// Creates a ring that supports all operations used in this module,
+/// or returns `None` if io_uring is not available.
+fn ring() -> Option<IoUring> {
+    let ring = IoUring::new(BATCH_SIZE as u32).ok()?;
+    let mut probe = Probe::new();
+    ring.submitter().register_probe(&mut probe).ok()?;
+    [
+        opcode::OpenAt::CODE,
+        opcode::Write::CODE,
+        opcode::Close::CODE,
+        opcode::UnlinkAt::CODE,
+    ]
+    .into_iter()
+    .all(|code| probe.is_supported(code))
+    .then_some(ring)
+}
+
+/// Opens, writes, and closes a chunk of files, with one submission per step.
+/// Returns an error if the operations cannot be submitted.
+fn write_chunk(ring: &mut IoUring, files: &[(PathBuf, &[u8])]) -> io::Result<Vec<io::Result<()>>> {
+    let mut results = Vec::with_capacity(files.len());
+    let mut paths = Vec::with_capacity(files.len());
+    for (path, _) in files {
+        match c_path(path) {
+            Ok(path) => {
+                paths.push(Some(path));
+                results.push(Ok(()));
+            }
+            Err(e) => {
+                paths.push(None);
+                results.push(Err(e));
+            }
+        }
+    }
+
+    let opens = paths
+        .iter()
+        .enumerate()
+        .filter_map(|(index, path)| Some((index, path.as_ref()?)))
+        .map(|(index, path)| {
+            let open = opcode::OpenAt::new(types::Fd(libc::AT_FDCWD), path.as_ptr())
+                .flags(libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC | libc::O_CLOEXEC)
+                .mode(0o666)
+                .build();
+            (index, open)
+        })
+        .collect::<Vec<_>>();
+    // SAFETY: The paths outlive the operations.
+    let opened = unsafe { run(ring, opens.iter().map(|(_, open)| open.clone())) }?;
+    let mut fds = Vec::with_capacity(opens.len());
+    for ((index, _), result) in opens.iter().zip(opened) {
+        match result {
+            // SAFETY: The kernel returned a new file descriptor that is owned by nobody else.
+            Ok(fd) => fds.push((*index, unsafe { OwnedFd::from_raw_fd(fd as i32) })),
+            Err(e) => results[*index] = Err(e),
+        }
+    }
+
+    let writes = fds.iter().map(|(index, fd)| {
+        let content = files[*index].1;
+        let len = content.len().min(MAX_WRITE_LEN);
+        opcode::Write::new(types::Fd(fd.as_raw_fd()), content.as_ptr(), len as u32)
+            .offset(0)
+            .build()
+    });
+    // SAFETY: The contents and file descriptors outlive the operations.
+    let written = unsafe { run(ring, writes) }?;
+    for ((index, fd), result) in fds.iter().zip(written) {
+        let content = files[*index].1;
+        results[*index] = result.and_then(|written| {
+            // Short writes are rare for regular files; the rest is written without the ring.
+            let written = written as usize;
+            if written < content.len() {
+                std::fs::File::from(fd.try_clone()?)
+                    .write_all_at(&content[written..], written as u64)
+            } else {
+                Ok(())
+            }
+        });
+    }
+
+    let (indices, fds): (Vec<_>, Vec<_>) = fds
+        .into_iter()
+        .map(|(index, fd)| (index, fd.into_raw_fd()))
+        .unzip();
+    let closes = fds
+        .iter()
+        .map(|&fd| opcode::Close::new(types::Fd(fd)).build());
+    // SAFETY: The operations do not reference any memory.
+    let closed = unsafe { run(ring, closes) }?;
+    for (index, result) in indices.into_iter().zip(closed) {
+        if let (Ok(()), Err(e)) = (&results[index], result) {
+            results[index] = Err(e);
+        }
+    }
+    Ok(results)
+}
+
+/// Removes a tree: all files and symlinks first, then the directories from the deepest
+/// level up, with one submission per batch.
+fn remove_with_ring(ring: &mut IoUring, path: &Path) -> io::Result<()> {
+    let mut files = Vec::new();
+    let mut dirs = vec![(0, path.to_path_buf())];
+    collect_entries(path, 1, &mut files, &mut dirs)?;
+
+    let mut first_error = unlink_all(ring, &files, 0)?.err();
+    dirs.sort_by_key(|(depth, _)| Reverse(*depth));
+    for level in dirs.chunk_by(|(a, _), (b, _)| a == b) {
+        let paths = level
+            .iter()
+            .map(|(_, path)| path.clone())
+            .collect::<Vec<_>>();
+        if let Err(e) = unlink_all(ring, &paths, libc::AT_REMOVEDIR)? {
+            first_error.get_or_insert(e);
+        }
+    }
+    first_error.map_or(Ok(()), Err)
+}
+
+/// Collects the files and symlinks below `path` and the directories with their depths.
+fn collect_entries(
+    path: &Path,
+    depth: usize,
+    files: &mut Vec<PathBuf>,
+    dirs: &mut Vec<(usize, PathBuf)>,
+) -> io::Result<()> {
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            collect_entries(&entry.path(), depth + 1, files, dirs)?;
+            dirs.push((depth, entry.path()));
+        } else {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Unlinks the given paths with the given `unlinkat` flags, ignoring missing entries.
+/// Returns the first error of an operation in the inner result, and an error in the outer
+/// result if the operations cannot be submitted.
+fn unlink_all(ring: &mut IoUring, paths: &[PathBuf], flags: i32) -> io::Result<io::Result<()>> {
+    let paths = paths
+        .iter()
+        .map(|path| c_path(path))
+        .collect::<io::Result<Vec<_>>>()?;
+    let unlinks = paths.iter().map(|path| {
+        opcode::UnlinkAt::new(types::Fd(libc::AT_FDCWD), path.as_ptr())
+            .flags(flags)
+            .build()
+    });
+    // SAFETY: The paths outlive the operations.
+    let results = unsafe { run(ring, unlinks) }?;
+    Ok(results
+        .into_iter()
+        .filter(|result| !matches!(result, Err(e) if e.kind() == io::ErrorKind::NotFound))
+        .find_map(Result::err)
+        .map_or(Ok(()), Err))
+}
+
+/// Submits the operations in batches and returns their results in the same order.
+/// Returns an error if the operations cannot be submitted.
+///
+/// # Safety
+/// All memory referenced by the operations must stay valid until this function returns.
+unsafe fn run(
+    ring: &mut IoUring,
+    operations: impl IntoIterator<Item = squeue::Entry>,
+) -> io::Result<Vec<io::Result<u32>>> {
+    let operations = operations.into_iter().collect::<Vec<_>>();
+    let mut results = Vec::with_capacity(operations.len());
+    for batch in operations.chunks(BATCH_SIZE) {
+        let offset = results.len();
+        results.resize_with(offset + batch.len(), || Ok(0));
+        for (index, operation) in batch.iter().enumerate() {
+            let operation = operation.clone().user_data(index as u64);
+            // SAFETY: The caller guarantees that the referenced memory stays valid,
+            // and all operations are completed before this function returns.
+            unsafe { ring.submission().push(&operation) }
+                .map_err(|_| io::Error::other("the submission queue is full"))?;
+        }
+        // If submitting fails, no operation was handed to the kernel.
+        ring.submit()?;
+        let mut pending = batch.len();
+        while pending > 0 {
+            // Submitted operations must complete before their memory may be released,
+            // so waiting is retried after interruptions and other transient errors.
+            let _ = ring.submit_and_wait(pending);
+            for completion in ring.completion() {
+                let result = completion.result();
+                results[offset + completion.user_data() as usize] = if result < 0 {
+                    Err(io::Error::from_raw_os_error(-result))
+                } else {
+                    Ok(result as u32)
+                };
+                pending -= 1;
+            }
+        }
+    }
+    Ok(results)
+}
+
+/// Converts a path into a C string.
+fn c_path(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn write_files() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("file0.txt"), "old content").unwrap();
+        let contents = (0..300)
+            .map(|i| format!("content {i}").into_bytes())
+            .collect::<Vec<_>>();
+        let mut files = contents
+            .iter()
+            .enumerate()
+            .map(|(i, content)| (temp_dir.path().join(format!("file{i}.txt")), &content[..]))
+            .collect::<Vec<_>>();
+        files.push((temp_dir.path().join("missing/file.txt"), b"content"));
+
+        let Some(results) = super::write_files(&files) else {
+            return; // io_uring is not available
+        };
+
+        assert_eq!(results.len(), 301);
+        assert!(results[..300].iter().all(Result::is_ok));
+        assert_eq!(
+            results[300].as_ref().unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+        for (path, content) in &files[..300] {
+            assert_eq!(std::fs::read(path).unwrap(), *content);
+        }
+    }
+
+    #[test]
+    fn remove_dir_all() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("root");
+        for i in 0..10 {
+            let subdir = root.join(format!("dir{i}/nested"));
+            std::fs::create_dir_all(&subdir).unwrap();
+            for j in 0..30 {
+                std::fs::write(subdir.join(format!("file{j}.txt")), b"content").unwrap();
+            }
+        }
+        let outside = temp_dir.path().join("outside");
+        std::fs::create_dir(&outside).unwrap();
+        std::fs::write(outside.join("file.txt"), b"content").unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+        let Some(result) = super::remove_dir_all(&root) else {
+            return; // io_uring is not available
+        };
+
+        result.unwrap();
+        assert!(!root.exists());
+        assert!(outside.join("file.txt").exists());
+    }
+}
@@ -3,20 +3,187 @@ use super::*;
 /// Utility functions for internal use.
 impl Directory {
     /// Creates the directory on the file system if it does not exist.
-    /// Panics if the directory cannot be created.
+    /// Panics if the path exceeds the platform's length limits or if the directory
+    /// cannot be created.
     pub(super) fn ensure_exists(&self) {
+        crate::util::check_path_length(&self.path).unwrap_or_else(|e| panic!("{e}"));
         std::fs::create_dir_all(&self.path).unwrap_or_else(|e| {
             panic!("Failed to create directory at {}: {e}", self.path.display())
         });
     }
 
     /// Removes the directory from the file system if it still exists.
+    /// Failed attempts are retried according to the directory's retry policy.
     /// Panics if the directory cannot be removed.
     pub(super) fn remove(&self) {
-        if self.path.exists() {
-            std::fs::remove_dir_all(&self.path).unwrap_or_else(|e| {
-                panic!("Failed to remove directory at {}: {e}", self.path.display())
-            });
+        self.try_remove().unwrap_or_else(|e| {
+            panic!("Failed to remove directory at {}: {e}", self.path.display())
+        });
+    }
+
+    /// Removes the directory from the file system if it still exists.
+    /// Failed attempts are retried according to the directory's retry policy.
+    pub(super) fn try_remove(&self) -> std::io::Result<()> {
+        let files = self.count_files_for_stats();
+        let result = remove_dir_with_retry(&self.path, &self.remove_retry);
+        match result {
+            Ok(()) => self.record_removed(files),
+            Err(_) => self.record_error(),
+        }
+        result
+    }
+
+    /// Returns a human-readable listing of the directory tree, e.g. for failure messages.
+    /// Entries are sorted by name, directories are marked with a trailing `/`,
+    /// and entries that cannot be read are reported inline instead of panicking.
+    pub(super) fn tree_listing(&self) -> String {
+        let mut listing = format!("{}\n", self.path.display());
+        append_tree_listing(&self.path, 1, &mut listing);
+        listing
+    }
+}
+
+/// Returns the topmost directory among `path` and its ancestors that does not exist,
+/// i.e. the first component that is created when `path` is created with all of its parents.
+/// Returns `None` if `path` already exists.
+pub(super) fn first_missing_ancestor(path: &std::path::Path) -> Option<PathBuf> {
+    path.ancestors()
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .take_while(|ancestor| std::fs::symlink_metadata(ancestor).is_err())
+        .last()
+        .map(std::path::Path::to_path_buf)
+}
+
+/// Removes the directory at `path` if it still exists, retrying according to `policy`.
+/// With the `io-uring` feature on Linux, the contents are removed in batches through
+/// io_uring if it is available. Otherwise, with the `rayon` feature, they are removed
+/// in parallel.
+pub(super) fn remove_dir_with_retry(
+    path: &std::path::Path,
+    policy: &RetryPolicy,
+) -> std::io::Result<()> {
+    policy.run(|| match remove_dir_all(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    })
+}
+
+/// Removes the directory at `path` and all of its contents.
+fn remove_dir_all(path: &std::path::Path) -> std::io::Result<()> {
+    #[cfg(all(feature = "io-uring", target_os = "linux"))]
+    if let Some(result) = uring::remove_dir_all(path) {
+        return result;
+    }
+    #[cfg(feature = "rayon")]
+    return parallel::remove_dir_all(path);
+    #[cfg(not(feature = "rayon"))]
+    std::fs::remove_dir_all(path)
+}
+
+/// Sets the modification time of the file or directory at `path`.
+/// Unlike opening the file for writing, this also works for read-only files.
+pub(super) fn set_modified(
+    path: &std::path::Path,
+    time: std::time::SystemTime,
+) -> std::io::Result<()> {
+    set_times(path, std::fs::FileTimes::new().set_modified(time))
+}
+
+/// Sets the given timestamps of the file or directory at `path`, following symlinks.
+/// Unlike opening the file for writing, this also works for read-only files.
+pub(super) fn set_times(path: &std::path::Path, times: std::fs::FileTimes) -> std::io::Result<()> {
+    #[cfg(windows)]
+    let file = {
+        use std::os::windows::fs::OpenOptionsExt;
+        use windows_sys::Win32::Storage::FileSystem::{
+            FILE_FLAG_BACKUP_SEMANTICS, FILE_WRITE_ATTRIBUTES,
+        };
+
+        // Directories can only be opened with backup semantics.
+        std::fs::File::options()
+            .access_mode(FILE_WRITE_ATTRIBUTES)
+            .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+            .open(path)?
+    };
+    #[cfg(not(windows))]
+    let file = std::fs::File::open(path)?;
+    file.set_times(times)
+}
+
+/// Returns the paths of all entries below `root`, relative to `root`, in sorted order.
+/// Directories are listed before their contents, and symlinks are not followed,
+/// so symlink cycles cannot cause endless recursion.
+pub(super) fn walk(root: &std::path::Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut entries = Vec::new();
+    append_entries(root, std::path::Path::new(""), 1, usize::MAX, &mut entries)?;
+    Ok(entries)
+}
+
+/// Returns the entries below `root` like `walk`, or `Error::DepthExceeded` as soon as
+/// an entry is found that is nested deeper than `max_depth` levels below `root`,
+/// where the entries of `root` itself are at depth 1.
+pub(super) fn walk_to_depth(
+    root: &std::path::Path,
+    max_depth: usize,
+) -> crate::Result<Vec<PathBuf>> {
+    let mut entries = Vec::new();
+    match append_entries(root, std::path::Path::new(""), 1, max_depth, &mut entries) {
+        Ok(None) => Ok(entries),
+        Ok(Some(relative_path)) => Err(crate::Error::DepthExceeded {
+            path: root.join(relative_path),
+            max_depth,
+        }),
+        Err(e) => Err(crate::Error::io(root, e)),
+    }
+}
+
+/// Appends the relative paths of the entries of `root.join(relative_dir)`, which are at
+/// the given depth, to `entries`.
+/// Stops and returns the relative path of the first entry deeper than `max_depth`, if any.
+fn append_entries(
+    root: &std::path::Path,
+    relative_dir: &std::path::Path,
+    depth: usize,
+    max_depth: usize,
+    entries: &mut Vec<PathBuf>,
+) -> std::io::Result<Option<PathBuf>> {
+    let mut dir_entries =
+        std::fs::read_dir(root.join(relative_dir))?.collect::<std::io::Result<Vec<_>>>()?;
+    dir_entries.sort_by_key(|entry| entry.file_name());
+    for entry in dir_entries {
+        let relative_path = relative_dir.join(entry.file_name());
+        if depth > max_depth {
+            return Ok(Some(relative_path));
+        }
+        entries.push(relative_path.clone());
+        if entry.file_type()?.is_dir()
+            && let Some(too_deep) =
+                append_entries(root, &relative_path, depth + 1, max_depth, entries)?
+        {
+            return Ok(Some(too_deep));
+        }
+    }
+    Ok(None)
+}
+
+/// Appends the entries of the directory at `path` to `listing`, indented by `depth` levels.
+fn append_tree_listing(path: &std::path::Path, depth: usize, listing: &mut String) {
+    let indent = "  ".repeat(depth);
+    let mut entries = match std::fs::read_dir(path) {
+        Ok(entries) => entries.filter_map(Result::ok).collect::<Vec<_>>(),
+        Err(e) => {
+            listing.push_str(&format!("{indent}<failed to read directory: {e}>\n"));
+            return;
+        }
+    };
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name();
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            listing.push_str(&format!("{indent}{}/\n", name.to_string_lossy()));
+            append_tree_listing(&entry.path(), depth + 1, listing);
+        } else {
+            listing.push_str(&format!("{indent}{}\n", name.to_string_lossy()));
         }
     }
 }
@@ -32,10 +199,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let dir_path = temp_dir.path().join("test_dir");
 
-        let directory = Directory {
-            path: dir_path.clone(),
-            keep_on_drop: false,
-        };
+        let directory = Directory::new_unchecked(&dir_path);
         directory.ensure_exists();
 
         assert!(directory.path.exists());
@@ -51,13 +215,60 @@ mod tests {
         assert!(dir_path.exists());
         assert!(dir_path.is_dir());
 
-        let directory = Directory {
-            path: dir_path.clone(),
-            keep_on_drop: true,
-        };
+        let mut directory = Directory::new_unchecked(&dir_path);
+        directory.keep_on_drop = true;
 
         directory.remove();
 
         assert!(!dir_path.exists());
     }
+
+    #[test]
+    fn walk() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("b/c")).unwrap();
+        std::fs::write(temp_dir.path().join("b/c/file.txt"), b"").unwrap();
+        std::fs::write(temp_dir.path().join("a.txt"), b"").unwrap();
+
+        let entries = super::walk(temp_dir.path()).unwrap();
+
+        assert_eq!(
+            entries,
+            ["a.txt", "b", "b/c", "b/c/file.txt"].map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn walk_to_depth() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("b/c")).unwrap();
+        std::fs::write(temp_dir.path().join("b/c/file.txt"), b"").unwrap();
+
+        assert_eq!(super::walk_to_depth(temp_dir.path(), 3).unwrap().len(), 3);
+        assert!(matches!(
+            super::walk_to_depth(temp_dir.path(), 2),
+            Err(crate::Error::DepthExceeded { path, max_depth: 2 })
+                if path == temp_dir.path().join("b/c/file.txt")
+        ));
+    }
+
+    #[test]
+    fn tree_listing() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("test_dir");
+        std::fs::create_dir_all(dir_path.join("subdir")).unwrap();
+        std::fs::write(dir_path.join("subdir/nested.txt"), b"").unwrap();
+        std::fs::write(dir_path.join("file.txt"), b"").unwrap();
+
+        let mut directory = Directory::new_unchecked(&dir_path);
+        directory.keep_on_drop = true;
+
+        assert_eq!(
+            directory.tree_listing(),
+            format!(
+                "{}\n  file.txt\n  subdir/\n    nested.txt\n",
+                dir_path.display()
+            )
+        );
+    }
 }
@@ -0,0 +1,186 @@
+use super::*;
+
+use std::path::Path;
+
+use crate::util::{check_path_length, check_portable_path};
+use crate::{Error, Result};
+
+/// Methods for validating paths before they are created, so problems are reported
+/// with an actionable error instead of an opaque OS error, or only later on another platform.
+impl Directory {
+    /// Creates a new Directory instance from self that rejects file names that are
+    /// reserved or invalid on Windows (see `util::check_portable_path`) in the `write_*`
+    /// methods, so trees generated on other platforms don't fail later on Windows.
+    /// On Windows, such names are always rejected.
+    pub fn with_portable_names(mut self) -> Self {
+        self.portable_names = true;
+        self
+    }
+
+    /// Creates a new Directory instance from self that refuses to write files larger than
+    /// `max` bytes in the `write_*` methods, so that accidental huge writes (e.g. by a
+    /// runaway serializer) fail fast with `Error::FileTooLarge` instead of filling the disk.
+    /// The limit applies to each write operation separately, i.e. not to the total size
+    /// of files that are appended to.
+    ///
+    /// # Arguments
+    /// * `max` - The maximum size of a single file in bytes.
+    pub fn with_max_file_size(mut self, max: u64) -> Self {
+        self.max_file_size = Some(max);
+        self
+    }
+
+    /// Creates a new Directory instance from self that bounds its recursive operations
+    /// (walking the tree in `sync_to`, `copy_dir_from`, `diff_dir`, `bundle_to`, `clean`, ...)
+    /// to trees nested at most `max_depth` levels deep, so unexpectedly deep or adversarial
+    /// trees fail with `Error::DepthExceeded` instead of exhausting time or stack space.
+    /// The entries directly in a tree are at depth 1.
+    /// Removing the directory on drop is not bounded.
+    ///
+    /// # Arguments
+    /// * `max_depth` - The maximum depth of entries below the root of a tree.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Returns the entries below `root` (see `util::walk`), or `Error::DepthExceeded`
+    /// if the tree is nested deeper than the maximum depth, if any.
+    /// With `respect_ignore_files`, ignored entries are left out, and with `with_filter`,
+    /// only the selected entries are returned (with the `ignore` and `glob` features).
+    pub(super) fn walk_tree(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        let max_depth = self.max_depth.unwrap_or(usize::MAX);
+        #[cfg(feature = "ignore")]
+        let entries = if self.respect_ignore_files {
+            ignore_rules::walk_unignored(root, max_depth)?
+        } else {
+            util::walk_to_depth(root, max_depth)?
+        };
+        #[cfg(not(feature = "ignore"))]
+        let entries = util::walk_to_depth(root, max_depth)?;
+        #[cfg(feature = "glob")]
+        let entries = match &self.filter {
+            Some(filter) => filter.apply(entries),
+            None => entries,
+        };
+        Ok(entries)
+    }
+
+    /// Checks that content of `size` bytes for the file at the given relative path
+    /// does not exceed the maximum file size, if any.
+    pub(super) fn check_size(&self, relative_path: &Path, size: usize) -> Result<()> {
+        match self.max_file_size {
+            Some(max) if size as u64 > max => Err(Error::FileTooLarge {
+                path: self.path.join(relative_path),
+                size: size as u64,
+                max,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks a relative path within the directory before it is written:
+    /// its file names if portable names are enforced, and the length of the
+    /// resulting path (see `util::check_path_length`).
+    pub(super) fn check_path(&self, relative_path: &Path) -> Result<()> {
+        if cfg!(windows) || self.portable_names {
+            check_portable_path(relative_path)?;
+        }
+        check_path_length(&self.path.join(relative_path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    #[should_panic(expected = "is reserved or invalid on Windows")]
+    fn with_portable_names() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_portable_names();
+
+        directory.write_string("aux.txt", "");
+    }
+
+    #[test]
+    fn with_portable_names_new() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_portable_names();
+
+        let result = directory.write_string_new("file.", "");
+
+        assert!(matches!(result, Err(crate::Error::ReservedName { .. })));
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the maximum file size of 4 bytes")]
+    fn with_max_file_size() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_max_file_size(4);
+
+        directory.write_string("small.txt", "1234");
+        directory.write_string("large.txt", "12345");
+    }
+
+    #[test]
+    fn with_max_file_size_new() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_max_file_size(4);
+
+        let result = directory.write_bytes_new("large.bin", [0; 5]);
+
+        assert!(matches!(
+            result,
+            Err(crate::Error::FileTooLarge {
+                size: 5,
+                max: 4,
+                ..
+            })
+        ));
+        assert!(!directory.path().join("large.bin").exists());
+    }
+
+    #[test]
+    fn with_max_depth() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_max_depth(2);
+        std::fs::create_dir_all(directory.path().join("a/b")).unwrap();
+        directory.write_string("a/b/file.txt", "");
+
+        let result = directory.walk_tree(directory.path());
+
+        assert!(matches!(
+            result,
+            Err(crate::Error::DepthExceeded { path, max_depth: 2 })
+                if path == directory.path().join("a/b/file.txt")
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the maximum depth of 1")]
+    fn with_max_depth_clean() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).with_max_depth(1);
+        std::fs::create_dir(directory.path().join("a")).unwrap();
+        directory.write_string("a/file.txt", "");
+
+        let _ = directory.clean();
+    }
+
+    #[test]
+    fn check_path_length() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let long_name = "x".repeat(300);
+
+        let result = directory.write_string_new(Path::new("subdir").join(&long_name), "");
+
+        assert!(
+            matches!(result, Err(crate::Error::PathTooLong { component, .. }) if component == long_name)
+        );
+    }
+}
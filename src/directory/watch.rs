@@ -0,0 +1,341 @@
+use super::*;
+
+use crate::util::assert_relative_path;
+use crate::{Error, Result};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Upper and lower bounds for the interval in which the tree is polled for changes.
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+const MAX_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Default interval in which a followed file is polled for new lines.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Paths, sizes, and modification times of the entries of a tree.
+type Snapshot = Vec<(PathBuf, u64, Option<SystemTime>)>;
+
+/// Methods for observing files that other processes write into the directory.
+impl Directory {
+    /// Waits until the tree of the directory has not changed for `idle`, e.g. when an
+    /// external process writes an unknown number of files and signals completion only
+    /// by going quiet. Files that are created, removed, or modified (size or modification
+    /// time) count as changes.
+    /// The tree is polled in intervals of a tenth of `idle` (between 10 ms and 500 ms),
+    /// so changes that are undone within one interval go unnoticed.
+    /// Returns `Error::Io` with `std::io::ErrorKind::TimedOut` if the tree is still
+    /// changing after `timeout`, or `Error::Io` if the tree cannot be read.
+    ///
+    /// # Arguments
+    /// * `idle` - How long the tree must remain unchanged.
+    /// * `timeout` - How long to wait at most.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    /// use std::time::Duration;
+    ///
+    /// let dir = Directory::cargo_target_subdir("wait_quiescent_example");
+    /// dir.write_string("output.txt", "done");
+    /// dir.wait_quiescent(Duration::from_millis(50), Duration::from_secs(5))
+    ///     .unwrap();
+    /// ```
+    pub fn wait_quiescent(&self, idle: Duration, timeout: Duration) -> Result<()> {
+        let start = Instant::now();
+        let poll_interval = (idle / 10).clamp(MIN_POLL_INTERVAL, MAX_POLL_INTERVAL);
+        let mut snapshot = self.snapshot()?;
+        let mut unchanged_since = Instant::now();
+        loop {
+            if unchanged_since.elapsed() >= idle {
+                return Ok(());
+            }
+            if start.elapsed() >= timeout {
+                return Err(Error::io(
+                    &self.path,
+                    std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        format!("the tree did not stop changing within {timeout:?}"),
+                    ),
+                ));
+            }
+            std::thread::sleep(poll_interval);
+            let current = self.snapshot()?;
+            if current != snapshot {
+                snapshot = current;
+                unchanged_since = Instant::now();
+            }
+        }
+    }
+
+    /// Follows a text file at the given path within the directory like `tail -F`,
+    /// e.g. to consume the log of another process while it is being written.
+    /// The returned iterator yields the lines of the file, including the ones that are
+    /// already in it, and blocks until the next complete line is appended.
+    /// It waits for the file to be created if it does not exist yet, starts over if the
+    /// file is truncated, and switches to the new file if it is replaced (on Unix, e.g.
+    /// by log rotation), after yielding the rest of the old one.
+    /// Panics if the path is absolute.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the file within the directory.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::Directory;
+    ///
+    /// let dir = Directory::cargo_target_subdir("follow_example");
+    /// dir.write_string("app.log", "started\n");
+    /// let mut log = dir.follow("app.log");
+    /// assert_eq!(log.next().unwrap().unwrap(), "started");
+    /// assert!(log.poll_line().unwrap().is_none());
+    /// ```
+    pub fn follow<P: AsRef<Path>>(&self, relative_path: P) -> Follow {
+        assert_relative_path(relative_path.as_ref());
+        Follow {
+            path: self.path.join(relative_path),
+            file: None,
+            position: 0,
+            buffer: Vec::new(),
+            poll_interval: FOLLOW_POLL_INTERVAL,
+        }
+    }
+
+    /// Returns the paths, sizes, and modification times of all entries in the tree.
+    /// Entries that disappear while the tree is read are left out, and `None` is returned
+    /// if a subdirectory disappears, so concurrent changes are reported as changes.
+    fn snapshot(&self) -> Result<Option<Snapshot>> {
+        let entries = match self.walk_tree(&self.path) {
+            Ok(entries) => entries,
+            Err(Error::Io { source, .. }) if source.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        let mut snapshot = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match std::fs::symlink_metadata(self.path.join(&entry)) {
+                Ok(metadata) => snapshot.push((entry, metadata.len(), metadata.modified().ok())),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(Error::io(self.path.join(entry), e)),
+            }
+        }
+        Ok(Some(snapshot))
+    }
+}
+
+/// Iterator returned by [`Directory::follow`].
+/// Yields the lines of a file as they are appended, without line endings.
+/// Lines that cannot be read (e.g. invalid UTF-8) are returned as `Error::Io`.
+/// The iterator never ends; use `poll_line` to check for new lines without blocking.
+pub struct Follow {
+    path: PathBuf,
+    file: Option<File>,
+    position: u64,
+    buffer: Vec<u8>,
+    poll_interval: Duration,
+}
+
+impl Follow {
+    /// Sets the interval in which the file is polled for new lines (50 ms by default).
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Returns the next complete line if one is available, without blocking.
+    /// Returns `Error::Io` if the file cannot be read or the line is not valid UTF-8.
+    pub fn poll_line(&mut self) -> Result<Option<String>> {
+        loop {
+            if let Some(end) = self.buffer.iter().position(|&byte| byte == b'\n') {
+                let mut line = self.buffer.drain(..=end).collect::<Vec<_>>();
+                line.pop();
+                if line.last() == Some(&b'\r') {
+                    line.pop();
+                }
+                return self.decode(line).map(Some);
+            }
+            if self.read_available()? == 0 {
+                if !self.is_replaced() {
+                    return Ok(None);
+                }
+                // Continue with the new file, after the rest of the old one.
+                self.file = None;
+                self.position = 0;
+                if !self.buffer.is_empty() {
+                    let line = std::mem::take(&mut self.buffer);
+                    return self.decode(line).map(Some);
+                }
+            }
+        }
+    }
+
+    /// Reads everything that was appended to the file since the last call into the buffer,
+    /// opening the file first if needed and starting over if it was truncated.
+    /// Returns the number of bytes read.
+    fn read_available(&mut self) -> Result<usize> {
+        if self.file.is_none() {
+            match File::open(&self.path) {
+                Ok(file) => self.file = Some(file),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+                Err(e) => return Err(Error::io(&self.path, e)),
+            }
+        }
+        let file = self.file.as_mut().expect("the file was opened above");
+        let result = file.metadata().and_then(|metadata| {
+            if metadata.len() < self.position {
+                self.position = file.seek(SeekFrom::Start(0))?;
+                self.buffer.clear();
+            }
+            file.read_to_end(&mut self.buffer)
+        });
+        let read = result.map_err(|e| Error::io(&self.path, e))?;
+        self.position += read as u64;
+        Ok(read)
+    }
+
+    /// Returns whether the path now refers to a different file than the open one.
+    #[cfg(unix)]
+    fn is_replaced(&self) -> bool {
+        use std::os::unix::fs::MetadataExt;
+
+        let Some(file) = &self.file else {
+            return false;
+        };
+        match (file.metadata(), std::fs::metadata(&self.path)) {
+            (Ok(open), Ok(current)) => (open.dev(), open.ino()) != (current.dev(), current.ino()),
+            _ => false,
+        }
+    }
+
+    /// Returns whether the path now refers to a different file than the open one.
+    /// Open files cannot be replaced on this platform.
+    #[cfg(not(unix))]
+    fn is_replaced(&self) -> bool {
+        false
+    }
+
+    /// Decodes a line as UTF-8.
+    fn decode(&self, line: Vec<u8>) -> Result<String> {
+        String::from_utf8(line).map_err(|e| {
+            Error::io(
+                &self.path,
+                std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+            )
+        })
+    }
+}
+
+impl Iterator for Follow {
+    type Item = Result<String>;
+
+    /// Blocks until the next complete line is available.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.poll_line() {
+                Ok(Some(line)) => return Some(Ok(line)),
+                Ok(None) => std::thread::sleep(self.poll_interval),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn wait_quiescent() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let dir_path = directory.path_buf();
+        directory.write_string("file0.txt", "data");
+
+        let writer = std::thread::spawn(move || {
+            for i in 1..5 {
+                std::fs::write(dir_path.join(format!("file{i}.txt")), "data").unwrap();
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        });
+        directory
+            .wait_quiescent(Duration::from_millis(200), Duration::from_secs(10))
+            .unwrap();
+        writer.join().unwrap();
+
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 5);
+    }
+
+    #[test]
+    fn wait_quiescent_timeout() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        let result = directory.wait_quiescent(Duration::from_secs(10), Duration::from_millis(50));
+
+        assert!(matches!(
+            result,
+            Err(Error::Io { source, .. }) if source.kind() == std::io::ErrorKind::TimedOut
+        ));
+    }
+
+    #[test]
+    fn follow() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        let mut log = directory.follow("app.log");
+        assert!(log.poll_line().unwrap().is_none());
+        directory.write_string("app.log", "first\r\nsec");
+        assert_eq!(log.poll_line().unwrap().unwrap(), "first");
+        assert!(log.poll_line().unwrap().is_none());
+        let mut file = std::fs::File::options()
+            .append(true)
+            .open(directory.path().join("app.log"))
+            .unwrap();
+        std::io::Write::write_all(&mut file, b"ond\n").unwrap();
+
+        assert_eq!(log.next().unwrap().unwrap(), "second");
+    }
+
+    #[test]
+    fn follow_truncated() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("app.log", "old line\n");
+        let mut log = directory.follow("app.log");
+        assert_eq!(log.next().unwrap().unwrap(), "old line");
+
+        directory.write_string("app.log", "new\n");
+
+        assert_eq!(log.next().unwrap().unwrap(), "new");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follow_rotated() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("app.log", "before\n");
+        let mut log = directory.follow("app.log");
+        assert_eq!(log.next().unwrap().unwrap(), "before");
+
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(directory.path().join("app.log"))
+            .and_then(|mut file| std::io::Write::write_all(&mut file, b"last"))
+            .unwrap();
+        std::fs::rename(
+            directory.path().join("app.log"),
+            directory.path().join("app.log.1"),
+        )
+        .unwrap();
+        directory.write_string("app.log", "after\n");
+
+        assert_eq!(log.next().unwrap().unwrap(), "last");
+        assert_eq!(log.next().unwrap().unwrap(), "after");
+    }
+}
@@ -0,0 +1,152 @@
+use super::*;
+
+use std::path::Path;
+
+use crate::util::validate_relative_path;
+use crate::{Error, Result};
+
+/// Methods for reading and writing extended attributes of entries within the directory,
+/// e.g. for testing software that stores metadata in them.
+///
+/// Names include their namespace where the platform has one, e.g. `user.checksum`
+/// on Linux. Symlinks are followed.
+impl Directory {
+    /// Sets the extended attribute with the given name of the entry at the given path
+    /// within the directory, replacing an existing value.
+    /// Returns `Error::PathIsAbsolute` if the path is absolute, or `Error::Io` if the
+    /// attribute cannot be set, e.g. because the file system does not support it.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the entry within the directory.
+    /// * `name` - The name of the attribute, e.g. `user.checksum`.
+    /// * `value` - The value of the attribute.
+    pub fn set_xattr<P: AsRef<Path>, V: AsRef<[u8]>>(
+        &self,
+        relative_path: P,
+        name: &str,
+        value: V,
+    ) -> Result<()> {
+        let relative_path = relative_path.as_ref();
+        validate_relative_path(relative_path)?;
+        let entry_path = self.path.join(relative_path);
+        xattr::set(&entry_path, name, value.as_ref()).map_err(|e| Error::io(entry_path, e))
+    }
+
+    /// Returns the value of the extended attribute with the given name of the entry at
+    /// the given path within the directory, or `None` if the entry has no such attribute.
+    /// Returns `Error::PathIsAbsolute` if the path is absolute, or `Error::Io` if the
+    /// attributes cannot be read, e.g. because the entry does not exist.
+    ///
+    /// # Arguments
+    /// * `relative_path` - The path of the entry within the directory.
+    /// * `name` - The name of the attribute, e.g. `user.checksum`.
+    pub fn get_xattr<P: AsRef<Path>>(
+        &self,
+        relative_path: P,
+        name: &str,
+    ) -> Result<Option<Vec<u8>>> {
+        let relative_path = relative_path.as_ref();
+        validate_relative_path(relative_path)?;
+        let entry_path = self.path.join(relative_path);
+        xattr::get(&entry_path, name).map_err(|e| Error::io(entry_path, e))
+    }
+}
+
+/// Copies the extended attributes of the file or directory at `source` to `target`.
+/// Attributes are skipped if the source or the target does not support them, or if they
+/// require privileges (e.g. in the `security` and `trusted` namespaces on Linux).
+pub(super) fn copy_xattrs(source: &Path, target: &Path) -> std::io::Result<()> {
+    // `ENOTSUP` and `EOPNOTSUPP` are the same on some platforms.
+    let skipped = |e: &std::io::Error| {
+        e.raw_os_error()
+            .is_some_and(|code| [libc::ENOTSUP, libc::EOPNOTSUPP, libc::EPERM].contains(&code))
+    };
+    let names = match xattr::list(source) {
+        Err(e) if skipped(&e) => return Ok(()),
+        result => result?,
+    };
+    for name in names {
+        let Some(value) = xattr::get(source, &name)? else {
+            continue;
+        };
+        match xattr::set(target, &name, &value) {
+            Err(e) if skipped(&e) => {}
+            result => result?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    /// Returns whether the file system of `path` supports user attributes.
+    fn supported(path: &Path) -> bool {
+        xattr::set(path, "user.probe", b"").is_ok()
+    }
+
+    #[test]
+    fn set_xattr() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        directory.write_string("file.txt", "");
+        if !supported(&directory.path().join("file.txt")) {
+            return;
+        }
+
+        directory
+            .set_xattr("file.txt", "user.checksum", b"abc")
+            .unwrap();
+
+        assert_eq!(
+            directory.get_xattr("file.txt", "user.checksum").unwrap(),
+            Some(b"abc".to_vec())
+        );
+        assert_eq!(
+            directory.get_xattr("file.txt", "user.missing").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn get_xattr_missing_entry() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+
+        let result = directory.get_xattr("missing.txt", "user.checksum");
+
+        assert!(matches!(result, Err(Error::Io { .. })));
+    }
+
+    #[test]
+    fn copy_dir_from_preserves_xattrs() {
+        let temp_dir = tempdir().unwrap();
+        let source = Directory::create(temp_dir.path().join("source"));
+        std::fs::create_dir(source.path().join("subdir")).unwrap();
+        source.write_string("subdir/file.txt", "");
+        if !supported(source.path()) {
+            return;
+        }
+        source.set_xattr("subdir", "user.kind", b"dir").unwrap();
+        source
+            .set_xattr("subdir/file.txt", "user.kind", b"file")
+            .unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir")).preserve_metadata();
+
+        directory.copy_dir_from(&source, "copy");
+
+        assert_eq!(
+            directory.get_xattr("copy/subdir", "user.kind").unwrap(),
+            Some(b"dir".to_vec())
+        );
+        assert_eq!(
+            directory
+                .get_xattr("copy/subdir/file.txt", "user.kind")
+                .unwrap(),
+            Some(b"file".to_vec())
+        );
+    }
+}
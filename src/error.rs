@@ -0,0 +1,216 @@
+use std::path::PathBuf;
+
+/// Errors returned by the fallible operations of this crate.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A file already exists at the given path, but a new file was requested.
+    FileExists(PathBuf),
+    /// A directory already exists at the given path, but a new directory was requested.
+    DirectoryExists(PathBuf),
+    /// The directory is claimed by another live process (see `Directory::claim`).
+    Claimed { path: PathBuf, pid: u32 },
+    /// The given path contains a file name that is reserved or invalid on Windows,
+    /// e.g. `CON`, `aux.txt`, or a name with a trailing dot or space.
+    ReservedName { path: PathBuf, name: String },
+    /// The environment variable with the given name, which should contain the path
+    /// of a directory, is not set or empty (see `Directory::from_env`).
+    EnvVarNotSet(String),
+    /// An absolute path was given where a path relative to a directory is expected.
+    PathIsAbsolute(PathBuf),
+    /// The given path exceeds the platform's length limits at the given component.
+    PathTooLong {
+        path: PathBuf,
+        component: String,
+        max: usize,
+    },
+    /// Writing the file at the given path was refused because its content exceeds
+    /// the maximum file size (see `Directory::with_max_file_size`).
+    FileTooLarge { path: PathBuf, size: u64, max: u64 },
+    /// The entry at the given path is nested deeper than the maximum depth
+    /// of recursive operations (see `Directory::with_max_depth`).
+    DepthExceeded { path: PathBuf, max_depth: usize },
+    /// The given glob pattern of a `Filter` is invalid (see the `glob` feature).
+    InvalidPattern { pattern: String, message: String },
+    /// An I/O operation on the given path failed.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// Serializing data to the given format for the file at the given path failed.
+    Serialization {
+        format: &'static str,
+        path: PathBuf,
+        message: String,
+    },
+    /// The JSON for the file at the given path does not conform to a JSON Schema
+    /// (see `Directory::write_json_validated`). Each violation names the location
+    /// within the JSON document and what is wrong there.
+    SchemaViolation {
+        path: PathBuf,
+        violations: Vec<String>,
+    },
+}
+
+/// Result type for the fallible operations of this crate.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Creates an `Error::Io` for the given path.
+    pub(crate) fn io<P: Into<PathBuf>>(path: P, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FileExists(path) => write!(f, "File already exists at {}", path.display()),
+            Self::DirectoryExists(path) => {
+                write!(f, "Directory already exists at {}", path.display())
+            }
+            Self::Claimed { path, pid } => {
+                write!(f, "Claim at {} is held by process {pid}", path.display())
+            }
+            Self::ReservedName { path, name } => write!(
+                f,
+                "File name {name:?} in {} is reserved or invalid on Windows",
+                path.display()
+            ),
+            Self::EnvVarNotSet(name) => {
+                write!(f, "Environment variable {name} is not set or empty")
+            }
+            Self::PathIsAbsolute(path) => write!(
+                f,
+                "Expected a relative path, but got an absolute path: {}",
+                path.display()
+            ),
+            Self::PathTooLong {
+                path,
+                component,
+                max,
+            } => write!(
+                f,
+                "Path {} exceeds the length limit of {max} at component {component:?}",
+                path.display()
+            ),
+            Self::FileTooLarge { path, size, max } => write!(
+                f,
+                "Refusing to write {size} bytes to {}, which exceeds the maximum file size of {max} bytes",
+                path.display()
+            ),
+            Self::DepthExceeded { path, max_depth } => write!(
+                f,
+                "Entry at {} exceeds the maximum depth of {max_depth}",
+                path.display()
+            ),
+            Self::InvalidPattern { pattern, message } => {
+                write!(f, "Invalid glob pattern {pattern:?}: {message}")
+            }
+            Self::Io { path, source } => {
+                write!(f, "I/O error at {}: {source}", path.display())
+            }
+            Self::Serialization {
+                format,
+                path,
+                message,
+            } => write!(
+                f,
+                "Failed to serialize object to {format} for file at {}: {message}",
+                path.display()
+            ),
+            Self::SchemaViolation { path, violations } => write!(
+                f,
+                "JSON for file at {} does not conform to the schema: {}",
+                path.display(),
+                violations.join("; ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Error::FileExists(PathBuf::from("dir/file.txt")).to_string(),
+            "File already exists at dir/file.txt"
+        );
+        assert_eq!(
+            Error::EnvVarNotSet("OUT_DIR".to_string()).to_string(),
+            "Environment variable OUT_DIR is not set or empty"
+        );
+        assert_eq!(
+            Error::PathIsAbsolute(PathBuf::from("/file.txt")).to_string(),
+            "Expected a relative path, but got an absolute path: /file.txt"
+        );
+        assert_eq!(
+            Error::FileTooLarge {
+                path: PathBuf::from("dir/file.bin"),
+                size: 11,
+                max: 10,
+            }
+            .to_string(),
+            "Refusing to write 11 bytes to dir/file.bin, which exceeds the maximum file size of 10 bytes"
+        );
+        assert_eq!(
+            Error::DepthExceeded {
+                path: PathBuf::from("dir/a/b"),
+                max_depth: 1,
+            }
+            .to_string(),
+            "Entry at dir/a/b exceeds the maximum depth of 1"
+        );
+        assert_eq!(
+            Error::InvalidPattern {
+                pattern: "[a".to_string(),
+                message: "unclosed character class".to_string(),
+            }
+            .to_string(),
+            "Invalid glob pattern \"[a\": unclosed character class"
+        );
+        assert_eq!(
+            Error::Serialization {
+                format: "JSON",
+                path: PathBuf::from("data"),
+                message: "invalid".to_string(),
+            }
+            .to_string(),
+            "Failed to serialize object to JSON for file at data: invalid"
+        );
+        assert_eq!(
+            Error::SchemaViolation {
+                path: PathBuf::from("data.json"),
+                violations: vec!["/a: first".to_string(), "/b: second".to_string()],
+            }
+            .to_string(),
+            "JSON for file at data.json does not conform to the schema: /a: first; /b: second"
+        );
+    }
+
+    #[test]
+    fn source() {
+        let error = Error::io(
+            "file.txt",
+            std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied"),
+        );
+
+        assert!(std::error::Error::source(&error).is_some());
+        assert_eq!(error.to_string(), "I/O error at file.txt: denied");
+    }
+}
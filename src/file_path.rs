@@ -0,0 +1,157 @@
+use std::path::{Path, PathBuf};
+
+/// Represents a single file in the file system, analogous to `Directory`.
+/// Missing parent directories are created when this struct is instantiated,
+/// but the file itself is left to the caller (e.g. a tool under test).
+/// By default, the file is temporary: it is removed when the instance is dropped,
+/// along with the parent directories that were created for it, if they are empty.
+pub struct FilePath {
+    path: PathBuf,
+    created_parents: Vec<PathBuf>,
+    keep_on_drop: bool,
+}
+
+impl FilePath {
+    /// Creates a new temporary FilePath instance and the missing parent directories.
+    /// Panics if a parent directory cannot be created.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the file.
+    ///
+    /// # Example
+    /// ```rust
+    /// use conv_wd::FilePath;
+    ///
+    /// let dir = std::env::temp_dir().join("conv_wd_file_path_example");
+    /// let report = FilePath::create(dir.join("reports/report.txt"));
+    /// std::fs::write(&report, "done").unwrap();
+    /// drop(report);
+    /// assert!(!dir.join("reports").exists());
+    /// ```
+    pub fn create<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let mut created_parents = Vec::new();
+        let mut parent = path.parent();
+        while let Some(dir) = parent.filter(|dir| !dir.as_os_str().is_empty() && !dir.exists()) {
+            created_parents.push(dir.to_path_buf());
+            parent = dir.parent();
+        }
+        if let Some(dir) = created_parents.first() {
+            std::fs::create_dir_all(dir)
+                .unwrap_or_else(|e| panic!("Failed to create directory at {}: {e}", dir.display()));
+        }
+        Self {
+            path,
+            created_parents,
+            keep_on_drop: false,
+        }
+    }
+
+    /// Creates a new persistent FilePath instance from self.
+    /// Neither the file nor its parent directories are removed when the instance is dropped.
+    pub fn keep(mut self) -> Self {
+        self.keep_on_drop = true;
+        self
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns whether the file currently exists.
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+}
+
+impl AsRef<Path> for FilePath {
+    /// Returns the path of the file, so a `FilePath` can be passed
+    /// wherever a path is expected.
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for FilePath {
+    /// Drops the FilePath instance.
+    /// If the file is temporary, it is removed from the file system (if it exists),
+    /// followed by the parent directories that were created for it, as long as they are empty.
+    /// Panics if the file cannot be removed, unless the thread is already panicking.
+    fn drop(&mut self) {
+        if self.keep_on_drop {
+            return;
+        }
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound && !std::thread::panicking() => {
+                panic!("Failed to remove file at {}: {e}", self.path.display())
+            }
+            _ => {}
+        }
+        for dir in &self.created_parents {
+            if std::fs::remove_dir(dir).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn create() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("a/b/file.txt");
+
+        let file = FilePath::create(&file_path);
+
+        assert_eq!(file.path(), file_path);
+        assert!(temp_dir.path().join("a/b").is_dir());
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn drop_removes_file_and_created_parents() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::create_dir(temp_dir.path().join("existing")).unwrap();
+
+        {
+            let file = FilePath::create(temp_dir.path().join("existing/a/b/file.txt"));
+            std::fs::write(&file, "content").unwrap();
+        }
+
+        assert!(temp_dir.path().join("existing").is_dir());
+        assert!(!temp_dir.path().join("existing/a").exists());
+    }
+
+    #[test]
+    fn drop_keeps_non_empty_parents() {
+        let temp_dir = tempdir().unwrap();
+
+        {
+            let file = FilePath::create(temp_dir.path().join("a/file.txt"));
+            std::fs::write(&file, "content").unwrap();
+            std::fs::write(temp_dir.path().join("a/other.txt"), "other").unwrap();
+        }
+
+        assert!(!temp_dir.path().join("a/file.txt").exists());
+        assert!(temp_dir.path().join("a/other.txt").exists());
+    }
+
+    #[test]
+    fn keep() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("a/file.txt");
+
+        {
+            let file = FilePath::create(&file_path).keep();
+            std::fs::write(&file, "content").unwrap();
+        }
+
+        assert!(file_path.exists());
+    }
+}
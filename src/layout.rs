@@ -0,0 +1,106 @@
+use crate::Directory;
+
+/// Derives `DirLayout` for a struct with named fields (with the `derive` feature).
+#[cfg(feature = "derive")]
+pub use conv_wd_derive::DirLayout;
+
+/// A fixed layout of files and subdirectories in a working directory,
+/// with one file or subdirectory per field of the implementing struct.
+///
+/// With the `derive` feature, this trait can be derived for structs with named fields.
+/// Each field needs a `#[layout(...)]` attribute with its format:
+/// - `json` and `toml`: a serde-serializable value, stored with `Directory::write_json`
+///   or `Directory::write_toml` (the extension is added to the file name).
+/// - `text`: a `String`, stored with `Directory::write_string` as `<field>.txt`.
+/// - `bytes`: a `Vec<u8>`, stored with `Directory::write_bytes` as `<field>.bin`.
+/// - `dir`: a nested `DirLayout`, stored in the subdirectory `<field>`.
+///
+/// The file name can be set with `file = "..."`, e.g. `#[layout(text, file = "README")]`.
+/// It must not contain path separators; use a nested layout for subdirectories.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "derive", feature = "json"))] {
+/// use conv_wd::{DirLayout, Directory};
+///
+/// #[derive(DirLayout)]
+/// struct Job {
+///     #[layout(json)]
+///     settings: Vec<u32>,
+///     #[layout(text, file = "README")]
+///     readme: String,
+/// }
+///
+/// let dir = Directory::cargo_target_subdir("dir_layout_example");
+/// Job { settings: vec![1, 2], readme: "Hello".into() }.save_to(&dir);
+/// assert!(dir.path().join("settings.json").exists());
+/// assert_eq!(Job::load_from(&dir).readme, "Hello");
+/// # }
+/// ```
+pub trait DirLayout: Sized {
+    /// Writes all fields to their files and subdirectories in the given directory.
+    /// Panics if a write operation fails.
+    fn save_to(&self, directory: &Directory);
+
+    /// Reads all fields from their files and subdirectories in the given directory.
+    /// Panics if a file cannot be read or deserialized.
+    fn load_from(directory: &Directory) -> Self;
+}
+
+/// Returns a persistent handle to the subdirectory `name` of `directory`,
+/// which is created if needed. Used by the code generated by `#[derive(DirLayout)]`.
+#[doc(hidden)]
+pub fn layout_subdir(directory: &Directory, name: &str) -> Directory {
+    Directory::create(directory.path().join(name)).keep()
+}
+
+#[cfg(all(test, feature = "derive", feature = "json", feature = "toml"))]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[derive(DirLayout, Debug, PartialEq)]
+    struct Outputs {
+        #[layout(bytes, file = "result.dat")]
+        result: Vec<u8>,
+    }
+
+    #[derive(DirLayout, Debug, PartialEq)]
+    struct Job {
+        #[layout(json)]
+        input: Vec<u32>,
+        #[layout(toml, file = "settings")]
+        config: std::collections::BTreeMap<String, String>,
+        #[layout(text)]
+        log: String,
+        #[layout(dir)]
+        outputs: Outputs,
+    }
+
+    #[test]
+    fn derive_dir_layout() {
+        let temp_dir = tempdir().unwrap();
+        let directory = Directory::create(temp_dir.path().join("test_dir"));
+        let job = Job {
+            input: vec![1, 2, 3],
+            config: [("mode".to_string(), "fast".to_string())].into(),
+            log: "done\n".to_string(),
+            outputs: Outputs {
+                result: vec![0, 255],
+            },
+        };
+
+        job.save_to(&directory);
+
+        for file in [
+            "input.json",
+            "settings.toml",
+            "log.txt",
+            "outputs/result.dat",
+        ] {
+            assert!(directory.path().join(file).is_file(), "{file}");
+        }
+        assert_eq!(Job::load_from(&directory), job);
+    }
+}
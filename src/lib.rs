@@ -1,6 +1,32 @@
 #![doc = include_str!("../README.md")]
 
+// Lets the code generated by `#[derive(DirLayout)]` refer to `::conv_wd` in tests.
+#[cfg(test)]
+extern crate self as conv_wd;
+
+mod config;
 mod directory;
-pub use directory::Directory;
+mod error;
+mod file_path;
+mod layout;
+mod process;
+mod workspace;
+pub use config::{Config, config, set_config};
+#[cfg(feature = "glob")]
+pub use directory::Filter;
+pub use directory::{
+    ClaimGuard, CurrentDirGuard, DirDiff, Directory, EnvVarGuard, FileDiff, FileLockGuard,
+    FillGuard, Follow, IoStats, LineChange, Marker, Newline, Promotion, RetryPolicy, Stages,
+    Summary, SweepPolicy, SyncPolicy, SyncReport, Tree, TreeEntry, TreeSpec,
+};
+pub use error::{Error, Result};
+pub use file_path::FilePath;
+pub use layout::DirLayout;
+#[doc(hidden)]
+pub use layout::layout_subdir;
+pub use workspace::Workspace;
 
 pub mod util;
+
+#[cfg(feature = "ctrlc")]
+pub mod signal;
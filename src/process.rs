@@ -0,0 +1,161 @@
+//! Identification of processes, e.g. for ownership of shared directories.
+
+/// Identifies a running process by its PID and start time.
+/// The start time guards against PID reuse: a process with the same PID but a
+/// different start time is a different process. It is only available on Linux
+/// and Windows and is `None` elsewhere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ProcessId {
+    pub(crate) pid: u32,
+    pub(crate) start_time: Option<u64>,
+}
+
+impl ProcessId {
+    /// Returns the identity of the current process.
+    pub(crate) fn current() -> Self {
+        let pid = std::process::id();
+        Self {
+            pid,
+            start_time: start_time(pid),
+        }
+    }
+
+    /// Returns whether the identified process is still running.
+    pub(crate) fn is_alive(&self) -> bool {
+        is_running(self.pid)
+            && match (self.start_time, start_time(self.pid)) {
+                (Some(recorded), Some(actual)) => recorded == actual,
+                _ => true,
+            }
+    }
+}
+
+/// Returns whether a process with the given PID is running.
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: Signal 0 performs only the existence and permission checks.
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    // The process exists but belongs to another user.
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Returns whether a process with the given PID is running.
+#[cfg(windows)]
+fn is_running(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, ERROR_ACCESS_DENIED, GetLastError};
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    const STILL_ACTIVE: u32 = 259;
+
+    // SAFETY: The handle is checked for null and closed before returning.
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return GetLastError() == ERROR_ACCESS_DENIED;
+        }
+        let mut exit_code = 0;
+        let running = GetExitCodeProcess(handle, &mut exit_code) != 0 && exit_code == STILL_ACTIVE;
+        CloseHandle(handle);
+        running
+    }
+}
+
+/// Returns the start time of the process with the given PID in clock ticks since boot.
+#[cfg(target_os = "linux")]
+fn start_time(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // The command name in parentheses may contain spaces, so fields are counted
+    // after its closing parenthesis. The start time is field 22, i.e. the 20th field
+    // after the command name.
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(19)?.parse().ok()
+}
+
+/// Returns the creation time of the process with the given PID as a `FILETIME` value.
+#[cfg(windows)]
+fn start_time(pid: u32) -> Option<u64> {
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows_sys::Win32::System::Threading::{
+        GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    // SAFETY: The handle is checked for null and closed before returning.
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+        let zero = FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        };
+        let (mut creation, mut exit, mut kernel, mut user) = (zero, zero, zero, zero);
+        let success =
+            GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user) != 0;
+        CloseHandle(handle);
+        success
+            .then(|| (u64::from(creation.dwHighDateTime) << 32) | u64::from(creation.dwLowDateTime))
+    }
+}
+
+/// Returns `None`, since process start times are not supported on this platform.
+#[cfg(not(any(target_os = "linux", windows)))]
+fn start_time(_pid: u32) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_process_is_alive() {
+        let current = ProcessId::current();
+
+        assert_eq!(current.pid, std::process::id());
+        assert!(current.is_alive());
+        #[cfg(any(target_os = "linux", windows))]
+        assert!(current.start_time.is_some());
+    }
+
+    #[test]
+    fn exited_process_is_not_alive() {
+        let mut child = std::process::Command::new(std::env::current_exe().unwrap())
+            .arg("--list")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+
+        assert!(
+            !ProcessId {
+                pid,
+                start_time: None
+            }
+            .is_alive()
+        );
+    }
+
+    #[test]
+    fn reused_pid_is_not_alive() {
+        let current = ProcessId::current();
+        let Some(start_time) = current.start_time else {
+            return;
+        };
+
+        let stale = ProcessId {
+            start_time: Some(start_time + 1),
+            ..current
+        };
+
+        assert!(!stale.is_alive());
+    }
+}
@@ -0,0 +1,95 @@
+//! Cleanup of temporary directories on process termination signals.
+//!
+//! `Drop` never runs when a process is killed, e.g. when a test run is aborted
+//! with Ctrl+C, so temporary directories would be left behind.
+//! With the `ctrlc` feature enabled, all live temporary `Directory` instances are
+//! tracked in a global list, and [`install_cleanup_handler`] registers a handler for
+//! SIGINT and SIGTERM (Ctrl+C and Ctrl+Break on Windows) that removes them.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Exit code used after cleaning up on a termination signal (128 + SIGINT).
+const SIGNAL_EXIT_CODE: i32 = 130;
+
+/// Paths of all live temporary directories.
+static VOLATILE_DIRECTORIES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Installs a handler for SIGINT and SIGTERM that removes all live temporary
+/// directories and then exits the process.
+/// Only one handler can be installed per process, so this returns an error if
+/// another handler has already been registered via the `ctrlc` crate.
+/// Applications with their own handler can call [`cleanup_volatile_directories`] instead.
+pub fn install_cleanup_handler() -> Result<(), ctrlc::Error> {
+    ctrlc::set_handler(|| {
+        cleanup_volatile_directories();
+        std::process::exit(SIGNAL_EXIT_CODE);
+    })
+}
+
+/// Removes all live temporary directories from the file system, unless the
+/// `CONV_WD_KEEP` environment variable requests to keep all directories.
+/// Errors are ignored, since this is meant to be called while the process is terminating.
+pub fn cleanup_volatile_directories() {
+    if crate::directory::keep_requested_by_env() {
+        return;
+    }
+    for path in volatile_directories().drain(..) {
+        let _ = std::fs::remove_dir_all(path);
+    }
+}
+
+/// Registers a temporary directory to be removed on termination signals.
+pub(crate) fn register(path: &Path) {
+    volatile_directories().push(path.to_path_buf());
+}
+
+/// Unregisters a directory, e.g. because it was dropped or made persistent.
+pub(crate) fn unregister(path: &Path) {
+    let mut paths = volatile_directories();
+    if let Some(index) = paths.iter().position(|p| p == path) {
+        paths.swap_remove(index);
+    }
+}
+
+/// Locks the list of live temporary directories.
+/// A poisoned lock is recovered, since the list stays consistent even if a holder panicked.
+fn volatile_directories() -> MutexGuard<'static, Vec<PathBuf>> {
+    VOLATILE_DIRECTORIES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Directory;
+    use tempfile::tempdir;
+
+    fn is_registered(path: &Path) -> bool {
+        volatile_directories().iter().any(|p| p == path)
+    }
+
+    #[test]
+    fn register_volatile_directory() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("volatile_dir");
+
+        {
+            let _directory = Directory::create(&dir_path);
+            assert!(is_registered(&dir_path));
+        }
+        assert!(!is_registered(&dir_path));
+    }
+
+    #[test]
+    fn unregister_persistent_directory() {
+        let temp_dir = tempdir().unwrap();
+        let dir_path = temp_dir.path().join("persistent_dir");
+
+        let _directory = Directory::create(&dir_path).keep();
+
+        assert!(!is_registered(&dir_path));
+    }
+}
@@ -1,9 +1,191 @@
+//! Public path helpers used by `Directory`, for validating paths up front in
+//! downstream code with the same rules.
+//!
+//! Lifecycle management is implemented by [`Directory`](crate::Directory) and,
+//! for single files, [`FilePath`](crate::FilePath).
+
+use std::path::{Component, Path};
+
+use crate::{Error, Result};
+
+/// Maximum length of a single file name on common file systems
+/// (in bytes on Unix, in UTF-16 code units on Windows).
+pub const MAX_NAME_LEN: usize = 255;
+
+/// Maximum length of a whole path, excluding the terminating NUL.
+/// On Windows, this is the classic `MAX_PATH` limit that many tools still enforce,
+/// even though the standard library itself supports longer paths.
+pub const MAX_PATH_LEN: usize = if cfg!(windows) {
+    259
+} else if cfg!(target_os = "macos") {
+    1023
+} else {
+    4095
+};
+
+/// Names of devices that are reserved on Windows, regardless of case and extension.
+const RESERVED_NAMES: [&str; 4] = ["CON", "PRN", "AUX", "NUL"];
+
 /// Asserts that the given path is a relative path.
-pub fn assert_relative_path(path: &std::path::Path) {
+/// Panics with the message of `Error::PathIsAbsolute` otherwise
+/// (see `validate_relative_path` for the fallible variant).
+/// This is the check of the `Directory` methods that panic on errors.
+pub fn assert_relative_path(path: &Path) {
+    validate_relative_path(path).unwrap_or_else(|e| panic!("{e}"));
+}
+
+/// Checks that the given path is a relative path.
+/// Returns `Error::PathIsAbsolute` otherwise.
+/// This is the check of the `Directory` methods that return a `Result`.
+pub fn validate_relative_path(path: &Path) -> Result<()> {
     if path.is_absolute() {
-        panic!(
-            "Expected a relative path, but got an absolute path: {}",
-            path.display()
+        return Err(Error::PathIsAbsolute(path.to_path_buf()));
+    }
+    Ok(())
+}
+
+/// Checks that all file names in the given path are valid on Windows.
+/// Returns `Error::ReservedName` for reserved device names such as `CON`, `NUL`,
+/// `COM1`, or `LPT1` (also with an extension, e.g. `aux.txt`), and for names that end
+/// with a dot or a space, which Windows silently strips.
+pub fn check_portable_path(path: &Path) -> Result<()> {
+    for component in path.components() {
+        let Component::Normal(name) = component else {
+            continue;
+        };
+        let name = name.to_string_lossy();
+        if is_reserved_name(&name) {
+            return Err(Error::ReservedName {
+                path: path.to_path_buf(),
+                name: name.into_owned(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the given path does not exceed the platform's length limits,
+/// i.e. `MAX_NAME_LEN` per file name and `MAX_PATH_LEN` in total.
+/// Returns `Error::PathTooLong` naming the first component at which a limit is exceeded.
+/// Verbatim paths (`\\?\` prefix) on Windows are only checked per file name.
+pub fn check_path_length(path: &Path) -> Result<()> {
+    let verbatim = matches!(
+        path.components().next(),
+        Some(Component::Prefix(prefix)) if prefix.kind().is_verbatim()
+    );
+    let mut prefix = std::path::PathBuf::new();
+    for component in path.components() {
+        prefix.push(component);
+        let (len, max) = match component {
+            Component::Normal(name) if os_str_len(name) > MAX_NAME_LEN => {
+                (os_str_len(name), MAX_NAME_LEN)
+            }
+            _ if !verbatim => (os_str_len(prefix.as_os_str()), MAX_PATH_LEN),
+            _ => continue,
+        };
+        if len > max {
+            return Err(Error::PathTooLong {
+                path: path.to_path_buf(),
+                component: component.as_os_str().to_string_lossy().into_owned(),
+                max,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the length of the given string as seen by the platform's file APIs.
+fn os_str_len(s: &std::ffi::OsStr) -> usize {
+    #[cfg(windows)]
+    return std::os::windows::ffi::OsStrExt::encode_wide(s).count();
+    #[cfg(not(windows))]
+    return s.as_encoded_bytes().len();
+}
+
+/// Returns whether the given file name is reserved or invalid on Windows.
+fn is_reserved_name(name: &str) -> bool {
+    if name.ends_with(['.', ' ']) {
+        return true;
+    }
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+    let upper = stem.to_ascii_uppercase();
+    RESERVED_NAMES.contains(&upper.as_str())
+        || (upper.len() == 4
+            && (upper.starts_with("COM") || upper.starts_with("LPT"))
+            && upper.ends_with(|c: char| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_relative_path() {
+        let absolute_path = std::env::temp_dir().join("file.txt");
+
+        assert!(super::validate_relative_path(Path::new("dir/file.txt")).is_ok());
+        assert!(matches!(
+            super::validate_relative_path(&absolute_path),
+            Err(Error::PathIsAbsolute(path)) if path == absolute_path
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected a relative path")]
+    fn assert_relative_path() {
+        super::assert_relative_path(&std::env::temp_dir());
+    }
+
+    #[test]
+    fn check_path_length() {
+        let long_name = "x".repeat(MAX_NAME_LEN + 1);
+        let deep_path = Path::new("dir").join(
+            std::iter::repeat_n("component", MAX_PATH_LEN / 10 + 1)
+                .collect::<Vec<_>>()
+                .join("/"),
         );
+
+        assert!(super::check_path_length(Path::new("dir/file.txt")).is_ok());
+        assert!(matches!(
+            super::check_path_length(&Path::new("dir").join(&long_name).join("file.txt")),
+            Err(Error::PathTooLong { component, max: MAX_NAME_LEN, .. }) if component == long_name
+        ));
+        assert!(matches!(
+            super::check_path_length(&deep_path),
+            Err(Error::PathTooLong { component, max: MAX_PATH_LEN, .. }) if component == "component"
+        ));
+    }
+
+    #[test]
+    fn check_portable_path() {
+        for path in [
+            "file.txt",
+            "dir/console.txt",
+            "nul_file",
+            "COM10",
+            ".gitignore",
+        ] {
+            assert!(
+                super::check_portable_path(Path::new(path)).is_ok(),
+                "{path}"
+            );
+        }
+        for (path, name) in [
+            ("CON", "CON"),
+            ("dir/aux.txt", "aux.txt"),
+            ("nul.tar.gz", "nul.tar.gz"),
+            ("Com1", "Com1"),
+            ("lpt9.log", "lpt9.log"),
+            ("trailing./file", "trailing."),
+            ("dir/trailing ", "trailing "),
+        ] {
+            assert!(
+                matches!(
+                    super::check_portable_path(Path::new(path)),
+                    Err(Error::ReservedName { name: n, .. }) if n == name
+                ),
+                "{path}"
+            );
+        }
     }
 }
@@ -0,0 +1,236 @@
+use std::path::Path;
+
+use crate::Directory;
+
+/// A set of named directories (roles such as `input`, `output`, or `logs`)
+/// below a common root directory.
+/// The root directory is created when this struct is instantiated, and the role
+/// directories are created by `initialize_all`.
+///
+/// Each role has its own drop policy: temporary roles are removed when the workspace
+/// is dropped, persistent roles remain. Declaring a persistent role makes the root
+/// persistent as well, so only the temporary roles are removed from it.
+///
+/// # Example
+/// ```rust
+/// use conv_wd::Workspace;
+///
+/// let root = std::env::temp_dir().join("workspace_example");
+/// let mut ws = Workspace::create(&root)
+///     .with_dir("input")
+///     .with_dir("cache")
+///     .with_persistent_dir("output");
+/// ws.initialize_all();
+/// ws.dir("output").write_string("result.txt", "done");
+/// drop(ws);
+/// assert!(!root.join("cache").exists());
+/// assert!(root.join("output/result.txt").exists());
+/// # std::fs::remove_dir_all(&root).unwrap();
+/// ```
+pub struct Workspace {
+    // Roles are declared before the root, so they are dropped first.
+    roles: Vec<Role>,
+    root: Directory,
+}
+
+/// A named directory of a `Workspace`.
+struct Role {
+    name: String,
+    keep: bool,
+    directory: Option<Directory>,
+}
+
+impl Workspace {
+    /// Creates a new Workspace instance with the given root path and no roles.
+    /// The root directory is created like `Directory::create`, i.e. it is temporary
+    /// unless the process-wide defaults say otherwise.
+    /// Panics if the root directory cannot be created.
+    ///
+    /// # Arguments
+    /// * `root` - The path of the root directory.
+    pub fn create<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            roles: Vec::new(),
+            root: Directory::create(root),
+        }
+    }
+
+    /// Creates a new Workspace instance from self with a persistent root directory.
+    /// The drop policies of the roles are not affected.
+    pub fn keep(mut self) -> Self {
+        self.root = self.root.keep();
+        self
+    }
+
+    /// Creates a new Workspace instance from self with an additional temporary role.
+    /// Panics if `name` is not a relative path or if the role is already declared.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the role, which is also the path below the root.
+    pub fn with_dir(self, name: &str) -> Self {
+        self.with_role(name, false)
+    }
+
+    /// Creates a new Workspace instance from self with an additional persistent role.
+    /// The root directory is made persistent as well.
+    /// Panics if `name` is not a relative path or if the role is already declared.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the role, which is also the path below the root.
+    pub fn with_persistent_dir(self, name: &str) -> Self {
+        self.with_role(name, true).keep()
+    }
+
+    /// Declares a role with the given drop policy.
+    /// Panics if `name` is not a relative path or if the role is already declared.
+    fn with_role(mut self, name: &str, keep: bool) -> Self {
+        crate::util::assert_relative_path(Path::new(name));
+        if self.roles.iter().any(|role| role.name == name) {
+            panic!(
+                "Role {name} is already declared in workspace {}",
+                self.root.path().display()
+            );
+        }
+        self.roles.push(Role {
+            name: name.to_string(),
+            keep,
+            directory: None,
+        });
+        self
+    }
+
+    /// Creates the directories of all declared roles that have not been created yet.
+    /// Panics if a directory cannot be created.
+    pub fn initialize_all(&mut self) {
+        for role in &mut self.roles {
+            if role.directory.is_none() {
+                let directory = Directory::create(self.root.path().join(&role.name));
+                role.directory = Some(if role.keep {
+                    directory.keep()
+                } else {
+                    directory
+                });
+            }
+        }
+    }
+
+    /// Returns the root directory of the workspace.
+    pub fn root(&self) -> &Directory {
+        &self.root
+    }
+
+    /// Returns the directory of the given role.
+    /// Panics if the role is not declared or has not been initialized.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the role.
+    pub fn dir(&self, name: &str) -> &Directory {
+        let role = self
+            .roles
+            .iter()
+            .find(|role| role.name == name)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Role {name} is not declared in workspace {}",
+                    self.root.path().display()
+                )
+            });
+        role.directory.as_ref().unwrap_or_else(|| {
+            panic!(
+                "Role {name} in workspace {} has not been initialized",
+                self.root.path().display()
+            )
+        })
+    }
+
+    /// Returns the names of all declared roles in the order of declaration.
+    pub fn roles(&self) -> impl Iterator<Item = &str> {
+        self.roles.iter().map(|role| role.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn create() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("workspace");
+
+        {
+            let ws = Workspace::create(&root);
+            assert_eq!(ws.root().path(), root);
+            assert!(root.is_dir());
+        }
+
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn initialize_all() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("workspace");
+
+        let mut ws = Workspace::create(&root)
+            .with_dir("input")
+            .with_dir("logs/run");
+        assert!(!root.join("input").exists());
+        ws.initialize_all();
+
+        assert!(root.join("input").is_dir());
+        assert!(root.join("logs/run").is_dir());
+        assert_eq!(ws.roles().collect::<Vec<_>>(), ["input", "logs/run"]);
+    }
+
+    #[test]
+    fn dir() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("workspace");
+
+        let mut ws = Workspace::create(&root).with_dir("output");
+        ws.initialize_all();
+
+        assert_eq!(ws.dir("output").path(), root.join("output"));
+    }
+
+    #[test]
+    #[should_panic(expected = "has not been initialized")]
+    fn dir_uninitialized() {
+        let temp_dir = tempdir().unwrap();
+
+        let ws = Workspace::create(temp_dir.path().join("workspace")).with_dir("output");
+
+        ws.dir("output");
+    }
+
+    #[test]
+    #[should_panic(expected = "is already declared")]
+    fn with_dir_duplicate() {
+        let temp_dir = tempdir().unwrap();
+
+        let _ = Workspace::create(temp_dir.path().join("workspace"))
+            .with_dir("output")
+            .with_persistent_dir("output");
+    }
+
+    #[test]
+    fn with_persistent_dir() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("workspace");
+
+        {
+            let mut ws = Workspace::create(&root)
+                .with_dir("cache")
+                .with_persistent_dir("output");
+            ws.initialize_all();
+            ws.dir("cache").write_string("entry.bin", "");
+            ws.dir("output").write_string("result.txt", "");
+        }
+
+        assert!(!root.join("cache").exists());
+        assert!(root.join("output/result.txt").exists());
+    }
+}
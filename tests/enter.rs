@@ -0,0 +1,22 @@
+//! Tests of `Directory::enter`, which changes the current directory of the process.
+//! They live in their own test binary with a single test, so no other test observes
+//! the changed directory, as its documentation requires.
+
+use conv_wd::Directory;
+use tempfile::tempdir;
+
+#[test]
+fn enter() {
+    let temp_dir = tempdir().unwrap();
+    let directory = Directory::create(temp_dir.path().join("cwd_dir"));
+    let previous = std::env::current_dir().unwrap();
+
+    let guard = directory.enter();
+    assert_eq!(
+        std::env::current_dir().unwrap().canonicalize().unwrap(),
+        directory.path().canonicalize().unwrap()
+    );
+    drop(guard);
+
+    assert_eq!(std::env::current_dir().unwrap(), previous);
+}
@@ -0,0 +1,27 @@
+//! Tests of `Directory::export_env`, which modifies the environment of the process.
+//! They live in their own test binary with a single test, so no other thread reads
+//! or writes the environment concurrently, as required by its safety contract.
+
+use conv_wd::Directory;
+use tempfile::tempdir;
+
+#[test]
+fn export_env() {
+    let temp_dir = tempdir().unwrap();
+    let outer = Directory::create(temp_dir.path().join("outer_dir"));
+    let inner = Directory::create(temp_dir.path().join("inner_dir"));
+    let name = "CONV_WD_TEST_EXPORT_ENV";
+
+    // SAFETY: This is the only test in this binary, so no other thread accesses
+    // the environment.
+    let outer_guard = unsafe { outer.export_env(name) };
+    assert_eq!(std::env::var_os(name).unwrap(), outer.path());
+    {
+        // SAFETY: See above.
+        let _inner_guard = unsafe { inner.export_env(name) };
+        assert_eq!(std::env::var_os(name).unwrap(), inner.path());
+    }
+    assert_eq!(std::env::var_os(name).unwrap(), outer.path());
+    drop(outer_guard);
+    assert!(std::env::var_os(name).is_none());
+}